@@ -3,11 +3,12 @@
 use rand::Rng;
 
 use ai_school_core::types::{
-    AgentConfig, AgentId, AgentState, AgentActivity, CareerAspiration, CareerCategory,
-    EmotionalState, AbilityMetrics, LocationId, PersonalityParams, SimulationTime,
+    AgentConfig, AgentId, AgentRole, AgentState, AgentActivity, CareerAspiration, CareerCategory,
+    EmotionalState, AbilityMetrics, LocationId, PersonalityModelKind, PersonalityParams,
+    SimulationTime,
 };
 use crate::career::default_subject_preferences;
-use crate::personality::generate_diverse_personalities;
+use crate::personality::{generate_diverse_big_five, generate_diverse_personalities};
 
 /// Agent 构建器
 pub struct AgentBuilder {
@@ -16,6 +17,8 @@ pub struct AgentBuilder {
     career: Option<CareerAspiration>,
     background: Option<String>,
     age: u8,
+    role: AgentRole,
+    location: Option<LocationId>,
 }
 
 impl AgentBuilder {
@@ -26,6 +29,8 @@ impl AgentBuilder {
             career: None,
             background: None,
             age: 16,
+            role: AgentRole::Student,
+            location: None,
         }
     }
 
@@ -54,6 +59,17 @@ impl AgentBuilder {
         self
     }
 
+    pub fn role(mut self, role: AgentRole) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// 初始位置（缺省时由世界放到场景的初始位置）
+    pub fn location(mut self, location: LocationId) -> Self {
+        self.location = Some(location);
+        self
+    }
+
     /// 构建 AgentState
     pub fn build(self, start_time: &SimulationTime) -> AgentState {
         let personality = self.personality.unwrap_or_else(|| {
@@ -71,6 +87,7 @@ impl AgentBuilder {
             category: CareerCategory::Other("未定".to_string()),
             subject_preferences: Vec::new(),
             clarity: 0.3,
+            history: Vec::new(),
         });
 
         let config = AgentConfig {
@@ -79,11 +96,12 @@ impl AgentBuilder {
             career_aspiration: career,
             background: self.background,
             age: self.age,
+            role: self.role,
         };
 
         AgentState {
             id: AgentId::new(),
-            location: LocationId("dormitory".to_string()),
+            location: self.location.unwrap_or_else(|| LocationId(String::new())),
             activity: AgentActivity::Resting,
             emotion: EmotionalState::default(),
             abilities: AbilityMetrics::default(),
@@ -101,8 +119,17 @@ impl Default for AgentBuilder {
     }
 }
 
-/// 批量生成随机 Agent 群体
+/// 批量生成随机 Agent 群体（MBTI 人格）
 pub fn generate_random_agents(count: usize, start_time: &SimulationTime) -> Vec<AgentState> {
+    generate_random_agents_with_model(count, start_time, PersonalityModelKind::Mbti)
+}
+
+/// 批量生成随机 Agent 群体，使用指定的人格模型
+pub fn generate_random_agents_with_model(
+    count: usize,
+    start_time: &SimulationTime,
+    model: PersonalityModelKind,
+) -> Vec<AgentState> {
     let names = [
         "小明", "小红", "小华", "小丽", "小强",
         "小芳", "小刚", "小美", "小龙", "小雪",
@@ -121,7 +148,10 @@ pub fn generate_random_agents(count: usize, start_time: &SimulationTime) -> Vec<
         ("数据科学家", CareerCategory::Science),
     ];
 
-    let personalities = generate_diverse_personalities(count);
+    let personalities = match model {
+        PersonalityModelKind::Mbti => generate_diverse_personalities(count),
+        PersonalityModelKind::BigFive => generate_diverse_big_five(count),
+    };
     let mut rng = rand::thread_rng();
 
     personalities
@@ -139,8 +169,10 @@ pub fn generate_random_agents(count: usize, start_time: &SimulationTime) -> Vec<
                     category: career_cat.clone(),
                     subject_preferences: Vec::new(),
                     clarity: 0.5,
+                    history: Vec::new(),
                 }),
                 clarity: rng.gen_range(0.3..0.8),
+                history: Vec::new(),
             };
 
             AgentBuilder::new()
@@ -180,4 +212,13 @@ mod tests {
         let ids: std::collections::HashSet<_> = agents.iter().map(|a| a.id.0).collect();
         assert_eq!(ids.len(), 5);
     }
+
+    #[test]
+    fn test_generate_big_five_agents() {
+        let time = SimulationTime::new();
+        let agents = generate_random_agents_with_model(4, &time, PersonalityModelKind::BigFive);
+        assert!(agents
+            .iter()
+            .all(|a| a.config.personality.model_kind() == PersonalityModelKind::BigFive));
+    }
 }
@@ -1,9 +1,11 @@
 //! M1.2 职业志向模型
 //!
-//! 职业与学科偏好的映射，职业志向对行为的影响。
+//! 职业与学科偏好的映射，职业志向对行为的影响，职业志向随经历演化。
 
+use crate::personality::model_for;
 use ai_school_core::types::{
-    CareerAspiration, CareerCategory, CareerMatch, PersonalityParams, SubjectPreference,
+    CareerAspiration, CareerCategory, CareerChange, CareerChangeKind, CareerMatch, EventId,
+    PersonalityParams, SimulationTime, SubjectPreference,
 };
 
 /// 预定义职业列表及其与 MBTI 的关联
@@ -41,6 +43,16 @@ impl CareerDatabase {
         matches
     }
 
+    /// 计算人格与指定职业的匹配度，未收录的职业返回 None
+    pub fn match_score(personality: &PersonalityParams, career: &str) -> Option<f32> {
+        Self::career_personality_mappings()
+            .into_iter()
+            .find(|(name, ..)| *name == career)
+            .map(|(_, _, e_i, s_n, t_f, j_p)| {
+                Self::calculate_match_score(personality, e_i, s_n, t_f, j_p)
+            })
+    }
+
     /// 生成职业志向的描述文本（用于 Prompt）
     pub fn aspiration_description(aspiration: &CareerAspiration) -> String {
         let clarity_desc = if aspiration.clarity > 0.7 {
@@ -86,6 +98,7 @@ impl CareerDatabase {
         ]
     }
 
+    /// 按人格模型计算与职业理想人格画像的匹配度
     fn calculate_match_score(
         personality: &PersonalityParams,
         ideal_e_i: f32,
@@ -93,14 +106,8 @@ impl CareerDatabase {
         ideal_t_f: f32,
         ideal_j_p: f32,
     ) -> f32 {
-        let diff_e_i = (personality.e_i - ideal_e_i).abs();
-        let diff_s_n = (personality.s_n - ideal_s_n).abs();
-        let diff_t_f = (personality.t_f - ideal_t_f).abs();
-        let diff_j_p = (personality.j_p - ideal_j_p).abs();
-
-        // 距离越小，匹配越高；最大距离为 2.0（从 -1.0 到 1.0）
-        let avg_diff = (diff_e_i + diff_s_n + diff_t_f + diff_j_p) / 4.0;
-        (1.0 - avg_diff / 2.0).max(0.0)
+        let ideal = PersonalityParams::new(ideal_e_i, ideal_s_n, ideal_t_f, ideal_j_p);
+        model_for(personality.model_kind()).match_score(personality, &ideal)
     }
 
     fn generate_reasons(personality: &PersonalityParams, career: &str) -> Vec<String> {
@@ -135,6 +142,155 @@ impl CareerDatabase {
     }
 }
 
+/// 影响职业志向的经历
+#[derive(Debug, Clone)]
+pub enum CareerExperience {
+    /// 参加社团/课外活动
+    ClubActivity { name: String },
+    /// 学科表现
+    SubjectPerformance { subject: String, performance: f32 },
+    /// 与心理辅导员对话
+    CounselorChat,
+}
+
+/// 职业志向演化控制器
+#[derive(Debug, Clone)]
+pub struct CareerEvolution {
+    /// 社团活动带来的清晰度增益
+    pub club_gain: f32,
+    /// 偏好学科取得好成绩带来的清晰度增益
+    pub grade_gain: f32,
+    /// 辅导员对话带来的清晰度增益
+    pub counselor_gain: f32,
+    /// 视为"好成绩"的表现阈值
+    pub good_grade_threshold: f32,
+    /// 新职业匹配度需超出当前职业的幅度才会转变
+    pub switch_margin: f32,
+    /// 清晰度每跨过一档（如 0.1）才记入历史
+    pub clarity_step: f32,
+    /// 历史最多保留的条数（超出时丢弃最早的）
+    pub history_limit: usize,
+}
+
+impl Default for CareerEvolution {
+    fn default() -> Self {
+        Self {
+            club_gain: 0.03,
+            grade_gain: 0.02,
+            counselor_gain: 0.05,
+            good_grade_threshold: 0.7,
+            switch_margin: 0.15,
+            clarity_step: 0.1,
+            history_limit: 50,
+        }
+    }
+}
+
+impl CareerEvolution {
+    /// 记录一次经历，相关经历提升职业清晰度（越接近 1.0 增长越慢）
+    ///
+    /// 每次变化都会返回，但只有跨过 `clarity_step` 档位时才记入历史。
+    pub fn record_experience(
+        &self,
+        aspiration: &mut CareerAspiration,
+        experience: &CareerExperience,
+        timestamp: &SimulationTime,
+        trigger_event_id: Option<EventId>,
+    ) -> Option<CareerChange> {
+        let (gain, reason) = match experience {
+            CareerExperience::ClubActivity { name } => {
+                (self.club_gain, format!("参加活动: {name}"))
+            }
+            CareerExperience::SubjectPerformance {
+                subject,
+                performance,
+            } => {
+                let preference = aspiration
+                    .subject_preferences
+                    .iter()
+                    .find(|p| p.subject == *subject)
+                    .map(|p| p.preference)
+                    .unwrap_or(0.0);
+                if preference <= 0.5 || *performance < self.good_grade_threshold {
+                    return None;
+                }
+                (
+                    self.grade_gain * preference,
+                    format!("偏好学科{subject}表现优秀 ({:.0}%)", performance * 100.0),
+                )
+            }
+            CareerExperience::CounselorChat => {
+                (self.counselor_gain, "与心理辅导员交流职业规划".to_string())
+            }
+        };
+
+        let old = aspiration.clarity;
+        let new = (old + gain * (1.0 - old)).clamp(0.0, 1.0);
+        if (new - old).abs() < f32::EPSILON {
+            return None;
+        }
+        aspiration.clarity = new;
+
+        let change = CareerChange {
+            timestamp: timestamp.clone(),
+            trigger_event_id,
+            kind: CareerChangeKind::Clarity { old, new },
+            reason,
+        };
+        if (old / self.clarity_step).floor() != (new / self.clarity_step).floor() {
+            self.push_history(aspiration, change.clone());
+        }
+        Some(change)
+    }
+
+    /// 人格漂移后重新评估理想职业
+    ///
+    /// 当推荐职业的匹配度超出当前职业 `switch_margin` 以上时转变志向，
+    /// 清晰度减半、学科偏好按新职业类别重置。未收录的职业不参与评估。
+    pub fn reevaluate(
+        &self,
+        aspiration: &mut CareerAspiration,
+        personality: &PersonalityParams,
+        timestamp: &SimulationTime,
+        trigger_event_id: Option<EventId>,
+    ) -> Option<CareerChange> {
+        let current = CareerDatabase::match_score(personality, &aspiration.ideal_career)?;
+        let best = CareerDatabase::suggest_careers(personality).into_iter().next()?;
+
+        if best.career == aspiration.ideal_career || best.score - current < self.switch_margin {
+            return None;
+        }
+
+        let from = std::mem::replace(&mut aspiration.ideal_career, best.career.clone());
+        aspiration.category = best.category;
+        aspiration.subject_preferences = default_subject_preferences(aspiration);
+        aspiration.clarity *= 0.5;
+
+        let change = CareerChange {
+            timestamp: timestamp.clone(),
+            trigger_event_id,
+            kind: CareerChangeKind::Switch {
+                from: from.clone(),
+                to: best.career.clone(),
+            },
+            reason: format!(
+                "人格变化后与{}的匹配度 ({:.0}%) 明显高于{from} ({:.0}%)",
+                best.career,
+                best.score * 100.0,
+                current * 100.0
+            ),
+        };
+        self.push_history(aspiration, change.clone());
+        Some(change)
+    }
+
+    fn push_history(&self, aspiration: &mut CareerAspiration, change: CareerChange) {
+        aspiration.history.push(change);
+        let excess = aspiration.history.len().saturating_sub(self.history_limit);
+        aspiration.history.drain(..excess);
+    }
+}
+
 /// 为 Agent 生成默认的学科偏好
 pub fn default_subject_preferences(career: &CareerAspiration) -> Vec<SubjectPreference> {
     match &career.category {
@@ -160,3 +316,82 @@ pub fn default_subject_preferences(career: &CareerAspiration) -> Vec<SubjectPref
         ],
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aspiration(career: &str, category: CareerCategory) -> CareerAspiration {
+        let mut aspiration = CareerAspiration {
+            ideal_career: career.to_string(),
+            category,
+            subject_preferences: Vec::new(),
+            clarity: 0.3,
+            history: Vec::new(),
+        };
+        aspiration.subject_preferences = default_subject_preferences(&aspiration);
+        aspiration
+    }
+
+    #[test]
+    fn test_good_grade_in_preferred_subject_raises_clarity() {
+        let evolution = CareerEvolution::default();
+        let mut a = aspiration("软件工程师", CareerCategory::Technology);
+        let time = SimulationTime::new();
+
+        let weak = CareerExperience::SubjectPerformance {
+            subject: "数学".to_string(),
+            performance: 0.5,
+        };
+        assert!(evolution.record_experience(&mut a, &weak, &time, None).is_none());
+
+        let strong = CareerExperience::SubjectPerformance {
+            subject: "数学".to_string(),
+            performance: 0.9,
+        };
+        assert!(evolution.record_experience(&mut a, &strong, &time, None).is_some());
+        assert!(a.clarity > 0.3);
+    }
+
+    #[test]
+    fn test_history_records_only_clarity_steps() {
+        let evolution = CareerEvolution::default();
+        let mut a = aspiration("软件工程师", CareerCategory::Technology);
+        let time = SimulationTime::new();
+
+        for _ in 0..200 {
+            evolution.record_experience(&mut a, &CareerExperience::CounselorChat, &time, None);
+        }
+        // 0.3 → 接近 1.0 至多跨过 7 档
+        assert!(a.clarity > 0.9);
+        assert!((1..=7).contains(&a.history.len()));
+
+        let capped = CareerEvolution {
+            clarity_step: 0.001,
+            history_limit: 5,
+            ..Default::default()
+        };
+        let mut b = aspiration("软件工程师", CareerCategory::Technology);
+        for _ in 0..50 {
+            capped.record_experience(&mut b, &CareerExperience::CounselorChat, &time, None);
+        }
+        assert_eq!(b.history.len(), 5);
+    }
+
+    #[test]
+    fn test_reevaluate_switches_after_personality_drift() {
+        let evolution = CareerEvolution::default();
+        // 艺术家 ideal: I, N, F, P — 人格已漂移到 ESTJ
+        let mut a = aspiration("艺术家", CareerCategory::Arts);
+        let personality = PersonalityParams::new(-0.3, -0.2, -0.7, -0.8);
+        let time = SimulationTime::new();
+
+        let change = evolution.reevaluate(&mut a, &personality, &time, None);
+        assert!(matches!(
+            change.map(|c| c.kind),
+            Some(CareerChangeKind::Switch { .. })
+        ));
+        assert_ne!(a.ideal_career, "艺术家");
+        assert!(a.clarity < 0.3);
+    }
+}
@@ -3,7 +3,9 @@
 //! 感知-思考-行动循环。**不直接调用 LLM**，只组装决策输入。
 
 use ai_school_core::traits::llm::{ChatMessage, CompletionRequest, MessageRole};
-use ai_school_core::types::{AgentState, BehaviorIntent, IntentType, SituationContext};
+use ai_school_core::types::{
+    AgentRole, AgentState, BehaviorIntent, IntentType, SituationContext,
+};
 
 use crate::career::CareerDatabase;
 use crate::personality::personality_description;
@@ -15,7 +17,7 @@ pub struct CognitionProcessor;
 
 impl CognitionProcessor {
     /// 感知阶段：从情境中提取与人格相关的信息
-    pub fn perceive(agent: &AgentState, context: &SituationContext) -> String {
+    pub fn perceive(context: &SituationContext) -> String {
         let mut perception = String::new();
 
         perception.push_str(&format!(
@@ -25,14 +27,28 @@ impl CognitionProcessor {
 
         perception.push_str(&format!(
             "你在: {}\n",
-            agent.location.0
+            context.perception.location_name
         ));
 
+        if context.perception.crowding >= 1.0 {
+            perception.push_str("这里已经挤满了人\n");
+        } else if context.perception.crowding > 0.8 {
+            perception.push_str("这里人很多，有些拥挤\n");
+        }
+
         if !context.perception.nearby_agents.is_empty() {
-            perception.push_str(&format!(
-                "附近有 {} 个同学\n",
-                context.perception.nearby_agents.len()
-            ));
+            perception.push_str("附近的人:\n");
+            for other in &context.perception.nearby_agents {
+                let relation = other
+                    .relationship
+                    .as_deref()
+                    .map(|r| format!("（{r}）"))
+                    .unwrap_or_default();
+                perception.push_str(&format!(
+                    "  - {}{relation}: {}，{}\n",
+                    other.name, other.activity, other.mood
+                ));
+            }
         }
 
         if !context.perception.observable_activities.is_empty() {
@@ -51,6 +67,13 @@ impl CognitionProcessor {
             }
         }
 
+        if !context.perception.local_events.is_empty() {
+            perception.push_str("这里刚发生过:\n");
+            for event in &context.perception.local_events {
+                perception.push_str(&format!("  - {event}\n"));
+            }
+        }
+
         perception
     }
 
@@ -60,9 +83,12 @@ impl CognitionProcessor {
     pub fn think(agent: &AgentState, context: &SituationContext) -> CompletionRequest {
         let personality_desc = personality_description(&agent.config.personality);
         let career_desc = CareerDatabase::aspiration_description(&agent.config.career_aspiration);
-        let perception = Self::perceive(agent, context);
+        let perception = Self::perceive(context);
 
-        let system_prompt = format!(
+        let system_prompt = if agent.config.role.is_staff() {
+            Self::staff_system_prompt(agent, &personality_desc, &context.emotional_summary)
+        } else {
+            format!(
             r#"你是"{}"，一个正在上学的学生。
 
 ## 你的人格特征
@@ -80,10 +106,11 @@ impl CognitionProcessor {
 3. 用第一人称回应，描述你想做什么以及为什么
 4. 回应应该简洁（50-100字），只描述你下一步的行动意图
 5. 考虑你的记忆和当前情境做出自然的决策"#,
-            agent.config.name,
-            emotional_summary = context.emotional_summary,
-            name = agent.config.name,
-        );
+                agent.config.name,
+                emotional_summary = context.emotional_summary,
+                name = agent.config.name,
+            )
+        };
 
         let mut user_message = format!("## 当前情境\n{perception}\n");
 
@@ -95,6 +122,9 @@ impl CognitionProcessor {
         }
 
         user_message.push_str("\n请描述你接下来想做什么？");
+        if agent.config.role.is_staff() {
+            user_message.push_str("如果要表扬、批评某位学生或找其谈心，请写出学生的名字。");
+        }
 
         CompletionRequest {
             system: system_prompt,
@@ -107,23 +137,82 @@ impl CognitionProcessor {
         }
     }
 
+    /// 教职工的系统提示：人物设定、教学风格与职责
+    fn staff_system_prompt(agent: &AgentState, personality_desc: &str, emotional_summary: &str) -> String {
+        let title = agent.config.role.title();
+        let background = agent
+            .config
+            .background
+            .as_deref()
+            .unwrap_or("一位在这所学校工作多年的教职工");
+        let style = match &agent.config.role {
+            AgentRole::Teacher { style, .. } => format!("\n## 你的教学风格\n{}\n", style.description()),
+            _ => String::new(),
+        };
+
+        format!(
+            r#"你是"{name}"，学校的{title}。
+
+## 你的人物设定
+{background}
+{style}
+## 你的人格特征
+{personality_desc}
+
+## 你的当前情绪
+{emotional_summary}
+
+## 你的职责
+1. 留意身边学生的状态，压力大、情绪低落或成绩下滑的学生要及时关心
+2. 你可以表扬进步的学生、批评懈怠的学生，或找学生谈心
+3. 你的做法要符合你的身份与风格
+
+## 角色扮演规则
+1. 你必须始终以"{name}"的身份思考和行动
+2. 用第一人称回应，描述你想做什么以及为什么
+3. 回应应该简洁（50-100字），只描述你下一步的行动意图"#,
+            name = agent.config.name,
+        )
+    }
+
     /// 行动阶段：解析 LLM 输出为 BehaviorIntent
-    pub fn act(agent: &AgentState, llm_response: &str) -> BehaviorIntent {
-        let intent_type = Self::classify_intent(llm_response);
+    ///
+    /// 回应中提到的附近的人作为交互对象。
+    pub fn act(agent: &AgentState, context: &SituationContext, llm_response: &str) -> BehaviorIntent {
+        let intent_type = Self::classify_intent(llm_response, agent.config.role.is_staff());
+        let target_agents = context
+            .perception
+            .nearby_agents
+            .iter()
+            .filter(|other| llm_response.contains(&other.name))
+            .map(|other| other.id.clone())
+            .collect();
 
         BehaviorIntent {
             agent_id: agent.id.clone(),
             description: llm_response.to_string(),
             target_location: None,
-            target_agents: Vec::new(),
+            target_agents,
             intent_type,
         }
     }
 
-    /// 简单的意图分类（基于关键词）
-    fn classify_intent(response: &str) -> IntentType {
+    /// 简单的意图分类（基于关键词）；教职工优先识别表扬/批评/谈心
+    fn classify_intent(response: &str, is_staff: bool) -> IntentType {
         let lower = response.to_lowercase();
 
+        if is_staff {
+            if lower.contains("表扬") || lower.contains("夸") || lower.contains("称赞") {
+                return IntentType::Praise;
+            }
+            if lower.contains("批评") || lower.contains("提醒") || lower.contains("训") {
+                return IntentType::Criticize;
+            }
+            if lower.contains("谈心") || lower.contains("关心") || lower.contains("辅导") {
+                return IntentType::Counsel;
+            }
+        }
+
         if lower.contains("图书馆") || lower.contains("学习") || lower.contains("作业") || lower.contains("看书") {
             IntentType::Study
         } else if lower.contains("聊天") || lower.contains("说话") || lower.contains("一起") || lower.contains("交流") {
@@ -153,16 +242,60 @@ mod tests {
     #[test]
     fn test_classify_intent() {
         assert_eq!(
-            CognitionProcessor::classify_intent("我想去图书馆学习"),
+            CognitionProcessor::classify_intent("我想去图书馆学习", false),
             IntentType::Study
         );
         assert_eq!(
-            CognitionProcessor::classify_intent("我想休息一下放松放松"),
+            CognitionProcessor::classify_intent("我想休息一下放松放松", false),
             IntentType::Rest
         );
         assert_eq!(
-            CognitionProcessor::classify_intent("我想和小红聊天"),
+            CognitionProcessor::classify_intent("我想和小红聊天", false),
             IntentType::Talk
         );
+        assert_eq!(
+            CognitionProcessor::classify_intent("我想找小红谈心，她最近压力很大", true),
+            IntentType::Counsel
+        );
+        assert_eq!(
+            CognitionProcessor::classify_intent("小明这次进步很大，我要表扬他", true),
+            IntentType::Praise
+        );
+    }
+
+    #[test]
+    fn test_perceive_names_nearby_agents() {
+        use ai_school_core::types::{AgentId, ObservedAgent, Perception, SimulationTime};
+
+        let context = SituationContext {
+            agent_id: AgentId::new(),
+            time: SimulationTime::new(),
+            perception: Perception {
+                location_name: "数学教室".to_string(),
+                crowding: 0.9,
+                nearby_agents: vec![ObservedAgent {
+                    id: AgentId::new(),
+                    name: "小红".to_string(),
+                    activity: "在学习数学".to_string(),
+                    mood: "心情很好".to_string(),
+                    relationship: Some("好朋友".to_string()),
+                }],
+                observable_activities: vec!["小红在学习数学".to_string()],
+                environment_description: String::new(),
+                recent_events: vec![],
+                local_events: vec!["小明在这里打翻了水杯".to_string()],
+                event_ids: vec![],
+            },
+            relevant_memories: vec![],
+            emotional_summary: String::new(),
+            personality_description: String::new(),
+            career_summary: String::new(),
+        };
+
+        let text = CognitionProcessor::perceive(&context);
+        assert!(text.contains("你在: 数学教室"));
+        assert!(text.contains("有些拥挤"));
+        assert!(text.contains("小红（好朋友）: 在学习数学，心情很好"));
+        assert!(text.contains("小明在这里打翻了水杯"));
     }
 }
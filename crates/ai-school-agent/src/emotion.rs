@@ -0,0 +1,339 @@
+//! M1.4 情绪动力学模型
+//!
+//! 每个仿真步运行：情绪向人格决定的基线回归（指数衰减），
+//! 事件按类型与强度评估（appraisal），反应幅度由心理韧性调节。
+//! 纯规则计算，不调用 LLM。
+
+pub use ai_school_core::config::EmotionParams;
+use ai_school_core::types::{AbilityMetrics, EmotionalState, EventType, PersonalityParams};
+
+/// 事件评估结果 — 对三个情绪维度的冲击量
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Appraisal {
+    pub valence: f32,
+    pub arousal: f32,
+    pub stress: f32,
+}
+
+impl Appraisal {
+    /// 按事件类型评估的单位冲击（强度 1.0 时）
+    pub fn for_event_type(event_type: &EventType) -> Self {
+        let (valence, arousal, stress) = match event_type {
+            EventType::Routine => (0.0, -0.05, 0.0),
+            EventType::SocialInteraction => (0.15, 0.1, -0.05),
+            EventType::Academic => (0.0, 0.15, 0.15),
+            EventType::Conflict => (-0.3, 0.3, 0.25),
+            EventType::Cooperation => (0.2, 0.1, -0.1),
+            EventType::SpecialEvent => (0.1, 0.3, 0.05),
+            EventType::System => (0.0, 0.0, 0.0),
+            EventType::Intervention => (0.0, 0.2, 0.1),
+        };
+        Self {
+            valence,
+            arousal,
+            stress,
+        }
+    }
+
+    /// 学业反馈评估：满意度 (-1.0 ~ 1.0) → 情绪冲击
+    pub fn academic(satisfaction: f32) -> Self {
+        Self {
+            valence: 0.15 * satisfaction,
+            arousal: 0.05,
+            stress: -0.1 * satisfaction,
+        }
+    }
+
+    /// 拥挤评估：拥挤度（人数/容量）超过 0.8 后压力上升，内向者更敏感
+    pub fn crowding(crowding: f32, personality: &PersonalityParams) -> Self {
+        let excess = (crowding - 0.8).max(0.0);
+        let sensitivity = 1.0 + 0.5 * personality.e_i;
+        Self {
+            valence: -0.05 * excess * sensitivity,
+            arousal: 0.1 * excess,
+            stress: 0.2 * excess * sensitivity,
+        }
+    }
+
+    /// 被老师表扬
+    pub fn praise() -> Self {
+        Self {
+            valence: 0.2,
+            arousal: 0.1,
+            stress: -0.05,
+        }
+    }
+
+    /// 被老师批评：情感型（t_f > 0）更受触动
+    pub fn criticism(personality: &PersonalityParams) -> Self {
+        let sensitivity = 1.0 + 0.4 * personality.t_f;
+        Self {
+            valence: -0.15 * sensitivity,
+            arousal: 0.15,
+            stress: 0.15 * sensitivity,
+        }
+    }
+
+    /// 考试排名公布：百分位越低越沮丧、压力越大，名次下滑额外增加压力
+    ///
+    /// `percentile` 第一名为 1.0，`previous` 为上次考试的百分位。
+    pub fn exam_result(percentile: f32, previous: Option<f32>) -> Self {
+        let change = previous.map(|p| percentile - p).unwrap_or(0.0);
+        Self {
+            valence: 0.4 * (percentile - 0.5) + 0.3 * change,
+            arousal: 0.2,
+            stress: 0.3 * (0.5 - percentile).max(0.0) + 0.3 * (-change).max(0.0) - 0.05,
+        }
+    }
+
+    /// 与老师/辅导员谈心
+    pub fn counseling() -> Self {
+        Self {
+            valence: 0.1,
+            arousal: -0.05,
+            stress: -0.2,
+        }
+    }
+
+    /// 竞争压力：`excess` 为压力高出默认水平的部分 (-0.5 ~ 0.5)，
+    /// 为正时加重学业压力、为负时减轻，`standing` (0.0 ~ 1.0) 越低越明显
+    pub fn competition(excess: f32, standing: f32) -> Self {
+        Self {
+            valence: 0.0,
+            arousal: 0.1 * excess,
+            stress: excess * (0.6 - 0.4 * standing.clamp(0.0, 1.0)),
+        }
+    }
+
+    /// 按强度缩放
+    pub fn scaled(self, factor: f32) -> Self {
+        Self {
+            valence: self.valence * factor,
+            arousal: self.arousal * factor,
+            stress: self.stress * factor,
+        }
+    }
+}
+
+/// 情绪动力学模型
+#[derive(Debug, Clone, Default)]
+pub struct EmotionModel {
+    pub params: EmotionParams,
+}
+
+impl EmotionModel {
+    pub fn new(params: EmotionParams) -> Self {
+        Self { params }
+    }
+
+    /// 人格决定的情绪基线
+    ///
+    /// 外向者基线效价与唤醒度更高；情感型与高神经质基线压力略高。
+    pub fn baseline(&self, personality: &PersonalityParams) -> EmotionalState {
+        let neutral = &self.params.neutral_baseline;
+        let extraversion = -personality.e_i;
+        let w = self.params.extraversion_weight;
+        let neuroticism = Self::neuroticism_offset(personality);
+
+        EmotionalState {
+            valence: (neutral.valence + w * extraversion - 0.1 * neuroticism).clamp(-1.0, 1.0),
+            arousal: (neutral.arousal + w * 0.5 * extraversion).clamp(0.0, 1.0),
+            stress: (neutral.stress + 0.05 * personality.t_f + 0.2 * neuroticism).clamp(0.0, 1.0),
+        }
+    }
+
+    /// 神经质相对中性值的偏移 (-0.5 ~ 0.5)；MBTI 人格恒为 0
+    fn neuroticism_offset(personality: &PersonalityParams) -> f32 {
+        personality
+            .big_five
+            .as_ref()
+            .map(|b| b.neuroticism - 0.5)
+            .unwrap_or(0.0)
+    }
+
+    /// 向基线回归：x ← x + (baseline − x) × (1 − e^(−rate × hours))
+    ///
+    /// 心理韧性越高，压力恢复越快。
+    pub fn decay(
+        &self,
+        emotion: &mut EmotionalState,
+        personality: &PersonalityParams,
+        abilities: &AbilityMetrics,
+        hours: u32,
+    ) {
+        let baseline = self.baseline(personality);
+        let hours = hours as f32;
+        let pull = |rate: f32| 1.0 - (-rate * hours).exp();
+
+        let stress_rate = self.params.stress_decay * (0.5 + abilities.resilience);
+
+        emotion.valence += (baseline.valence - emotion.valence) * pull(self.params.valence_decay);
+        emotion.arousal += (baseline.arousal - emotion.arousal) * pull(self.params.arousal_decay);
+        emotion.stress += (baseline.stress - emotion.stress) * pull(stress_rate);
+    }
+
+    /// 事件评估：按事件类型与强度更新情绪
+    pub fn appraise(
+        &self,
+        emotion: &mut EmotionalState,
+        personality: &PersonalityParams,
+        abilities: &AbilityMetrics,
+        event_type: &EventType,
+        intensity: f32,
+    ) {
+        let appraisal = Appraisal::for_event_type(event_type).scaled(intensity.clamp(0.0, 1.0));
+        self.apply(emotion, personality, abilities, appraisal);
+    }
+
+    /// 应用一次情绪冲击
+    ///
+    /// 情感型与高神经质人格反应更强；消极冲击（效价下降、压力上升）被心理韧性缓冲。
+    pub fn apply(
+        &self,
+        emotion: &mut EmotionalState,
+        personality: &PersonalityParams,
+        abilities: &AbilityMetrics,
+        appraisal: Appraisal,
+    ) {
+        let reactivity = 1.0
+            + self.params.feeling_reactivity * personality.t_f
+            + 0.6 * Self::neuroticism_offset(personality);
+        let buffer = 1.0 - self.params.resilience_buffer * abilities.resilience.clamp(0.0, 1.0);
+
+        let negative = |delta: f32| if delta < 0.0 { delta * buffer } else { delta };
+        let stressful = |delta: f32| if delta > 0.0 { delta * buffer } else { delta };
+
+        emotion.valence =
+            (emotion.valence + negative(appraisal.valence) * reactivity).clamp(-1.0, 1.0);
+        emotion.arousal = (emotion.arousal + appraisal.arousal * reactivity).clamp(0.0, 1.0);
+        emotion.stress = (emotion.stress + stressful(appraisal.stress) * reactivity).clamp(0.0, 1.0);
+    }
+}
+
+/// 情绪摘要文本（用于 Prompt）
+pub fn emotion_description(emotion: &EmotionalState) -> String {
+    let mood = emotion.mood_label();
+
+    let energy = if emotion.arousal > 0.6 {
+        "情绪激动"
+    } else if emotion.arousal > 0.3 {
+        "精神尚可"
+    } else {
+        "比较平静"
+    };
+
+    let pressure = if emotion.stress > 0.7 {
+        "压力很大"
+    } else if emotion.stress > 0.4 {
+        "有一定压力"
+    } else {
+        "比较放松"
+    };
+
+    format!(
+        "{mood}，{energy}，{pressure}（效价={:.1}, 唤醒={:.1}, 压力={:.1}）",
+        emotion.valence, emotion.arousal, emotion.stress
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decay_toward_baseline() {
+        let model = EmotionModel::default();
+        let personality = PersonalityParams::new(0.0, 0.0, 0.0, 0.0);
+        let abilities = AbilityMetrics::default();
+        let baseline = model.baseline(&personality);
+
+        let mut emotion = EmotionalState {
+            valence: -0.8,
+            arousal: 0.9,
+            stress: 0.9,
+        };
+        for _ in 0..200 {
+            model.decay(&mut emotion, &personality, &abilities, 1);
+        }
+
+        assert!((emotion.valence - baseline.valence).abs() < 0.01);
+        assert!((emotion.arousal - baseline.arousal).abs() < 0.01);
+        assert!((emotion.stress - baseline.stress).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_extravert_baseline_is_happier() {
+        let model = EmotionModel::default();
+        let extravert = model.baseline(&PersonalityParams::new(-0.9, 0.0, 0.0, 0.0));
+        let introvert = model.baseline(&PersonalityParams::new(0.9, 0.0, 0.0, 0.0));
+        assert!(extravert.valence > introvert.valence);
+        assert!(extravert.arousal > introvert.arousal);
+    }
+
+    #[test]
+    fn test_resilience_buffers_conflict() {
+        let model = EmotionModel::default();
+        let personality = PersonalityParams::new(0.0, 0.0, 0.0, 0.0);
+        let fragile = AbilityMetrics {
+            resilience: 0.1,
+            ..Default::default()
+        };
+        let sturdy = AbilityMetrics {
+            resilience: 0.9,
+            ..Default::default()
+        };
+
+        let mut a = EmotionalState::default();
+        let mut b = EmotionalState::default();
+        model.appraise(&mut a, &personality, &fragile, &EventType::Conflict, 1.0);
+        model.appraise(&mut b, &personality, &sturdy, &EventType::Conflict, 1.0);
+
+        assert!(a.valence < b.valence);
+        assert!(a.stress > b.stress);
+    }
+
+    #[test]
+    fn test_crowding_stresses_introverts_more() {
+        let introvert = PersonalityParams::new(0.9, 0.0, 0.0, 0.0);
+        let extravert = PersonalityParams::new(-0.9, 0.0, 0.0, 0.0);
+
+        assert_eq!(Appraisal::crowding(0.5, &introvert).stress, 0.0);
+        assert!(
+            Appraisal::crowding(1.2, &introvert).stress > Appraisal::crowding(1.2, &extravert).stress
+        );
+    }
+
+    #[test]
+    fn test_feelers_take_criticism_harder() {
+        let feeler = PersonalityParams::new(0.0, 0.0, 0.9, 0.0);
+        let thinker = PersonalityParams::new(0.0, 0.0, -0.9, 0.0);
+
+        assert!(Appraisal::criticism(&feeler).stress > Appraisal::criticism(&thinker).stress);
+        assert!(Appraisal::criticism(&feeler).valence < Appraisal::praise().valence);
+    }
+
+    #[test]
+    fn test_exam_rank_drop_hurts() {
+        let top = Appraisal::exam_result(1.0, None);
+        let bottom = Appraisal::exam_result(0.0, None);
+        assert!(top.valence > 0.0 && bottom.valence < 0.0);
+        assert!(bottom.stress > top.stress);
+
+        // 同样的名次，下滑比进步更难受
+        let dropped = Appraisal::exam_result(0.5, Some(0.9));
+        let improved = Appraisal::exam_result(0.5, Some(0.1));
+        assert!(dropped.valence < improved.valence);
+        assert!(dropped.stress > improved.stress);
+    }
+
+    #[test]
+    fn test_emotion_description() {
+        let desc = emotion_description(&EmotionalState {
+            valence: -0.6,
+            arousal: 0.2,
+            stress: 0.8,
+        });
+        assert!(desc.contains("沮丧"));
+        assert!(desc.contains("压力很大"));
+    }
+}
@@ -1,4 +1,6 @@
 pub mod builder;
 pub mod career;
 pub mod cognition;
+pub mod emotion;
 pub mod personality;
+pub mod policy;
@@ -1,10 +1,15 @@
 //! M1.1 人格初始化引擎
 //!
-//! 从 MBTI 4D 分数生成行为倾向权重，支持随机生成保证分布多样性。
+//! 从人格参数（MBTI 4D 分数或大五特质）生成行为倾向权重，支持随机生成保证分布多样性。
+//! `PersonalityModel` 抽象描述生成、行为倾向、职业匹配与演化维度解析，
+//! 由 `model_for` 按人格参数的模型类型分派。
 
 use rand::Rng;
 
-use ai_school_core::types::PersonalityParams;
+use ai_school_core::types::{
+    BigFiveParams, BigFiveTrait, EventId, EventType, PersonalityDimension, PersonalityModelKind, PersonalityParams,
+    SimulationEvent,
+};
 
 /// 人格行为倾向权重 — 从 MBTI 4D 映射到具体行为倾向
 #[derive(Debug, Clone)]
@@ -23,11 +28,18 @@ pub struct BehaviorTendencies {
     pub empathy: f32,
     /// 独立性 (0.0 依赖 ~ 1.0 独立)
     pub independence: f32,
+    /// 情绪稳定性 (0.0 易波动 ~ 1.0 稳定)；MBTI 无对应维度，取 0.5
+    pub emotional_stability: f32,
 }
 
 impl BehaviorTendencies {
-    /// 从 MBTI 人格参数计算行为倾向
+    /// 从人格参数计算行为倾向（按人格模型分派）
     pub fn from_personality(params: &PersonalityParams) -> Self {
+        model_for(params.model_kind()).tendencies(params)
+    }
+
+    /// 从 MBTI 人格参数计算行为倾向
+    pub fn from_mbti(params: &PersonalityParams) -> Self {
         // E/I 维度影响：社交主动性、独立性
         let social_initiative = (1.0 - params.e_i) / 2.0; // E(-1) → 1.0, I(+1) → 0.0
         let independence = (1.0 + params.e_i) / 2.0; // I(+1) → 1.0, E(-1) → 0.0
@@ -51,10 +63,150 @@ impl BehaviorTendencies {
             risk_taking,
             empathy,
             independence,
+            emotional_stability: 0.5,
+        }
+    }
+
+    /// 从大五人格参数计算行为倾向
+    pub fn from_big_five(big_five: &BigFiveParams) -> Self {
+        let stability = 1.0 - big_five.neuroticism;
+        Self {
+            social_initiative: big_five.extraversion,
+            learning_style: big_five.openness,
+            decision_style: big_five.agreeableness,
+            planning: big_five.conscientiousness,
+            risk_taking: 0.7 * big_five.openness + 0.3 * stability,
+            empathy: big_five.agreeableness,
+            independence: 0.7 * (1.0 - big_five.extraversion) + 0.3 * stability,
+            emotional_stability: stability,
+        }
+    }
+}
+
+/// 人格模型抽象
+pub trait PersonalityModel: Send + Sync {
+    fn kind(&self) -> PersonalityModelKind;
+
+    /// 人格描述文本（用于 Prompt）
+    fn describe(&self, params: &PersonalityParams) -> String;
+
+    /// 行为倾向
+    fn tendencies(&self, params: &PersonalityParams) -> BehaviorTendencies;
+
+    /// 与理想人格画像的匹配度 (0.0 ~ 1.0)
+    fn match_score(&self, params: &PersonalityParams, ideal: &PersonalityParams) -> f32;
+
+    /// 反思输出中可用的演化维度代码
+    fn dimension_codes(&self) -> &'static [&'static str];
+
+    /// 解析演化维度代码，不属于本模型的代码返回 None
+    fn parse_dimension(&self, code: &str) -> Option<PersonalityDimension>;
+}
+
+/// MBTI 4 维连续人格模型
+pub struct MbtiModel;
+
+/// 大五人格 (OCEAN) 模型
+pub struct BigFiveModel;
+
+/// 按模型类型获取人格模型
+pub fn model_for(kind: PersonalityModelKind) -> &'static dyn PersonalityModel {
+    match kind {
+        PersonalityModelKind::Mbti => &MbtiModel,
+        PersonalityModelKind::BigFive => &BigFiveModel,
+    }
+}
+
+impl PersonalityModel for MbtiModel {
+    fn kind(&self) -> PersonalityModelKind {
+        PersonalityModelKind::Mbti
+    }
+
+    fn describe(&self, params: &PersonalityParams) -> String {
+        mbti_description(params)
+    }
+
+    fn tendencies(&self, params: &PersonalityParams) -> BehaviorTendencies {
+        BehaviorTendencies::from_mbti(params)
+    }
+
+    fn match_score(&self, params: &PersonalityParams, ideal: &PersonalityParams) -> f32 {
+        let diff = (params.e_i - ideal.e_i).abs()
+            + (params.s_n - ideal.s_n).abs()
+            + (params.t_f - ideal.t_f).abs()
+            + (params.j_p - ideal.j_p).abs();
+
+        // 距离越小，匹配越高；每轴最大距离为 2.0（从 -1.0 到 1.0）
+        (1.0 - diff / 4.0 / 2.0).max(0.0)
+    }
+
+    fn dimension_codes(&self) -> &'static [&'static str] {
+        &["EI", "SN", "TF", "JP"]
+    }
+
+    fn parse_dimension(&self, code: &str) -> Option<PersonalityDimension> {
+        match code {
+            "EI" => Some(PersonalityDimension::EI),
+            "SN" => Some(PersonalityDimension::SN),
+            "TF" => Some(PersonalityDimension::TF),
+            "JP" => Some(PersonalityDimension::JP),
+            _ => None,
         }
     }
 }
 
+impl PersonalityModel for BigFiveModel {
+    fn kind(&self) -> PersonalityModelKind {
+        PersonalityModelKind::BigFive
+    }
+
+    fn describe(&self, params: &PersonalityParams) -> String {
+        big_five_description(&params.to_big_five())
+    }
+
+    fn tendencies(&self, params: &PersonalityParams) -> BehaviorTendencies {
+        BehaviorTendencies::from_big_five(&params.to_big_five())
+    }
+
+    /// 五个特质的平均距离；理想画像未给出神经质时取 0.3（情绪稳定总体有利）
+    fn match_score(&self, params: &PersonalityParams, ideal: &PersonalityParams) -> f32 {
+        let actual = params.to_big_five();
+        let ideal = ideal.big_five.clone().unwrap_or_else(|| {
+            BigFiveParams::from_mbti_axes(ideal.e_i, ideal.s_n, ideal.t_f, ideal.j_p, 0.3)
+        });
+
+        let diff: f32 = BIG_FIVE_TRAITS
+            .iter()
+            .map(|t| (actual.get(*t) - ideal.get(*t)).abs())
+            .sum();
+        (1.0 - diff / BIG_FIVE_TRAITS.len() as f32).max(0.0)
+    }
+
+    fn dimension_codes(&self) -> &'static [&'static str] {
+        &["O", "C", "E", "A", "N"]
+    }
+
+    fn parse_dimension(&self, code: &str) -> Option<PersonalityDimension> {
+        let t = match code {
+            "O" => BigFiveTrait::Openness,
+            "C" => BigFiveTrait::Conscientiousness,
+            "E" => BigFiveTrait::Extraversion,
+            "A" => BigFiveTrait::Agreeableness,
+            "N" => BigFiveTrait::Neuroticism,
+            _ => return None,
+        };
+        Some(PersonalityDimension::BigFive(t))
+    }
+}
+
+const BIG_FIVE_TRAITS: [BigFiveTrait; 5] = [
+    BigFiveTrait::Openness,
+    BigFiveTrait::Conscientiousness,
+    BigFiveTrait::Extraversion,
+    BigFiveTrait::Agreeableness,
+    BigFiveTrait::Neuroticism,
+];
+
 /// 随机生成一组多样化的人格参数
 pub fn generate_diverse_personalities(count: usize) -> Vec<PersonalityParams> {
     let mut rng = rand::thread_rng();
@@ -85,9 +237,84 @@ pub fn generate_diverse_personalities(count: usize) -> Vec<PersonalityParams> {
     personalities
 }
 
-/// 根据 MBTI 类型生成人格描述文本（用于 Prompt）
+/// 随机生成一组多样化的大五人格参数
+///
+/// 在 MBTI 分层生成的基础上映射到大五，并随机补充神经质。
+pub fn generate_diverse_big_five(count: usize) -> Vec<PersonalityParams> {
+    let mut rng = rand::thread_rng();
+
+    generate_diverse_personalities(count)
+        .into_iter()
+        .map(|mbti| {
+            let big_five = BigFiveParams::from_mbti_axes(
+                mbti.e_i,
+                mbti.s_n,
+                mbti.t_f,
+                mbti.j_p,
+                rng.gen_range(0.1..0.9),
+            );
+            let mut params = PersonalityParams::from_big_five(big_five);
+            params.stability = mbti.stability;
+            params
+        })
+        .collect()
+}
+
+/// 每个维度一周内最多累积的漂移信号
+pub const MAX_WEEKLY_DRIFT: f32 = 0.02;
+
+/// 一段时间的经历累积出的人格漂移信号（实际幅度再经稳定度与衰减系数缩放，见 `apply_shift`）
+#[derive(Debug, Clone)]
+pub struct PersonalityDrift {
+    pub dimension: PersonalityDimension,
+    pub signal: f32,
+    /// 最近一次相关的事件
+    pub event_id: EventId,
+    pub reason: String,
+}
+
+/// 经历 → 人格漂移：社交与合作略向外倾，冲突略向思考，学业事件略向判断，特别活动略向直觉
+///
+/// `events` 为 Agent 参与的事件（按时间顺序），每个维度的信号不超过 `MAX_WEEKLY_DRIFT`。
+pub fn drift_from_events(events: &[&SimulationEvent]) -> Vec<PersonalityDrift> {
+    let mut drifts: Vec<(PersonalityDrift, usize)> = Vec::new();
+    for event in events {
+        let (dimension, signal, label) = match event.event_type {
+            EventType::SocialInteraction | EventType::Cooperation => (PersonalityDimension::EI, -0.004, "社交与合作"),
+            EventType::Conflict => (PersonalityDimension::TF, -0.004, "冲突"),
+            EventType::Academic => (PersonalityDimension::JP, -0.003, "学业事件"),
+            EventType::SpecialEvent => (PersonalityDimension::SN, 0.003, "特别活动"),
+            _ => continue,
+        };
+        match drifts.iter_mut().find(|(d, _)| d.dimension == dimension) {
+            Some((drift, count)) => {
+                drift.signal = (drift.signal + signal).clamp(-MAX_WEEKLY_DRIFT, MAX_WEEKLY_DRIFT);
+                drift.event_id = event.id.clone();
+                *count += 1;
+                drift.reason = format!("近一周经历了{count}次{label}");
+            }
+            None => drifts.push((
+                PersonalityDrift {
+                    dimension,
+                    signal,
+                    event_id: event.id.clone(),
+                    reason: format!("近一周经历了1次{label}"),
+                },
+                1,
+            )),
+        }
+    }
+    drifts.into_iter().map(|(drift, _)| drift).collect()
+}
+
+/// 生成人格描述文本（用于 Prompt，按人格模型分派）
 pub fn personality_description(params: &PersonalityParams) -> String {
-    let tendencies = BehaviorTendencies::from_personality(params);
+    model_for(params.model_kind()).describe(params)
+}
+
+/// 根据 MBTI 类型生成人格描述文本
+fn mbti_description(params: &PersonalityParams) -> String {
+    let tendencies = BehaviorTendencies::from_mbti(params);
     let mbti = params.mbti_label();
 
     let mut desc = format!("人格类型: {mbti}\n");
@@ -138,9 +365,100 @@ pub fn personality_description(params: &PersonalityParams) -> String {
     desc
 }
 
+/// 根据大五特质生成人格描述文本
+fn big_five_description(big_five: &BigFiveParams) -> String {
+    let tendencies = BehaviorTendencies::from_big_five(big_five);
+    let mut desc = format!("人格类型（大五）: {}\n", big_five.ocean_label());
+
+    let level = |v: f32, high: &'static str, mid: &'static str, low: &'static str| {
+        if v > 0.65 {
+            high
+        } else if v < 0.35 {
+            low
+        } else {
+            mid
+        }
+    };
+
+    desc.push_str(level(
+        big_five.openness,
+        "好奇心强，乐于尝试新事物和新想法。",
+        "对新事物保持适度兴趣。",
+        "偏好熟悉的事物和既定方式。",
+    ));
+    desc.push_str(level(
+        big_five.conscientiousness,
+        "做事认真负责，有条理，自律性强。",
+        "在计划性和随性之间保持平衡。",
+        "比较随性，不太喜欢被计划约束。",
+    ));
+    desc.push_str(level(
+        big_five.extraversion,
+        "性格外向，喜欢与人交流，从社交中获得能量。",
+        "在社交和独处之间取得平衡，视情境调整。",
+        "性格内向，偏好独处和深度思考。",
+    ));
+    desc.push_str(level(
+        big_five.agreeableness,
+        "待人友善，重视合作与人际和谐。",
+        "在坚持己见与照顾他人之间保持平衡。",
+        "直率，有竞争意识，不轻易妥协。",
+    ));
+    desc.push_str(level(
+        big_five.neuroticism,
+        "情绪比较敏感，容易感到焦虑和压力。",
+        "情绪总体平稳，偶尔会有波动。",
+        "情绪稳定，面对压力也能保持冷静。",
+    ));
+
+    desc.push_str(&format!(
+        "\n社交主动性: {:.0}%, 计划性: {:.0}%, 共情能力: {:.0}%, 情绪稳定性: {:.0}%",
+        tendencies.social_initiative * 100.0,
+        tendencies.planning * 100.0,
+        tendencies.empathy * 100.0,
+        tendencies.emotional_stability * 100.0,
+    ));
+
+    desc
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ai_school_core::types::{EventTrigger, SimulationTime};
+
+    fn event(event_type: EventType) -> SimulationEvent {
+        SimulationEvent {
+            id: EventId::new(),
+            event_type,
+            trigger: EventTrigger::AgentAction,
+            timestamp: SimulationTime::new(),
+            involved_agents: Vec::new(),
+            narrative: String::new(),
+            state_changes: Vec::new(),
+            intensity: 0.5,
+            location: None,
+            causes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_drift_is_bounded_per_dimension() {
+        let mut events: Vec<SimulationEvent> = (0..20).map(|_| event(EventType::SocialInteraction)).collect();
+        events.push(event(EventType::Conflict));
+        events.push(event(EventType::Routine));
+        let refs: Vec<&SimulationEvent> = events.iter().collect();
+
+        let drifts = drift_from_events(&refs);
+        assert_eq!(drifts.len(), 2);
+        let social = &drifts[0];
+        assert_eq!(social.dimension, PersonalityDimension::EI);
+        assert_eq!(social.signal, -MAX_WEEKLY_DRIFT);
+        assert_eq!(social.event_id, events[19].id);
+        assert_eq!(social.reason, "近一周经历了20次社交与合作");
+        assert_eq!(drifts[1].dimension, PersonalityDimension::TF);
+        assert!(drift_from_events(&[]).is_empty());
+    }
 
     #[test]
     fn test_behavior_tendencies() {
@@ -172,4 +490,32 @@ mod tests {
         assert!(desc.contains("ENTJ"));
         assert!(desc.contains("外向"));
     }
+
+    #[test]
+    fn test_big_five_model() {
+        let big_five = BigFiveParams::new(0.8, 0.3, 0.9, 0.6, 0.2);
+        let params = PersonalityParams::from_big_five(big_five);
+        assert_eq!(params.model_kind(), PersonalityModelKind::BigFive);
+
+        let desc = personality_description(&params);
+        assert!(desc.contains("大五"));
+        assert!(desc.contains("外向"));
+
+        let tendencies = BehaviorTendencies::from_personality(&params);
+        assert!(tendencies.social_initiative > 0.8);
+        assert!(tendencies.emotional_stability > 0.7);
+    }
+
+    #[test]
+    fn test_mbti_big_five_round_trip() {
+        let mbti = PersonalityParams::new(-0.8, 0.6, -0.7, -0.5);
+        let exported = mbti.to_big_five();
+        let imported = PersonalityParams::from_big_five(exported);
+
+        assert!((imported.e_i - mbti.e_i).abs() < 1e-5);
+        assert!((imported.s_n - mbti.s_n).abs() < 1e-5);
+        assert!((imported.t_f - mbti.t_f).abs() < 1e-5);
+        assert!((imported.j_p - mbti.j_p).abs() < 1e-5);
+        assert_eq!(imported.mbti_label(), "ENTJ");
+    }
 }
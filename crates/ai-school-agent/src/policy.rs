@@ -0,0 +1,424 @@
+//! M1.5 启发式决策策略
+//!
+//! 不调用 LLM 的规则决策：由人格行为倾向、情绪、人际关系与时段
+//! 为每种意图打分，按分数加权抽样产生 `BehaviorIntent`。
+//! 既可作为整个仿真的决策模式（大规模/低成本运行），
+//! 也可作为单个 Agent LLM 决策失败时的兜底。
+//! 教职工另有一套打分：留意身边状态不好的学生，按教学风格表扬、批评或谈心。
+
+use rand::Rng;
+
+use ai_school_core::types::{
+    AgentActivity, AgentId, AgentRole, AgentState, BehaviorIntent, IntentType, SimulationTime,
+    TeachingStyle,
+};
+
+use crate::personality::BehaviorTendencies;
+
+/// 附近的 Agent 及其关系
+#[derive(Debug, Clone)]
+pub struct NearbyAgent {
+    pub id: AgentId,
+    pub name: String,
+    /// 与决策者的亲密度 (-1.0 ~ 1.0)，无关系记录时为 0.0
+    pub closeness: f32,
+}
+
+/// 教职工眼中的附近学生
+#[derive(Debug, Clone)]
+pub struct ObservedStudent {
+    pub id: AgentId,
+    pub name: String,
+    pub stress: f32,
+    pub valence: f32,
+    /// 近期学业表现 (0.0 ~ 1.0)，无记录时为 None
+    pub recent_performance: Option<f32>,
+}
+
+impl ObservedStudent {
+    /// 处境困难：压力大、情绪低落或成绩下滑
+    pub fn is_struggling(&self) -> bool {
+        self.stress > 0.7
+            || self.valence < -0.3
+            || self.recent_performance.is_some_and(|p| p < 0.4)
+    }
+}
+
+/// 启发式决策策略
+#[derive(Debug, Clone)]
+pub struct HeuristicPolicy {
+    /// 亲密度低于此值视为可能发生冲突的对象
+    pub hostility_threshold: f32,
+    /// 亲密度高于此值视为合作对象
+    pub friendship_threshold: f32,
+    /// 近期表现达到此值的学生值得表扬
+    pub praise_threshold: f32,
+    /// 社交意愿倍率（随社交密度调整）
+    pub social_factor: f32,
+    /// 学习与对抗竞争对手的意愿倍率（随竞争压力调整）
+    pub competition_factor: f32,
+}
+
+impl Default for HeuristicPolicy {
+    fn default() -> Self {
+        Self {
+            hostility_threshold: -0.3,
+            friendship_threshold: 0.3,
+            praise_threshold: 0.7,
+            social_factor: 1.0,
+            competition_factor: 1.0,
+        }
+    }
+}
+
+impl HeuristicPolicy {
+    /// 为每种意图打分（非负，未出现的意图视为 0）
+    pub fn scores(
+        &self,
+        agent: &AgentState,
+        nearby: &[NearbyAgent],
+        time: &SimulationTime,
+    ) -> Vec<(IntentType, f32)> {
+        let t = BehaviorTendencies::from_personality(&agent.config.personality);
+        let emotion = &agent.emotion;
+
+        let weekday = time.is_school_day();
+        let in_class = matches!(agent.activity, AgentActivity::Studying { .. });
+        let night = time.hour >= 22 || time.hour < 7;
+        let free_time = !weekday || (16..=17).contains(&time.hour);
+        let evening_study = weekday && (19..=21).contains(&time.hour);
+
+        let best_friend = nearby.iter().map(|n| n.closeness).fold(f32::MIN, f32::max);
+        let has_friend = best_friend > self.friendship_threshold;
+        let has_rival = nearby
+            .iter()
+            .any(|n| n.closeness < self.hostility_threshold);
+
+        let mut study = (0.2 + 0.4 * t.planning) * self.competition_factor;
+        if in_class {
+            study += 0.8;
+        }
+        if evening_study {
+            study += 0.4;
+        }
+
+        let mut rest = 0.1 + 0.6 * emotion.stress + 0.2 * (1.0 - emotion.arousal);
+        if night {
+            rest += 1.5;
+        }
+
+        let reflect = 0.1 + 0.3 * t.independence + 0.3 * (-emotion.valence).max(0.0);
+
+        let mut join_activity = 0.1 + 0.3 * t.risk_taking + 0.2 * t.social_initiative;
+        if free_time {
+            join_activity += 0.4;
+        }
+
+        let (talk, collaborate, confront) = if nearby.is_empty() || night {
+            (0.0, 0.0, 0.0)
+        } else {
+            let talk = 0.2 + 0.6 * t.social_initiative + 0.3 * best_friend.max(0.0);
+            let collaborate = if has_friend { 0.2 + 0.3 * t.empathy } else { 0.05 };
+            let confront = if has_rival {
+                (0.3 * (1.0 - t.empathy) * emotion.arousal + 0.2 * emotion.stress) * self.competition_factor
+            } else {
+                0.0
+            };
+            // 上课时社交意愿降低
+            let damp = if in_class { 0.3 } else { 1.0 };
+            let social = damp * self.social_factor;
+            (talk * social, collaborate * social, confront * damp)
+        };
+
+        vec![
+            (IntentType::Study, study),
+            (IntentType::Rest, rest),
+            (IntentType::Reflect, reflect),
+            (IntentType::JoinActivity, join_activity),
+            (IntentType::Talk, talk),
+            (IntentType::Collaborate, collaborate),
+            (IntentType::Confront, confront),
+        ]
+    }
+
+    /// 决策（使用线程随机数）
+    pub fn decide(
+        &self,
+        agent: &AgentState,
+        nearby: &[NearbyAgent],
+        time: &SimulationTime,
+    ) -> BehaviorIntent {
+        self.decide_with_rng(agent, nearby, time, &mut rand::thread_rng())
+    }
+
+    /// 决策：按分数加权抽样意图，再选择交互对象并生成描述
+    pub fn decide_with_rng<R: Rng>(
+        &self,
+        agent: &AgentState,
+        nearby: &[NearbyAgent],
+        time: &SimulationTime,
+        rng: &mut R,
+    ) -> BehaviorIntent {
+        let intent_type = Self::sample(self.scores(agent, nearby, time), rng);
+
+        let target = match intent_type {
+            IntentType::Talk | IntentType::Collaborate => nearby
+                .iter()
+                .max_by(|a, b| a.closeness.total_cmp(&b.closeness)),
+            IntentType::Confront => nearby
+                .iter()
+                .min_by(|a, b| a.closeness.total_cmp(&b.closeness)),
+            _ => None,
+        };
+
+        BehaviorIntent {
+            agent_id: agent.id.clone(),
+            description: Self::describe(&intent_type, agent, target),
+            target_location: None,
+            target_agents: target.map(|t| vec![t.id.clone()]).unwrap_or_default(),
+            intent_type,
+        }
+    }
+
+    /// 教职工意图打分：处境困难的学生优先谈心，其次按教学风格表扬或批评
+    pub fn staff_scores(
+        &self,
+        agent: &AgentState,
+        students: &[ObservedStudent],
+        time: &SimulationTime,
+    ) -> Vec<(IntentType, f32)> {
+        let night = time.hour >= 22 || time.hour < 7;
+        let struggling = students.iter().any(|s| s.is_struggling());
+        let excelling = students
+            .iter()
+            .any(|s| s.recent_performance.is_some_and(|p| p >= self.praise_threshold));
+        let slacking = students
+            .iter()
+            .any(|s| s.recent_performance.is_some_and(|p| p < 0.4) && s.stress <= 0.7);
+
+        // (谈心, 表扬, 批评) 的倾向
+        let (counsel, praise, criticize) = match &agent.config.role {
+            AgentRole::Teacher { style, .. } => match style {
+                TeachingStyle::Encouraging => (1.0, 0.9, 0.1),
+                TeachingStyle::Balanced => (0.6, 0.6, 0.4),
+                TeachingStyle::Strict => (0.3, 0.3, 0.8),
+            },
+            AgentRole::Counselor => (1.5, 0.3, 0.0),
+            AgentRole::Principal => (0.3, 0.5, 0.2),
+            AgentRole::Student => (0.0, 0.0, 0.0),
+        };
+        let gate = |present: bool, weight: f32| if present && !night { weight } else { 0.0 };
+
+        vec![
+            (IntentType::Counsel, gate(struggling, counsel)),
+            (IntentType::Praise, gate(excelling, praise)),
+            (IntentType::Criticize, gate(slacking, criticize)),
+            (IntentType::Other, 0.5),
+            (IntentType::Rest, if night { 1.5 } else { 0.1 + 0.5 * agent.emotion.stress }),
+        ]
+    }
+
+    /// 教职工决策（使用线程随机数）
+    pub fn decide_staff(
+        &self,
+        agent: &AgentState,
+        students: &[ObservedStudent],
+        time: &SimulationTime,
+    ) -> BehaviorIntent {
+        self.decide_staff_with_rng(agent, students, time, &mut rand::thread_rng())
+    }
+
+    /// 教职工决策：按分数抽样意图，谈心找压力最大的困难学生，表扬/批评看近期表现
+    pub fn decide_staff_with_rng<R: Rng>(
+        &self,
+        agent: &AgentState,
+        students: &[ObservedStudent],
+        time: &SimulationTime,
+        rng: &mut R,
+    ) -> BehaviorIntent {
+        let scores = self.staff_scores(agent, students, time);
+        let intent_type = Self::sample(scores, rng);
+
+        let performance = |s: &&ObservedStudent| s.recent_performance.unwrap_or(0.5);
+        let target = match intent_type {
+            IntentType::Counsel => students
+                .iter()
+                .filter(|s| s.is_struggling())
+                .max_by(|a, b| a.stress.total_cmp(&b.stress)),
+            IntentType::Praise => students.iter().max_by(|a, b| performance(a).total_cmp(&performance(b))),
+            IntentType::Criticize => students
+                .iter()
+                .filter(|s| s.stress <= 0.7)
+                .min_by(|a, b| performance(a).total_cmp(&performance(b))),
+            _ => None,
+        };
+        let other = target.map(|t| t.name.as_str()).unwrap_or("同学");
+        let description = match intent_type {
+            IntentType::Counsel => format!("{other}最近状态不太好，我想和{other}谈谈心。"),
+            IntentType::Praise => format!("{other}最近进步很大，我要表扬一下。"),
+            IntentType::Criticize => format!("{other}最近有些懈怠，我得提醒一下。"),
+            IntentType::Rest => "我想歇一会儿。".to_string(),
+            _ => match &agent.activity {
+                AgentActivity::Teaching { subject } => format!("我继续讲{subject}课。"),
+                _ => "我在办公室备课、批改作业。".to_string(),
+            },
+        };
+
+        BehaviorIntent {
+            agent_id: agent.id.clone(),
+            description,
+            target_location: None,
+            target_agents: target.map(|t| vec![t.id.clone()]).unwrap_or_default(),
+            intent_type,
+        }
+    }
+
+    /// 按分数加权抽样意图
+    fn sample<R: Rng>(scores: Vec<(IntentType, f32)>, rng: &mut R) -> IntentType {
+        let total: f32 = scores.iter().map(|(_, s)| s).sum();
+        let mut pick = rng.r#gen::<f32>() * total;
+        for (candidate, score) in scores {
+            if score <= 0.0 {
+                continue;
+            }
+            if pick < score {
+                return candidate;
+            }
+            pick -= score;
+        }
+        IntentType::Rest
+    }
+
+    fn describe(intent_type: &IntentType, agent: &AgentState, target: Option<&NearbyAgent>) -> String {
+        let other = target.map(|t| t.name.as_str()).unwrap_or("同学");
+        match intent_type {
+            IntentType::Study => match &agent.activity {
+                AgentActivity::Studying { subject } => format!("我想认真学习{subject}。"),
+                _ => "我想找个安静的地方学习一会儿。".to_string(),
+            },
+            IntentType::Rest => "我有点累了，想休息一下。".to_string(),
+            IntentType::Reflect => "我想一个人静静地想一想最近的事。".to_string(),
+            IntentType::JoinActivity => "我想去参加一些课外活动。".to_string(),
+            IntentType::Talk => format!("我想和{other}聊聊天。"),
+            IntentType::Collaborate => format!("我想和{other}一起合作完成点事情。"),
+            IntentType::Confront => format!("我对{other}有些不满，想当面说清楚。"),
+            IntentType::Move => "我想换个地方待着。".to_string(),
+            IntentType::Other | IntentType::Praise | IntentType::Criticize | IntentType::Counsel => {
+                "我想随便做点什么。".to_string()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::builder::AgentBuilder;
+    use ai_school_core::types::PersonalityParams;
+
+    fn score_of(scores: &[(IntentType, f32)], intent: IntentType) -> f32 {
+        scores.iter().find(|(t, _)| *t == intent).map(|(_, s)| *s).unwrap()
+    }
+
+    #[test]
+    fn test_night_prefers_rest() {
+        let mut time = SimulationTime::new();
+        time.hour = 23;
+        let agent = AgentBuilder::new().build(&time);
+
+        let scores = HeuristicPolicy::default().scores(&agent, &[], &time);
+        let rest = score_of(&scores, IntentType::Rest);
+        assert!(scores.iter().all(|(_, s)| *s <= rest));
+    }
+
+    #[test]
+    fn test_extravert_talks_more() {
+        let time = SimulationTime::new();
+        let nearby = vec![NearbyAgent {
+            id: AgentId::new(),
+            name: "小红".to_string(),
+            closeness: 0.5,
+        }];
+        let extravert = AgentBuilder::new()
+            .personality(PersonalityParams::new(-0.9, 0.0, 0.0, 0.0))
+            .build(&time);
+        let introvert = AgentBuilder::new()
+            .personality(PersonalityParams::new(0.9, 0.0, 0.0, 0.0))
+            .build(&time);
+
+        let policy = HeuristicPolicy::default();
+        let e = score_of(&policy.scores(&extravert, &nearby, &time), IntentType::Talk);
+        let i = score_of(&policy.scores(&introvert, &nearby, &time), IntentType::Talk);
+        assert!(e > i);
+    }
+
+    #[test]
+    fn test_social_density_scales_talk() {
+        let time = SimulationTime::new();
+        let nearby = vec![NearbyAgent {
+            id: AgentId::new(),
+            name: "小红".to_string(),
+            closeness: 0.5,
+        }];
+        let agent = AgentBuilder::new().build(&time);
+
+        let sociable = HeuristicPolicy {
+            social_factor: 1.5,
+            ..Default::default()
+        };
+        let normal = score_of(&HeuristicPolicy::default().scores(&agent, &nearby, &time), IntentType::Talk);
+        let more = score_of(&sociable.scores(&agent, &nearby, &time), IntentType::Talk);
+        assert!(more > normal);
+    }
+
+    #[test]
+    fn test_counselor_approaches_struggling_student() {
+        let time = SimulationTime::new();
+        let counselor = AgentBuilder::new().role(AgentRole::Counselor).build(&time);
+        let student = |name: &str, stress| ObservedStudent {
+            id: AgentId::new(),
+            name: name.to_string(),
+            stress,
+            valence: 0.2,
+            recent_performance: Some(0.6),
+        };
+        let students = vec![student("小红", 0.3), student("小明", 0.9)];
+
+        let policy = HeuristicPolicy::default();
+        let mut rng = StdRng::seed_from_u64(3);
+        let counsel = (0..50)
+            .map(|_| policy.decide_staff_with_rng(&counselor, &students, &time, &mut rng))
+            .filter(|i| i.intent_type == IntentType::Counsel)
+            .inspect(|i| assert_eq!(i.target_agents, vec![students[1].id.clone()]))
+            .count();
+        assert!(counsel > 25);
+
+        let calm = vec![student("小红", 0.3)];
+        let scores = policy.staff_scores(&counselor, &calm, &time);
+        assert_eq!(score_of(&scores, IntentType::Counsel), 0.0);
+    }
+
+    #[test]
+    fn test_social_intent_targets_nearby_agent() {
+        let time = SimulationTime::new();
+        let agent = AgentBuilder::new().build(&time);
+        let nearby = vec![NearbyAgent {
+            id: AgentId::new(),
+            name: "小红".to_string(),
+            closeness: 0.8,
+        }];
+
+        let policy = HeuristicPolicy::default();
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..50 {
+            let intent = policy.decide_with_rng(&agent, &nearby, &time, &mut rng);
+            if matches!(intent.intent_type, IntentType::Talk | IntentType::Collaborate) {
+                assert_eq!(intent.target_agents, vec![nearby[0].id.clone()]);
+                assert!(intent.description.contains("小红"));
+            }
+        }
+    }
+}
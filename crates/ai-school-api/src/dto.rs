@@ -1,23 +1,28 @@
 use serde::{Deserialize, Serialize};
 
-use ai_school_core::types::{PresetEvent, SimulationSpeed};
+use ai_school_core::types::{BigFiveParams, PresetEvent, SimulationSpeed};
+use ai_school_engine::scheduler::{ScheduledAction, TriggerCondition};
 
 /// 创建 Agent 请求
 #[derive(Debug, Deserialize)]
 pub struct CreateAgentRequest {
     pub name: String,
     /// MBTI E/I 维度 (-1.0 ~ 1.0)
+    #[serde(default)]
     pub e_i: f32,
     /// MBTI S/N 维度
+    #[serde(default)]
     pub s_n: f32,
     /// MBTI T/F 维度
+    #[serde(default)]
     pub t_f: f32,
     /// MBTI J/P 维度
+    #[serde(default)]
     pub j_p: f32,
+    /// 大五人格参数（给出时优先于 MBTI 维度）
+    pub big_five: Option<BigFiveParams>,
     /// 理想职业
     pub ideal_career: Option<String>,
-    /// 背景描述
-    pub background: Option<String>,
     /// 年龄
     pub age: Option<u8>,
 }
@@ -40,6 +45,18 @@ pub struct TriggerEventRequest {
     pub event: PresetEvent,
 }
 
+/// 新增或修改计划干预请求
+#[derive(Debug, Deserialize)]
+pub struct ScheduleInterventionRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub condition: TriggerCondition,
+    pub action: ScheduledAction,
+    /// 条件触发后是否继续等待下一次
+    #[serde(default)]
+    pub repeat: bool,
+}
+
 /// 参数调整请求
 #[derive(Debug, Deserialize)]
 pub struct AdjustParamsRequest {
@@ -47,6 +64,41 @@ pub struct AdjustParamsRequest {
     pub value: f32,
 }
 
+/// 加入社团请求
+#[derive(Debug, Deserialize)]
+pub struct JoinClubRequest {
+    pub agent_id: String,
+}
+
+/// 成绩查询参数（缺省为当前学期）
+#[derive(Debug, Deserialize)]
+pub struct GradesQuery {
+    pub semester: Option<u32>,
+}
+
+/// 社交网络查询参数（指定 Agent 时返回其指标随时间的变化）
+#[derive(Debug, Deserialize)]
+pub struct NetworkQuery {
+    pub agent_id: Option<String>,
+}
+
+/// 因果回溯查询参数（未指定的取默认值）
+#[derive(Debug, Deserialize)]
+pub struct CausesQuery {
+    /// 只看某个字段的变更，如 `emotion.stress`
+    pub field: Option<String>,
+    pub since_tick: Option<u64>,
+    pub limit: Option<usize>,
+    pub depth: Option<usize>,
+}
+
+/// 分页查询参数
+#[derive(Debug, Deserialize)]
+pub struct PageQuery {
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
 /// 对话请求
 #[derive(Debug, Deserialize)]
 pub struct ChatRequest {
@@ -12,6 +12,7 @@ use ai_school_core::config::{AppConfig, LlmConfig, QdrantConfig, DatabaseConfig,
 use ai_school_engine::simulation::SimulationRunner;
 use ai_school_llm::providers::deepseek::DeepSeekProvider;
 use ai_school_memory::store::in_memory::InMemoryStore;
+use ai_school_world::scenario::Scenario;
 
 mod dto;
 mod error;
@@ -75,7 +76,14 @@ async fn main() -> Result<()> {
 
     dotenvy::dotenv().ok();
 
-    let config = load_config();
+    let mut config = load_config();
+
+    let scenario = match std::env::var("SCENARIO_PATH") {
+        Ok(path) => Scenario::load(&path)?,
+        Err(_) => Scenario::bundled(),
+    };
+    config.simulation = scenario.config.clone();
+    info!(scenario = %scenario.name, "Loaded scenario");
 
     info!(
         chat_model = %config.llm.chat_model,
@@ -86,11 +94,7 @@ async fn main() -> Result<()> {
     let llm = Arc::new(DeepSeekProvider::new(&config.llm));
     let memory_store = Arc::new(InMemoryStore::new());
 
-    let runner = SimulationRunner::new(
-        llm.clone(),
-        memory_store.clone(),
-        config.simulation.clone(),
-    );
+    let runner = SimulationRunner::from_scenario(llm.clone(), memory_store.clone(), &scenario)?;
     let running_flag = runner.running_flag();
 
     let app_state = AppState {
@@ -113,6 +117,9 @@ async fn main() -> Result<()> {
         .merge(routes::simulation::router())
         .merge(routes::agents::router())
         .merge(routes::intervention::router())
+        .merge(routes::clubs::router())
+        .merge(routes::grades::router())
+        .merge(routes::relationships::router())
         .merge(routes::analysis::router())
         .merge(ws::router())
         .fallback_service(serve_frontend)
@@ -1,14 +1,18 @@
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::routing::{get, post};
 use axum::{Json, Router};
 
-use ai_school_core::types::{CareerAspiration, CareerCategory, PersonalityParams, SimulationTime};
+use ai_school_core::error::ApiError;
+use ai_school_core::types::{AgentId, CareerAspiration, CareerCategory, PersonalityParams};
 use ai_school_core::traits::llm::{ChatMessage, CompletionRequest, LlmProvider, MessageRole};
-use ai_school_agent::builder::{generate_random_agents, AgentBuilder};
+use ai_school_agent::builder::{generate_random_agents_with_model, AgentBuilder};
 use ai_school_agent::personality::personality_description;
-use ai_school_agent::career::CareerDatabase;
+use ai_school_agent::career::{CareerDatabase, CareerExperience};
+use ai_school_world::causality::TraceQuery;
+use ai_school_world::relationships::{label_of, relationship_description};
 
-use crate::dto::{ChatRequest, CreateAgentRequest, GenerateAgentsRequest, SuccessResponse};
+use crate::dto::{CausesQuery, ChatRequest, CreateAgentRequest, GenerateAgentsRequest, PageQuery, SuccessResponse};
+use crate::error::AppError;
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
@@ -16,6 +20,9 @@ pub fn router() -> Router<AppState> {
         .route("/api/agents", get(list_agents).post(create_agent))
         .route("/api/agents/generate", post(generate_agents))
         .route("/api/agents/{id}", get(get_agent))
+        .route("/api/agents/{id}/transcript", get(get_transcript))
+        .route("/api/agents/{id}/changes", get(get_changes))
+        .route("/api/agents/{id}/causes", get(get_causes))
         .route("/api/agents/{id}/chat", post(chat_with_agent))
 }
 
@@ -29,7 +36,10 @@ async fn list_agents(State(state): State<AppState>) -> Json<serde_json::Value> {
             serde_json::json!({
                 "id": a.id.0.to_string(),
                 "name": a.config.name,
+                "role": a.config.role.title(),
                 "mbti": a.config.personality.mbti_label(),
+                "personality_model": a.config.personality.model_kind(),
+                "personality_label": a.config.personality.type_label(),
                 "location": a.location.0,
                 "activity": format!("{:?}", a.activity),
                 "emotion": {
@@ -44,6 +54,7 @@ async fn list_agents(State(state): State<AppState>) -> Json<serde_json::Value> {
                     "t_f": a.config.personality.t_f,
                     "j_p": a.config.personality.j_p,
                 },
+                "big_five": a.config.personality.to_big_five(),
                 "abilities": a.abilities,
                 "current_thought": a.current_thought,
             })
@@ -57,24 +68,25 @@ async fn create_agent(
     State(state): State<AppState>,
     Json(req): Json<CreateAgentRequest>,
 ) -> Json<SuccessResponse> {
-    let personality = PersonalityParams::new(req.e_i, req.s_n, req.t_f, req.j_p);
-    let time = SimulationTime::new();
-
+    let personality = match req.big_five {
+        Some(big_five) => PersonalityParams::from_big_five(big_five),
+        None => PersonalityParams::new(req.e_i, req.s_n, req.t_f, req.j_p),
+    };
     let career = CareerAspiration {
         ideal_career: req.ideal_career.unwrap_or_else(|| "探索中".to_string()),
         category: CareerCategory::Other("未定".to_string()),
         subject_preferences: Vec::new(),
         clarity: 0.5,
+        history: Vec::new(),
     };
 
+    let mut runner = state.runner.write().await;
     let agent = AgentBuilder::new()
         .name(&req.name)
         .personality(personality)
         .career(career)
         .age(req.age.unwrap_or(16))
-        .build(&time);
-
-    let mut runner = state.runner.write().await;
+        .build(runner.world.clock.current_time());
     runner.add_agent(agent);
 
     Json(SuccessResponse {
@@ -88,10 +100,12 @@ async fn generate_agents(
     Json(req): Json<GenerateAgentsRequest>,
 ) -> Json<SuccessResponse> {
     let count = req.count.min(10);
-    let time = SimulationTime::new();
-    let agents = generate_random_agents(count, &time);
-
     let mut runner = state.runner.write().await;
+    let agents = generate_random_agents_with_model(
+        count,
+        runner.world.clock.current_time(),
+        state.config.simulation.personality_model,
+    );
     for agent in agents {
         runner.add_agent(agent);
     }
@@ -111,31 +125,153 @@ async fn get_agent(
     let agent = runner.world.agents.values().find(|a| a.id.0.to_string() == id);
 
     match agent {
-        Some(a) => Json(serde_json::json!({
-            "id": a.id.0.to_string(),
-            "name": a.config.name,
-            "personality": {
-                "e_i": a.config.personality.e_i,
-                "s_n": a.config.personality.s_n,
-                "t_f": a.config.personality.t_f,
-                "j_p": a.config.personality.j_p,
-                "mbti": a.config.personality.mbti_label(),
-                "stability": a.config.personality.stability,
-            },
-            "career": {
-                "ideal": a.config.career_aspiration.ideal_career,
-                "clarity": a.config.career_aspiration.clarity,
-            },
-            "location": a.location.0,
-            "activity": format!("{:?}", a.activity),
-            "emotion": a.emotion,
-            "abilities": a.abilities,
-            "current_thought": a.current_thought,
-        })),
+        Some(a) => {
+            let relationships: Vec<serde_json::Value> = runner
+                .world
+                .relationships
+                .get_agent_relationships(&a.id)
+                .into_iter()
+                .filter_map(|r| {
+                    let other_id = if r.agent_a == a.id { &r.agent_b } else { &r.agent_a };
+                    let other = runner.world.agents.get(other_id)?;
+                    Some(serde_json::json!({
+                        "id": other.id.0.to_string(),
+                        "name": other.config.name,
+                        "closeness": r.closeness,
+                        "trust": r.trust,
+                        "label": label_of(r).map(|l| l.tag()),
+                        "description": relationship_description(r),
+                    }))
+                })
+                .collect();
+            let cliques: Vec<Vec<String>> = runner
+                .world
+                .relationships
+                .cliques_of(&a.id)
+                .iter()
+                .map(|c| {
+                    c.iter()
+                        .filter_map(|id| runner.world.agents.get(id))
+                        .map(|m| m.config.name.clone())
+                        .collect()
+                })
+                .collect();
+
+            Json(serde_json::json!({
+                "id": a.id.0.to_string(),
+                "name": a.config.name,
+                "role": a.config.role,
+                "title": a.config.role.title(),
+                "personality": {
+                    "e_i": a.config.personality.e_i,
+                    "s_n": a.config.personality.s_n,
+                    "t_f": a.config.personality.t_f,
+                    "j_p": a.config.personality.j_p,
+                    "mbti": a.config.personality.mbti_label(),
+                    "model": a.config.personality.model_kind(),
+                    "label": a.config.personality.type_label(),
+                    "big_five": a.config.personality.to_big_five(),
+                    "stability": a.config.personality.stability,
+                },
+                "career": {
+                    "ideal": a.config.career_aspiration.ideal_career,
+                    "clarity": a.config.career_aspiration.clarity,
+                    "history": a.config.career_aspiration.history,
+                },
+                "location": a.location.0,
+                "activity": format!("{:?}", a.activity),
+                "class_group": runner.world.class_group_of(&a.id).map(|g| g.name.clone()),
+                "clubs": runner.world.clubs_of(&a.id).iter().map(|c| c.name.clone()).collect::<Vec<_>>(),
+                "relationships": relationships,
+                "cliques": cliques,
+                "emotion": a.emotion,
+                "abilities": a.abilities,
+                "current_thought": a.current_thought,
+            }))
+        }
         None => Json(serde_json::json!({ "error": "Agent not found" })),
     }
 }
 
+async fn get_transcript(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let runner = state.runner.read().await;
+
+    let agent = runner
+        .world
+        .agents
+        .values()
+        .find(|a| a.id.0.to_string() == id)
+        .ok_or_else(|| AppError(ApiError::NotFound(format!("Agent {id} not found"))))?;
+
+    Ok(Json(serde_json::json!({
+        "id": id,
+        "name": agent.config.name,
+        "exams": runner.world.gradebook.transcript(&agent.id),
+    })))
+}
+
+/// Agent 的状态变更审计记录（按时间顺序分页）
+async fn get_changes(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(page): Query<PageQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let runner = state.runner.read().await;
+
+    let agent = runner
+        .world
+        .agents
+        .values()
+        .find(|a| a.id.0.to_string() == id)
+        .ok_or_else(|| AppError(ApiError::NotFound(format!("Agent {id} not found"))))?;
+
+    let offset = page.offset.unwrap_or(0);
+    let limit = page.limit.unwrap_or(200);
+    Ok(Json(serde_json::json!({
+        "id": id,
+        "name": agent.config.name,
+        "offset": offset,
+        "limit": limit,
+        "changes": runner.world.audit_for(&agent.id, offset, limit),
+    })))
+}
+
+/// 回溯 Agent 状态变化背后的事件链与干预
+async fn get_causes(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<CausesQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let runner = state.runner.read().await;
+
+    let agent = runner
+        .world
+        .agents
+        .values()
+        .find(|a| a.id.0.to_string() == id)
+        .ok_or_else(|| AppError(ApiError::NotFound(format!("Agent {id} not found"))))?;
+
+    let defaults = TraceQuery::default();
+    let trace = runner.world.explain(
+        &agent.id,
+        &TraceQuery {
+            field: query.field,
+            since_tick: query.since_tick,
+            limit: query.limit.unwrap_or(defaults.limit),
+            max_depth: query.depth.unwrap_or(defaults.max_depth),
+        },
+    );
+
+    Ok(Json(serde_json::json!({
+        "id": id,
+        "name": agent.config.name,
+        "trace": trace,
+    })))
+}
+
 async fn chat_with_agent(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -158,25 +294,45 @@ async fn chat_with_agent(
     let personality_desc = personality_description(&agent.config.personality);
     let career_desc = CareerDatabase::aspiration_description(&agent.config.career_aspiration);
 
-    let system = format!(
-        "你是一个名叫{}的高中生。以下是你的角色设定：\n\
-        人格特征: {}\n\
-        职业志向: {}\n\
-        当前情绪: 效价={:.1}, 唤醒={:.1}, 压力={:.1}\n\
-        当前活动: {:?}\n\
-        当前位置: {}\n\n\
-        现在有一位{}想和你对话。请根据你的人格特征、情绪状态自然地回应。\
-        回应要简短（1-3句），像一个真实的高中生那样说话。用中文回答。",
-        agent.config.name,
-        personality_desc,
-        career_desc,
-        agent.emotion.valence,
-        agent.emotion.arousal,
-        agent.emotion.stress,
-        agent.activity,
-        agent.location.0,
-        role_desc,
-    );
+    let system = if agent.config.role.is_staff() {
+        format!(
+            "你是{}，学校的{}。{}\n\
+            人格特征: {}\n\
+            当前情绪: 效价={:.1}, 唤醒={:.1}, 压力={:.1}\n\
+            当前位置: {}\n\n\
+            现在有一位{}想和你对话。请以你的身份自然地回应。\
+            回应要简短（1-3句）。用中文回答。",
+            agent.config.name,
+            agent.config.role.title(),
+            agent.config.background.as_deref().unwrap_or(""),
+            personality_desc,
+            agent.emotion.valence,
+            agent.emotion.arousal,
+            agent.emotion.stress,
+            agent.location.0,
+            role_desc,
+        )
+    } else {
+        format!(
+            "你是一个名叫{}的高中生。以下是你的角色设定：\n\
+            人格特征: {}\n\
+            职业志向: {}\n\
+            当前情绪: 效价={:.1}, 唤醒={:.1}, 压力={:.1}\n\
+            当前活动: {:?}\n\
+            当前位置: {}\n\n\
+            现在有一位{}想和你对话。请根据你的人格特征、情绪状态自然地回应。\
+            回应要简短（1-3句），像一个真实的高中生那样说话。用中文回答。",
+            agent.config.name,
+            personality_desc,
+            career_desc,
+            agent.emotion.valence,
+            agent.emotion.arousal,
+            agent.emotion.stress,
+            agent.activity,
+            agent.location.0,
+            role_desc,
+        )
+    };
 
     let request = CompletionRequest {
         system,
@@ -192,12 +348,32 @@ async fn chat_with_agent(
     drop(runner);
 
     match llm.complete(&request).await {
-        Ok(response) => Json(serde_json::json!({
-            "reply": response.content,
-            "impact": "对话内容已被记录到 Agent 记忆中",
-        })),
+        Ok(response) => {
+            if req.role == "counselor" {
+                record_counselor_chat(&state, &agent.id).await;
+            }
+            Json(serde_json::json!({
+                "reply": response.content,
+                "impact": "对话内容已被记录到 Agent 记忆中",
+            }))
+        }
         Err(e) => Json(serde_json::json!({
             "error": format!("LLM call failed: {}", e),
         })),
     }
 }
+
+/// 辅导员对话提升职业清晰度
+async fn record_counselor_chat(state: &AppState, agent_id: &AgentId) {
+    let mut guard = state.runner.write().await;
+    let runner = &mut *guard;
+    let time = runner.world.clock.current_time().clone();
+    if let Some(agent) = runner.world.agents.get_mut(agent_id) {
+        runner.career_evolution.record_experience(
+            &mut agent.config.career_aspiration,
+            &CareerExperience::CounselorChat,
+            &time,
+            None,
+        );
+    }
+}
@@ -1,13 +1,21 @@
-use axum::extract::State;
+use axum::extract::{Path, Query, State};
 use axum::routing::get;
 use axum::{Json, Router};
 
+use ai_school_core::error::ApiError;
+use ai_school_core::types::EventId;
+use ai_school_world::causality::{causal_trace, TraceQuery};
+
+use crate::dto::{CausesQuery, NetworkQuery};
+use crate::error::AppError;
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/api/analysis/snapshot", get(get_snapshot))
         .route("/api/analysis/events", get(get_events))
+        .route("/api/analysis/events/{id}/causes", get(get_event_causes))
+        .route("/api/analysis/network", get(get_network))
         .route("/api/analysis/export", get(export_data))
 }
 
@@ -33,6 +41,7 @@ async fn get_events(State(state): State<AppState>) -> Json<serde_json::Value> {
                 "narrative": e.narrative,
                 "intensity": e.intensity,
                 "involved_agents": e.involved_agents.len(),
+                "causes": e.causes,
             })
         })
         .collect();
@@ -40,6 +49,109 @@ async fn get_events(State(state): State<AppState>) -> Json<serde_json::Value> {
     Json(serde_json::json!({ "events": events }))
 }
 
+/// 从一个事件沿因果边回溯
+async fn get_event_causes(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<CausesQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let runner = state.runner.read().await;
+    let event = uuid::Uuid::parse_str(&id)
+        .ok()
+        .and_then(|uuid| runner.world.event(&EventId(uuid)))
+        .ok_or_else(|| AppError(ApiError::NotFound(format!("Event {id} not found"))))?;
+
+    let changes = runner
+        .world
+        .change_audit
+        .iter()
+        .filter(|a| a.source_event.as_ref() == Some(&event.id))
+        .cloned()
+        .collect();
+    let depth = query.depth.unwrap_or(TraceQuery::default().max_depth);
+    let trace = causal_trace(&runner.world.event_log, vec![event.id.clone()], changes, depth);
+
+    Ok(Json(serde_json::json!({ "trace": trace })))
+}
+
+/// 当前社交网络指标、每天的整体指标走势，以及（可选）某个 Agent 的融入过程
+async fn get_network(
+    State(state): State<AppState>,
+    Query(query): Query<NetworkQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let runner = state.runner.read().await;
+    let world = &runner.world;
+    let name = |id| world.agents.get(id).map(|a| a.config.name.clone());
+    let current = world.network_metrics();
+
+    let agents: Vec<serde_json::Value> = current
+        .agents
+        .iter()
+        .map(|m| {
+            let mut value = serde_json::to_value(m).unwrap_or_default();
+            value["name"] = serde_json::json!(name(&m.agent_id));
+            value
+        })
+        .collect();
+    let communities: Vec<Vec<Option<String>>> = current
+        .communities
+        .iter()
+        .map(|c| c.iter().map(name).collect())
+        .collect();
+    let history: Vec<serde_json::Value> = world
+        .network_history
+        .iter()
+        .map(|m| {
+            serde_json::json!({
+                "time": m.time,
+                "tie_count": m.tie_count,
+                "density": m.density,
+                "average_clustering": m.average_clustering,
+                "trusted_tie_ratio": m.trusted_tie_ratio,
+                "communities": m.communities.len(),
+                "modularity": m.modularity,
+                "isolated": m.isolated.len(),
+                "environment": world.environment_at(m.time.tick),
+            })
+        })
+        .collect();
+
+    let mut body = serde_json::json!({
+        "time": current.time,
+        "node_count": current.node_count,
+        "tie_count": current.tie_count,
+        "density": current.density,
+        "average_clustering": current.average_clustering,
+        "trusted_tie_ratio": current.trusted_tie_ratio,
+        "modularity": current.modularity,
+        "communities": communities,
+        "isolated": current.isolated.iter().map(name).collect::<Vec<_>>(),
+        "agents": agents,
+        "environment": world.environment,
+        "history": history,
+    });
+
+    if let Some(id) = query.agent_id {
+        let agent_id = world
+            .agents
+            .keys()
+            .find(|a| a.0.to_string() == id)
+            .ok_or_else(|| AppError(ApiError::NotFound(format!("Agent {id} not found"))))?;
+        let timeline: Vec<serde_json::Value> = world
+            .network_history
+            .iter()
+            .chain(std::iter::once(&current))
+            .filter_map(|m| {
+                let metrics = m.agent(agent_id)?;
+                Some(serde_json::json!({ "time": m.time, "metrics": metrics }))
+            })
+            .collect();
+        body["agent_timeline"] = serde_json::json!(timeline);
+    }
+
+    Ok(Json(body))
+}
+
 async fn export_data(State(state): State<AppState>) -> Json<serde_json::Value> {
     let runner = state.runner.read().await;
 
@@ -64,5 +176,13 @@ async fn export_data(State(state): State<AppState>) -> Json<serde_json::Value> {
         },
         "agents": agents,
         "events": events,
+        "academic_records": runner.world.academics.export_json(),
+        "exam_records": runner.world.gradebook.export_json(),
+        "network": runner.world.network_metrics(),
+        "network_history": runner.world.network_timeline(),
+        "environment_history": runner.world.environment_history,
+        "change_audit": runner.world.change_audit,
+        "interventions": runner.interventions.export_logs(),
+        "scheduled_interventions": runner.scheduler.list(),
     }))
 }
@@ -0,0 +1,68 @@
+use axum::extract::{Path, State};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+
+use ai_school_core::error::ApiError;
+
+use crate::dto::{JoinClubRequest, SuccessResponse};
+use crate::error::AppError;
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/clubs", get(list_clubs))
+        .route("/api/clubs/{name}/members", post(join_club))
+}
+
+async fn list_clubs(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let runner = state.runner.read().await;
+    let clubs: Vec<serde_json::Value> = runner
+        .world
+        .clubs
+        .iter()
+        .map(|c| {
+            let members: Vec<serde_json::Value> = c
+                .members
+                .iter()
+                .filter_map(|id| runner.world.agents.get(id))
+                .map(|a| serde_json::json!({ "id": a.id.0.to_string(), "name": a.config.name }))
+                .collect();
+            serde_json::json!({
+                "name": c.name,
+                "description": c.description,
+                "location": c.location.0,
+                "meeting_days": c.meeting_days(),
+                "max_members": c.max_members,
+                "members": members,
+            })
+        })
+        .collect();
+
+    Json(serde_json::json!({ "clubs": clubs }))
+}
+
+async fn join_club(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(req): Json<JoinClubRequest>,
+) -> Result<Json<SuccessResponse>, AppError> {
+    let mut runner = state.runner.write().await;
+
+    let agent_id = runner
+        .world
+        .agents
+        .values()
+        .find(|a| a.id.0.to_string() == req.agent_id)
+        .map(|a| a.id.clone())
+        .ok_or_else(|| AppError(ApiError::NotFound(format!("Agent {} not found", req.agent_id))))?;
+
+    runner
+        .world
+        .join_club(&agent_id, &name)
+        .map_err(|e| AppError(ApiError::BadRequest(e.to_string())))?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        message: format!("Joined {name}"),
+    }))
+}
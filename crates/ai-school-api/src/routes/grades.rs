@@ -0,0 +1,80 @@
+use axum::extract::{Query, State};
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Json, Router};
+
+use crate::dto::GradesQuery;
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/grades", get(grade_report))
+        .route("/api/grades/export", get(export_grades))
+}
+
+/// 某学期各次考试的成绩分布
+async fn grade_report(
+    State(state): State<AppState>,
+    Query(query): Query<GradesQuery>,
+) -> Json<serde_json::Value> {
+    let runner = state.runner.read().await;
+    let semester = query
+        .semester
+        .unwrap_or(runner.world.clock.current_time().semester);
+    let calendar = runner.world.clock.calendar();
+    let upcoming: Vec<serde_json::Value> = runner
+        .world
+        .exam_calendar
+        .iter()
+        .map(|e| {
+            serde_json::json!({
+                "name": e.name,
+                "kind": e.kind,
+                "week": e.week,
+                "day_of_week": e.day_of_week,
+                "date": calendar.date_of(semester, e.week, e.day_of_week),
+            })
+        })
+        .collect();
+
+    Json(serde_json::json!({
+        "semester": semester,
+        "calendar": upcoming,
+        "exams": runner.world.gradebook.semester_report(semester),
+    }))
+}
+
+/// 导出全部成绩（CSV，每行一科）
+async fn export_grades(State(state): State<AppState>) -> impl IntoResponse {
+    let runner = state.runner.read().await;
+    let mut csv = String::from("agent_id,name,exam,kind,semester,week,day_of_week,date,subject,score,average,rank,cohort_size\n");
+    for record in runner.world.gradebook.all_records() {
+        let name = runner
+            .world
+            .agents
+            .get(&record.agent_id)
+            .map(|a| a.config.name.as_str())
+            .unwrap_or_default();
+        for score in &record.scores {
+            csv.push_str(&format!(
+                "{},{},{},{:?},{},{},{},{},{},{:.1},{:.1},{},{}\n",
+                record.agent_id,
+                name,
+                record.exam,
+                record.kind,
+                record.timestamp.semester,
+                record.timestamp.week,
+                record.timestamp.day_of_week,
+                record.timestamp.datetime.map(|d| d.date().to_string()).unwrap_or_default(),
+                score.subject,
+                score.score,
+                record.average,
+                record.rank,
+                record.cohort_size,
+            ));
+        }
+    }
+
+    ([(header::CONTENT_TYPE, "text/csv; charset=utf-8")], csv)
+}
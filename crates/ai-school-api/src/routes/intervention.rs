@@ -1,57 +1,154 @@
-use axum::extract::State;
-use axum::routing::{post, put};
+use axum::extract::{Path, State};
+use axum::routing::{get, post, put};
 use axum::{Json, Router};
 
-use crate::dto::{AdjustParamsRequest, TriggerEventRequest, SuccessResponse};
+use ai_school_core::error::ApiError;
+use ai_school_engine::intervention::EnvironmentParameter;
+use ai_school_engine::scheduler::SchedulerError;
+
+use crate::dto::{AdjustParamsRequest, ScheduleInterventionRequest, SuccessResponse, TriggerEventRequest};
+use crate::error::AppError;
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/api/interventions/event", post(trigger_event))
+        .route("/api/interventions/log", get(intervention_log))
+        .route("/api/interventions/scheduled", get(list_scheduled).post(create_scheduled))
+        .route(
+            "/api/interventions/scheduled/{id}",
+            get(get_scheduled).put(update_scheduled).delete(delete_scheduled),
+        )
+        .route("/api/interventions/scheduled/{id}/cancel", post(cancel_scheduled))
         .route("/api/simulation/params", put(adjust_params))
 }
 
 async fn trigger_event(
     State(state): State<AppState>,
     Json(req): Json<TriggerEventRequest>,
-) -> Json<SuccessResponse> {
+) -> Result<Json<SuccessResponse>, AppError> {
     let mut runner = state.runner.write().await;
-    let time = runner.world.clock.current_time().clone();
+    let outcome = runner
+        .trigger_preset(&req.event)
+        .await
+        .map_err(|e| AppError(ApiError::Simulation(e)))?;
+
+    let narratives: Vec<&str> = outcome.events.iter().map(|e| e.narrative.as_str()).collect();
+    let affected = outcome.events.first().map_or(0, |e| e.involved_agents.len());
+    let mut message = format!("Event triggered ({affected} agents affected): {}", narratives.join(" "));
+    if !outcome.warnings.is_empty() {
+        message.push_str(&format!(" [warnings: {}]", outcome.warnings.join("; ")));
+    }
 
-    let mut intervention_manager = ai_school_engine::intervention::InterventionManager::new();
-    let event = intervention_manager.trigger_preset_event(&req.event, &time);
+    Ok(Json(SuccessResponse {
+        success: true,
+        message,
+    }))
+}
 
-    runner.world.event_log.push(event);
+async fn intervention_log(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let runner = state.runner.read().await;
+    Json(serde_json::json!({ "interventions": runner.interventions.export_logs() }))
+}
 
-    Json(SuccessResponse {
+fn scheduler_error(e: SchedulerError) -> AppError {
+    match e {
+        SchedulerError::NotFound(_) => AppError(ApiError::NotFound(e.to_string())),
+        SchedulerError::NotPending(_) | SchedulerError::Invalid(_) => AppError(ApiError::BadRequest(e.to_string())),
+    }
+}
+
+async fn list_scheduled(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let runner = state.runner.read().await;
+    Json(serde_json::json!({ "scheduled": runner.scheduler.list() }))
+}
+
+async fn create_scheduled(
+    State(state): State<AppState>,
+    Json(req): Json<ScheduleInterventionRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let mut guard = state.runner.write().await;
+    let runner = &mut *guard;
+    let id = runner
+        .scheduler
+        .schedule(
+            req.name.unwrap_or_else(|| "计划干预".to_string()),
+            req.condition,
+            req.action,
+            req.repeat,
+            &runner.world,
+        )
+        .map_err(scheduler_error)?;
+    Ok(Json(serde_json::json!(runner.scheduler.get(id))))
+}
+
+async fn get_scheduled(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let runner = state.runner.read().await;
+    let entry = runner
+        .scheduler
+        .get(id)
+        .ok_or_else(|| scheduler_error(SchedulerError::NotFound(id)))?;
+    Ok(Json(serde_json::json!(entry)))
+}
+
+async fn update_scheduled(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+    Json(req): Json<ScheduleInterventionRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let mut guard = state.runner.write().await;
+    let runner = &mut *guard;
+    let name = match req.name {
+        Some(name) => name,
+        None => runner.scheduler.get(id).map(|e| e.name.clone()).unwrap_or_default(),
+    };
+    runner
+        .scheduler
+        .update(id, name, req.condition, req.action, req.repeat, &runner.world)
+        .map_err(scheduler_error)?;
+    Ok(Json(serde_json::json!(runner.scheduler.get(id))))
+}
+
+async fn cancel_scheduled(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<Json<SuccessResponse>, AppError> {
+    let mut runner = state.runner.write().await;
+    runner.scheduler.cancel(id).map_err(scheduler_error)?;
+    Ok(Json(SuccessResponse {
         success: true,
-        message: "Event triggered".to_string(),
-    })
+        message: format!("Scheduled intervention {id} cancelled"),
+    }))
+}
+
+async fn delete_scheduled(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<Json<SuccessResponse>, AppError> {
+    let mut runner = state.runner.write().await;
+    runner.scheduler.remove(id).map_err(scheduler_error)?;
+    Ok(Json(SuccessResponse {
+        success: true,
+        message: format!("Scheduled intervention {id} deleted"),
+    }))
 }
 
 async fn adjust_params(
     State(state): State<AppState>,
     Json(req): Json<AdjustParamsRequest>,
-) -> Json<SuccessResponse> {
+) -> Result<Json<SuccessResponse>, AppError> {
+    let parameter = EnvironmentParameter::from_name(&req.parameter)
+        .ok_or_else(|| AppError(ApiError::BadRequest(format!("Unknown parameter: {}", req.parameter))))?;
     let mut runner = state.runner.write().await;
+    let previous = runner
+        .set_parameter(&parameter, req.value)
+        .map_err(|e| AppError(ApiError::BadRequest(e.to_string())))?;
 
-    match req.parameter.as_str() {
-        "CourseDifficulty" => {
-            runner.config.random_event_frequency = req.value;
-        }
-        "RandomEventFrequency" => {
-            runner.config.random_event_frequency = req.value;
-        }
-        _ => {
-            return Json(SuccessResponse {
-                success: false,
-                message: format!("Unknown parameter: {}", req.parameter),
-            });
-        }
-    }
-
-    Json(SuccessResponse {
+    Ok(Json(SuccessResponse {
         success: true,
-        message: format!("Parameter '{}' set to {}", req.parameter, req.value),
-    })
+        message: format!("Parameter '{}' changed from {previous} to {}", req.parameter, req.value),
+    }))
 }
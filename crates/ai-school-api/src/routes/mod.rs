@@ -1,4 +1,7 @@
 pub mod agents;
 pub mod analysis;
+pub mod clubs;
+pub mod grades;
 pub mod intervention;
+pub mod relationships;
 pub mod simulation;
@@ -0,0 +1,59 @@
+use axum::extract::State;
+use axum::routing::get;
+use axum::{Json, Router};
+
+use ai_school_world::relationships::label_of;
+
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/relationships", get(list_relationships))
+        .route("/api/relationships/cliques", get(list_cliques))
+}
+
+async fn list_relationships(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let runner = state.runner.read().await;
+    let name = |id| runner.world.agents.get(id).map(|a| a.config.name.clone());
+    let relationships: Vec<serde_json::Value> = runner
+        .world
+        .relationships
+        .all_relationships()
+        .into_iter()
+        .map(|r| {
+            serde_json::json!({
+                "agent_a": r.agent_a.0.to_string(),
+                "agent_b": r.agent_b.0.to_string(),
+                "name_a": name(&r.agent_a),
+                "name_b": name(&r.agent_b),
+                "closeness": r.closeness,
+                "trust": r.trust,
+                "label": label_of(r).map(|l| l.tag()),
+                "tags": r.tags,
+                "last_interaction": r.last_interaction,
+            })
+        })
+        .collect();
+
+    Json(serde_json::json!({ "relationships": relationships }))
+}
+
+async fn list_cliques(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let runner = state.runner.read().await;
+    let cliques: Vec<serde_json::Value> = runner
+        .world
+        .relationships
+        .cliques()
+        .iter()
+        .map(|clique| {
+            let members: Vec<serde_json::Value> = clique
+                .iter()
+                .filter_map(|id| runner.world.agents.get(id))
+                .map(|a| serde_json::json!({ "id": a.id.0.to_string(), "name": a.config.name }))
+                .collect();
+            serde_json::json!({ "size": clique.len(), "members": members })
+        })
+        .collect();
+
+    Json(serde_json::json!({ "cliques": cliques }))
+}
@@ -1,17 +1,17 @@
-use ai_school_agent::builder::generate_random_agents;
+use ai_school_agent::builder::generate_random_agents_with_model;
 use ai_school_agent::career::CareerDatabase;
 use ai_school_agent::personality::personality_description;
-use ai_school_core::types::SimulationTime;
+use ai_school_core::types::{PersonalityModelKind, SimulationTime};
 
-pub fn execute(agent_count: usize) {
+pub fn execute(agent_count: usize, personality_model: PersonalityModelKind) {
     let time = SimulationTime::new();
-    let agents = generate_random_agents(agent_count, &time);
+    let agents = generate_random_agents_with_model(agent_count, &time, personality_model);
 
     println!("\n=== AI School Agent Inspection ===\n");
 
     for agent in &agents {
         println!("--- {} ---", agent.config.name);
-        println!("MBTI: {}", agent.config.personality.mbti_label());
+        println!("人格类型: {}", agent.config.personality.type_label());
         println!("{}", personality_description(&agent.config.personality));
         println!("职业志向: {}", agent.config.career_aspiration.ideal_career);
 
@@ -3,28 +3,55 @@ use std::sync::Arc;
 use anyhow::Result;
 use tracing::info;
 
-use ai_school_agent::builder::generate_random_agents;
-use ai_school_core::config::SimulationConfig;
-use ai_school_core::types::SimulationTime;
+use ai_school_agent::builder::generate_random_agents_with_model;
+use ai_school_core::config::DecisionMode;
+use ai_school_core::types::PersonalityModelKind;
 use ai_school_engine::simulation::SimulationRunner;
 use ai_school_llm::providers::mock::MockLlmProvider;
 use ai_school_memory::store::in_memory::InMemoryStore;
+use ai_school_world::scenario::Scenario;
 
-pub async fn execute(agent_count: usize, steps: usize, output: Option<String>) -> Result<()> {
-    info!(agents = agent_count, steps, "Starting batch simulation");
+pub async fn execute(
+    agent_count: usize,
+    steps: usize,
+    output: Option<String>,
+    heuristic: bool,
+    personality_model: Option<PersonalityModelKind>,
+    scenario_path: Option<String>,
+) -> Result<()> {
+    info!(agents = agent_count, steps, heuristic, "Starting batch simulation");
+
+    let mut scenario = match &scenario_path {
+        Some(path) => Scenario::load(path)?,
+        None => Scenario::bundled(),
+    };
+    info!(scenario = %scenario.name, "Scenario loaded");
+
+    // 命令行参数覆盖场景配置
+    if heuristic {
+        scenario.config.decision_mode = DecisionMode::Heuristic;
+    }
+    if let Some(model) = personality_model {
+        scenario.config.personality_model = model;
+    }
 
     let llm = Arc::new(MockLlmProvider::default());
     let memory = Arc::new(InMemoryStore::new());
-    let config = SimulationConfig::default();
+    let mut runner = SimulationRunner::from_scenario(llm, memory, &scenario)?;
 
-    let mut runner = SimulationRunner::new(llm, memory, config);
-
-    // Generate agents
-    let time = SimulationTime::new();
-    let agents = generate_random_agents(agent_count, &time);
-    for agent in agents {
-        info!(name = %agent.config.name, mbti = %agent.config.personality.mbti_label(), "Agent created");
-        runner.add_agent(agent);
+    // 场景名单中没有学生时随机生成学生 Agent
+    if !scenario.roster.iter().any(|entry| !entry.role.is_staff()) {
+        let agents = generate_random_agents_with_model(
+            agent_count,
+            runner.world.clock.current_time(),
+            scenario.config.personality_model,
+        );
+        for agent in agents {
+            runner.add_agent(agent);
+        }
+    }
+    for agent in runner.world.agents.values() {
+        info!(name = %agent.config.name, personality = %agent.config.personality.type_label(), "Agent created");
     }
 
     // Run simulation
@@ -53,11 +80,16 @@ pub async fn execute(agent_count: usize, steps: usize, output: Option<String>) -
     let export_data = serde_json::json!({
         "simulation": {
             "steps": steps,
-            "agent_count": agent_count,
+            "scenario": scenario.name,
+            "agent_count": snapshot.agents.len(),
             "final_time": snapshot.time,
         },
         "agents": snapshot.agents,
         "relationships": snapshot.relationships,
+        "grades": runner.world.gradebook.semester_report(snapshot.time.semester),
+        "network": runner.world.network_metrics(),
+        "network_history": runner.world.network_timeline(),
+        "change_audit": runner.world.change_audit,
         "event_count": runner.world.event_log.len(),
     });
 
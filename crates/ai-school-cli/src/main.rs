@@ -1,6 +1,8 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
+use ai_school_core::types::PersonalityModelKind;
+
 mod commands;
 
 #[derive(Parser)]
@@ -25,6 +27,18 @@ enum Commands {
         /// 输出文件路径
         #[arg(short, long)]
         output: Option<String>,
+
+        /// 使用启发式决策策略（不调用 LLM）
+        #[arg(long)]
+        heuristic: bool,
+
+        /// 使用大五人格模型生成 Agent
+        #[arg(long)]
+        big_five: bool,
+
+        /// 场景文件路径（JSON），缺省使用内置默认场景
+        #[arg(long)]
+        scenario: Option<String>,
     },
 
     /// 查看 Agent 人格匹配
@@ -32,9 +46,21 @@ enum Commands {
         /// Agent 数量
         #[arg(short, long, default_value_t = 5)]
         agents: usize,
+
+        /// 使用大五人格模型生成 Agent
+        #[arg(long)]
+        big_five: bool,
     },
 }
 
+fn personality_model(big_five: bool) -> PersonalityModelKind {
+    if big_five {
+        PersonalityModelKind::BigFive
+    } else {
+        PersonalityModelKind::Mbti
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt()
@@ -46,11 +72,19 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Run { agents, steps, output } => {
-            commands::run::execute(agents, steps, output).await?;
+        Commands::Run { agents, steps, output, heuristic, big_five, scenario } => {
+            commands::run::execute(
+                agents,
+                steps,
+                output,
+                heuristic,
+                big_five.then_some(PersonalityModelKind::BigFive),
+                scenario,
+            )
+            .await?;
         }
-        Commands::Inspect { agents } => {
-            commands::inspect::execute(agents);
+        Commands::Inspect { agents, big_five } => {
+            commands::inspect::execute(agents, personality_model(big_five));
         }
     }
 
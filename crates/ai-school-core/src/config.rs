@@ -1,7 +1,12 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::types::{EmotionalState, PersonalityModelKind};
+
 /// 仿真配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SimulationConfig {
     /// 最大 Agent 数量
     pub max_agents: usize,
@@ -15,6 +20,112 @@ pub struct SimulationConfig {
     pub reflection_threshold: usize,
     /// 人格演化衰减系数
     pub personality_decay_factor: f32,
+    /// Agent 决策模式
+    pub decision_mode: DecisionMode,
+    /// LLM 决策失败时是否回退到启发式策略
+    pub heuristic_fallback: bool,
+    /// 新生成 Agent 使用的人格模型
+    pub personality_model: PersonalityModelKind,
+    /// 状态变更与叙事矛盾时的处理策略
+    pub consistency_policy: ConsistencyPolicy,
+    /// 是否额外用 LLM 复核叙事与数据的一致性
+    pub consistency_llm_judge: bool,
+    /// 每步状态变化幅度上限
+    pub change_limits: ChangeLimits,
+    /// 状态变更审计日志最多保留的条数（超出时丢弃最早的记录）
+    pub audit_capacity: usize,
+    /// 情绪动力学参数
+    pub emotion: EmotionParams,
+}
+
+/// 叙事-数据不一致的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ConsistencyPolicy {
+    /// 只记录警告，照常应用变更
+    #[default]
+    Warn,
+    /// 带上问题重新仲裁一次，仍矛盾则丢弃变更
+    Rearbitrate,
+    /// 丢弃该事件的状态变更，只保留叙事
+    DropChanges,
+}
+
+/// 每步变化幅度上限（按字段路径，如 `emotion.stress`、`closeness`）
+///
+/// 同一对象同一字段在一步内的累计变化不超过上限；没有配置的字段不限。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeLimits {
+    pub per_tick: HashMap<String, f32>,
+}
+
+impl Default for ChangeLimits {
+    fn default() -> Self {
+        let limits = [
+            ("emotion.valence", 0.4),
+            ("emotion.arousal", 0.4),
+            ("emotion.stress", 0.3),
+            ("ability.academic", 0.05),
+            ("ability.social", 0.05),
+            ("ability.resilience", 0.05),
+            ("ability.creativity", 0.05),
+            ("career.clarity", 0.1),
+            ("closeness", 0.2),
+            ("trust", 0.2),
+        ];
+        Self {
+            per_tick: limits.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+        }
+    }
+}
+
+impl ChangeLimits {
+    pub fn limit(&self, path: &str) -> Option<f32> {
+        self.per_tick.get(path).copied()
+    }
+}
+
+/// 情绪模型参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmotionParams {
+    /// 效价回归速率（每小时）
+    pub valence_decay: f32,
+    /// 唤醒度回归速率（每小时）
+    pub arousal_decay: f32,
+    /// 压力回归速率（每小时）
+    pub stress_decay: f32,
+    /// 基线：中性人格的情绪状态
+    pub neutral_baseline: EmotionalState,
+    /// 外倾性对效价/唤醒基线的影响幅度
+    pub extraversion_weight: f32,
+    /// 情感型（F）对情绪反应强度的放大系数
+    pub feeling_reactivity: f32,
+    /// 心理韧性对消极冲击的缓冲系数（0.0 无缓冲 ~ 1.0 完全缓冲）
+    pub resilience_buffer: f32,
+}
+
+impl Default for EmotionParams {
+    fn default() -> Self {
+        Self {
+            valence_decay: 0.05,
+            arousal_decay: 0.15,
+            stress_decay: 0.03,
+            neutral_baseline: EmotionalState::default(),
+            extraversion_weight: 0.15,
+            feeling_reactivity: 0.3,
+            resilience_buffer: 0.6,
+        }
+    }
+}
+
+/// Agent 决策模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DecisionMode {
+    /// 每个 Agent 调用 LLM 决策
+    #[default]
+    Llm,
+    /// 规则启发式决策，不调用 LLM（大规模/低成本运行）
+    Heuristic,
 }
 
 impl Default for SimulationConfig {
@@ -26,6 +137,14 @@ impl Default for SimulationConfig {
             random_event_frequency: 0.1,
             reflection_threshold: 10,
             personality_decay_factor: 0.8,
+            decision_mode: DecisionMode::Llm,
+            heuristic_fallback: true,
+            personality_model: PersonalityModelKind::Mbti,
+            consistency_policy: ConsistencyPolicy::Warn,
+            consistency_llm_judge: false,
+            change_limits: ChangeLimits::default(),
+            audit_capacity: 20_000,
+            emotion: EmotionParams::default(),
         }
     }
 }
@@ -114,7 +233,7 @@ impl Default for ServerConfig {
 }
 
 /// 应用全局配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AppConfig {
     pub simulation: SimulationConfig,
     pub llm: LlmConfig,
@@ -122,15 +241,3 @@ pub struct AppConfig {
     pub database: DatabaseConfig,
     pub server: ServerConfig,
 }
-
-impl Default for AppConfig {
-    fn default() -> Self {
-        Self {
-            simulation: SimulationConfig::default(),
-            llm: LlmConfig::default(),
-            qdrant: QdrantConfig::default(),
-            database: DatabaseConfig::default(),
-            server: ServerConfig::default(),
-        }
-    }
-}
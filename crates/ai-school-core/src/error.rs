@@ -76,6 +76,9 @@ pub enum WorldError {
 
     #[error("World state error: {0}")]
     StateError(String),
+
+    #[error("Invalid scenario: {0}")]
+    InvalidScenario(String),
 }
 
 /// 仿真引擎错误
@@ -38,6 +38,69 @@ pub struct AgentConfig {
     pub background: Option<String>,
     /// Agent 年龄（影响人格稳定度）
     pub age: u8,
+    /// 角色（学生或教职工）
+    #[serde(default)]
+    pub role: AgentRole,
+}
+
+/// Agent 角色
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum AgentRole {
+    #[default]
+    Student,
+    /// 任课教师
+    Teacher {
+        subjects: Vec<String>,
+        style: TeachingStyle,
+    },
+    /// 心理辅导员
+    Counselor,
+    /// 校长
+    Principal,
+}
+
+impl AgentRole {
+    pub fn is_staff(&self) -> bool {
+        !matches!(self, AgentRole::Student)
+    }
+
+    /// 称谓，如“数学老师”
+    pub fn title(&self) -> String {
+        match self {
+            AgentRole::Student => "学生".to_string(),
+            AgentRole::Teacher { subjects, .. } => match subjects.first() {
+                Some(subject) => format!("{subject}老师"),
+                None => "老师".to_string(),
+            },
+            AgentRole::Counselor => "心理辅导员".to_string(),
+            AgentRole::Principal => "校长".to_string(),
+        }
+    }
+
+    pub fn teaches(&self, subject: &str) -> bool {
+        matches!(self, AgentRole::Teacher { subjects, .. } if subjects.iter().any(|s| s == subject))
+    }
+}
+
+/// 教学风格
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum TeachingStyle {
+    /// 鼓励型：多表扬、常谈心
+    Encouraging,
+    #[default]
+    Balanced,
+    /// 严格型：要求高、批评更直接
+    Strict,
+}
+
+impl TeachingStyle {
+    pub fn description(&self) -> &'static str {
+        match self {
+            TeachingStyle::Encouraging => "鼓励型：善于发现学生的进步，多表扬、常谈心",
+            TeachingStyle::Balanced => "平衡型：赏罚分明，因材施教",
+            TeachingStyle::Strict => "严格型：要求高，对懈怠直言批评",
+        }
+    }
 }
 
 /// Agent 当前活动状态
@@ -45,6 +108,8 @@ pub struct AgentConfig {
 pub enum AgentActivity {
     /// 在上课
     Studying { subject: String },
+    /// 在授课（教师）
+    Teaching { subject: String },
     /// 社交互动
     Socializing { with: Vec<AgentId> },
     /// 休息
@@ -70,6 +135,21 @@ pub struct EmotionalState {
     pub stress: f32,
 }
 
+impl EmotionalState {
+    /// 按效价划分的心情描述
+    pub fn mood_label(&self) -> &'static str {
+        if self.valence > 0.4 {
+            "心情很好"
+        } else if self.valence > 0.0 {
+            "心情平稳"
+        } else if self.valence > -0.4 {
+            "有些低落"
+        } else {
+            "非常沮丧"
+        }
+    }
+}
+
 impl Default for EmotionalState {
     fn default() -> Self {
         Self {
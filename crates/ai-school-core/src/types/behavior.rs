@@ -2,6 +2,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::agent::AgentId;
+use super::event::EventId;
 use super::world::{LocationId, SimulationTime};
 
 /// 行为意图 — Agent LLM 输出
@@ -38,21 +39,49 @@ pub enum IntentType {
     Confront,
     /// 反思/独处
     Reflect,
+    /// 表扬学生（教职工）
+    Praise,
+    /// 批评学生（教职工）
+    Criticize,
+    /// 找学生谈心、辅导（教职工）
+    Counsel,
     /// 其他
     Other,
 }
 
+/// 感知到的附近 Agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObservedAgent {
+    pub id: AgentId,
+    pub name: String,
+    /// 可观察到的活动描述
+    pub activity: String,
+    /// 外在的情绪表现
+    pub mood: String,
+    /// 与感知者的关系描述（无关系记录时为 None）
+    pub relationship: Option<String>,
+}
+
 /// 感知输入 — Agent 从环境中获取的信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Perception {
-    /// 当前位置的 Agent 列表
-    pub nearby_agents: Vec<AgentId>,
+    /// 当前位置的名称
+    pub location_name: String,
+    /// 当前位置的拥挤度（在场人数 / 容量）
+    pub crowding: f32,
+    /// 当前位置的其他 Agent
+    pub nearby_agents: Vec<ObservedAgent>,
     /// 可观察到的活动
     pub observable_activities: Vec<String>,
     /// 环境描述
     pub environment_description: String,
     /// 最近的事件提醒
     pub recent_events: Vec<String>,
+    /// 最近在当前位置发生的事件
+    pub local_events: Vec<String>,
+    /// 以上事件的 ID（用于记录决策的原因）
+    #[serde(default)]
+    pub event_ids: Vec<EventId>,
 }
 
 /// 情境上下文 — 组装 LLM 决策请求的完整输入
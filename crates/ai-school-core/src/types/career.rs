@@ -1,6 +1,9 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use super::event::EventId;
+use super::world::SimulationTime;
+
 /// 职业类别
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum CareerCategory {
@@ -27,6 +30,28 @@ pub struct CareerAspiration {
     pub subject_preferences: Vec<SubjectPreference>,
     /// 职业清晰度 (0.0 迷茫 ~ 1.0 确定)
     pub clarity: f32,
+    /// 职业志向变化历史
+    #[serde(default)]
+    pub history: Vec<CareerChange>,
+}
+
+/// 职业志向变化记录 — 支持研究分析的志向轨迹回溯
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CareerChange {
+    pub timestamp: SimulationTime,
+    /// 触发事件（学业反馈等非事件经历为 None）
+    pub trigger_event_id: Option<EventId>,
+    pub kind: CareerChangeKind,
+    pub reason: String,
+}
+
+/// 职业志向变化类型
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub enum CareerChangeKind {
+    /// 清晰度变化
+    Clarity { old: f32, new: f32 },
+    /// 理想职业转变
+    Switch { from: String, to: String },
 }
 
 /// 学科偏好
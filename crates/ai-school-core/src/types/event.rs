@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::agent::AgentId;
-use super::world::SimulationTime;
+use super::world::{LocationId, SimulationTime};
 
 /// 事件唯一标识符
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
@@ -100,6 +100,12 @@ pub struct SimulationEvent {
     pub state_changes: Vec<StateChange>,
     /// 事件强度 (0.0 ~ 1.0)
     pub intensity: f32,
+    /// 发生地点（跨地点或全校范围的事件为 None）
+    #[serde(default)]
+    pub location: Option<LocationId>,
+    /// 直接原因：决策时感知到的事件、检索到的记忆所关联的事件、触发它的干预
+    #[serde(default)]
+    pub causes: Vec<EventId>,
 }
 
 /// 预设事件模板
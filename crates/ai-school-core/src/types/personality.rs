@@ -4,7 +4,17 @@ use serde::{Deserialize, Serialize};
 use super::event::EventId;
 use super::world::SimulationTime;
 
-/// MBTI 人格维度
+/// 人格模型类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+pub enum PersonalityModelKind {
+    /// MBTI 4 维连续分数
+    #[default]
+    Mbti,
+    /// 大五人格 (OCEAN)
+    BigFive,
+}
+
+/// 人格维度（MBTI 4 轴 + 大五特质）
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum PersonalityDimension {
     /// 外倾 (E) ←→ 内倾 (I)
@@ -15,6 +25,119 @@ pub enum PersonalityDimension {
     TF,
     /// 判断 (J) ←→ 知觉 (P)
     JP,
+    /// 大五特质
+    BigFive(BigFiveTrait),
+}
+
+/// 大五人格特质
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub enum BigFiveTrait {
+    /// 开放性 (O)
+    Openness,
+    /// 尽责性 (C)
+    Conscientiousness,
+    /// 外向性 (E)
+    Extraversion,
+    /// 宜人性 (A)
+    Agreeableness,
+    /// 神经质 (N)
+    Neuroticism,
+}
+
+/// 大五人格参数，各特质取值 0.0 ~ 1.0
+///
+/// 与 MBTI 的映射（McCrae & Costa, 1989 的相关性结论，线性化处理）：
+///
+/// | 大五特质 | MBTI 轴 | 映射 |
+/// |---------|---------|------|
+/// | 外向性 E | E/I | `extraversion = (1 − e_i) / 2` |
+/// | 开放性 O | S/N | `openness = (1 + s_n) / 2` |
+/// | 宜人性 A | T/F | `agreeableness = (1 + t_f) / 2` |
+/// | 尽责性 C | J/P | `conscientiousness = (1 − j_p) / 2` |
+/// | 神经质 N | —   | MBTI 无对应维度，MBTI 导入时取 0.5 |
+///
+/// 映射可逆（神经质除外），MBTI → 大五 → MBTI 往返无损。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BigFiveParams {
+    pub openness: f32,
+    pub conscientiousness: f32,
+    pub extraversion: f32,
+    pub agreeableness: f32,
+    pub neuroticism: f32,
+}
+
+impl BigFiveParams {
+    pub fn new(
+        openness: f32,
+        conscientiousness: f32,
+        extraversion: f32,
+        agreeableness: f32,
+        neuroticism: f32,
+    ) -> Self {
+        Self {
+            openness: openness.clamp(0.0, 1.0),
+            conscientiousness: conscientiousness.clamp(0.0, 1.0),
+            extraversion: extraversion.clamp(0.0, 1.0),
+            agreeableness: agreeableness.clamp(0.0, 1.0),
+            neuroticism: neuroticism.clamp(0.0, 1.0),
+        }
+    }
+
+    /// 从 MBTI 轴映射（神经质需单独给出）
+    pub fn from_mbti_axes(e_i: f32, s_n: f32, t_f: f32, j_p: f32, neuroticism: f32) -> Self {
+        Self::new(
+            (1.0 + s_n) / 2.0,
+            (1.0 - j_p) / 2.0,
+            (1.0 - e_i) / 2.0,
+            (1.0 + t_f) / 2.0,
+            neuroticism,
+        )
+    }
+
+    /// 映射回 MBTI 轴 (e_i, s_n, t_f, j_p)
+    pub fn to_mbti_axes(&self) -> (f32, f32, f32, f32) {
+        (
+            1.0 - 2.0 * self.extraversion,
+            2.0 * self.openness - 1.0,
+            2.0 * self.agreeableness - 1.0,
+            1.0 - 2.0 * self.conscientiousness,
+        )
+    }
+
+    pub fn get(&self, t: BigFiveTrait) -> f32 {
+        match t {
+            BigFiveTrait::Openness => self.openness,
+            BigFiveTrait::Conscientiousness => self.conscientiousness,
+            BigFiveTrait::Extraversion => self.extraversion,
+            BigFiveTrait::Agreeableness => self.agreeableness,
+            BigFiveTrait::Neuroticism => self.neuroticism,
+        }
+    }
+
+    pub fn set(&mut self, t: BigFiveTrait, value: f32) {
+        let clamped = value.clamp(0.0, 1.0);
+        match t {
+            BigFiveTrait::Openness => self.openness = clamped,
+            BigFiveTrait::Conscientiousness => self.conscientiousness = clamped,
+            BigFiveTrait::Extraversion => self.extraversion = clamped,
+            BigFiveTrait::Agreeableness => self.agreeableness = clamped,
+            BigFiveTrait::Neuroticism => self.neuroticism = clamped,
+        }
+    }
+
+    /// OCEAN 标签，高于 0.5 大写（如 "OcEAn"）
+    pub fn ocean_label(&self) -> String {
+        [
+            ('O', self.openness),
+            ('C', self.conscientiousness),
+            ('E', self.extraversion),
+            ('A', self.agreeableness),
+            ('N', self.neuroticism),
+        ]
+        .iter()
+        .map(|(c, v)| if *v > 0.5 { *c } else { c.to_ascii_lowercase() })
+        .collect()
+    }
 }
 
 /// MBTI 4 维连续人格参数 — 对应 ADR-0002 的人格参数空间 Θ
@@ -32,6 +155,9 @@ pub struct PersonalityParams {
     pub stability: f32,
     /// 人格变化历史
     pub shift_history: Vec<PersonalityShift>,
+    /// 大五人格参数 — 存在时为权威表示，MBTI 轴由映射同步
+    #[serde(default)]
+    pub big_five: Option<BigFiveParams>,
 }
 
 impl PersonalityParams {
@@ -44,9 +170,34 @@ impl PersonalityParams {
             j_p: j_p.clamp(-1.0, 1.0),
             stability: 1.0,
             shift_history: Vec::new(),
+            big_five: None,
+        }
+    }
+
+    /// 以大五人格参数创建，MBTI 轴按映射同步
+    pub fn from_big_five(big_five: BigFiveParams) -> Self {
+        let (e_i, s_n, t_f, j_p) = big_five.to_mbti_axes();
+        let mut params = Self::new(e_i, s_n, t_f, j_p);
+        params.big_five = Some(big_five);
+        params
+    }
+
+    /// 使用的人格模型
+    pub fn model_kind(&self) -> PersonalityModelKind {
+        if self.big_five.is_some() {
+            PersonalityModelKind::BigFive
+        } else {
+            PersonalityModelKind::Mbti
         }
     }
 
+    /// 导出为大五人格参数（MBTI 人格的神经质取 0.5）
+    pub fn to_big_five(&self) -> BigFiveParams {
+        self.big_five.clone().unwrap_or_else(|| {
+            BigFiveParams::from_mbti_axes(self.e_i, self.s_n, self.t_f, self.j_p, 0.5)
+        })
+    }
+
     /// 获取指定维度的值
     pub fn get_dimension(&self, dim: PersonalityDimension) -> f32 {
         match dim {
@@ -54,10 +205,11 @@ impl PersonalityParams {
             PersonalityDimension::SN => self.s_n,
             PersonalityDimension::TF => self.t_f,
             PersonalityDimension::JP => self.j_p,
+            PersonalityDimension::BigFive(t) => self.to_big_five().get(t),
         }
     }
 
-    /// 设置指定维度的值
+    /// 设置指定维度的值，MBTI 轴与大五参数保持同步
     pub fn set_dimension(&mut self, dim: PersonalityDimension, value: f32) {
         let clamped = value.clamp(-1.0, 1.0);
         match dim {
@@ -65,6 +217,32 @@ impl PersonalityParams {
             PersonalityDimension::SN => self.s_n = clamped,
             PersonalityDimension::TF => self.t_f = clamped,
             PersonalityDimension::JP => self.j_p = clamped,
+            PersonalityDimension::BigFive(t) => {
+                let mut big_five = self.to_big_five();
+                big_five.set(t, value);
+                (self.e_i, self.s_n, self.t_f, self.j_p) = big_five.to_mbti_axes();
+                self.big_five = Some(big_five);
+                return;
+            }
+        }
+
+        if let Some(big_five) = &self.big_five {
+            let neuroticism = big_five.neuroticism;
+            self.big_five = Some(BigFiveParams::from_mbti_axes(
+                self.e_i,
+                self.s_n,
+                self.t_f,
+                self.j_p,
+                neuroticism,
+            ));
+        }
+    }
+
+    /// 当前人格模型的类型标签（MBTI 如 "INTJ"，大五如 "OcEAn"）
+    pub fn type_label(&self) -> String {
+        match &self.big_five {
+            Some(big_five) => big_five.ocean_label(),
+            None => self.mbti_label(),
         }
     }
 
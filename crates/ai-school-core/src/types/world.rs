@@ -1,3 +1,4 @@
+use chrono::NaiveDateTime;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -34,6 +35,8 @@ pub enum LocationType {
     StudyRoom,
     /// 休息区
     RestArea,
+    /// 教师办公室
+    Office,
 }
 
 /// 校园位置定义
@@ -55,7 +58,7 @@ pub struct Location {
 pub struct SimulationTime {
     /// 学期编号 (从 1 开始)
     pub semester: u32,
-    /// 第几周 (1-20)
+    /// 学期内第几周（超过学期周数即为寒暑假）
     pub week: u32,
     /// 星期几 (1=Monday, 7=Sunday)
     pub day_of_week: u32,
@@ -63,6 +66,44 @@ pub struct SimulationTime {
     pub hour: u32,
     /// 总步数 (全局递增)
     pub tick: u64,
+    /// 当天类型
+    #[serde(default)]
+    pub day_type: DayType,
+    /// 校历换算出的真实日期时间（导出为 ISO 8601）
+    #[serde(default)]
+    pub datetime: Option<NaiveDateTime>,
+    /// 从开学第一天零点起经过的小时数
+    #[serde(default)]
+    pub elapsed_hours: u64,
+}
+
+/// 日期类型
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+pub enum DayType {
+    /// 上课日
+    #[default]
+    SchoolDay,
+    /// 考试日（仍按作息上课铃）
+    ExamDay,
+    Weekend,
+    /// 法定节假日
+    Holiday,
+    /// 寒暑假
+    Vacation,
+}
+
+impl DayType {
+    pub fn label(&self) -> &str {
+        match self {
+            DayType::SchoolDay => "上课日",
+            DayType::ExamDay => "考试日",
+            DayType::Weekend => "周末",
+            DayType::Holiday => "节假日",
+            DayType::Vacation => "假期",
+        }
+    }
 }
 
 impl SimulationTime {
@@ -73,9 +114,17 @@ impl SimulationTime {
             day_of_week: 1,
             hour: 8,
             tick: 0,
+            day_type: DayType::SchoolDay,
+            datetime: None,
+            elapsed_hours: 8,
         }
     }
 
+    /// 是否上课日
+    pub fn is_school_day(&self) -> bool {
+        matches!(self.day_type, DayType::SchoolDay | DayType::ExamDay)
+    }
+
     /// 格式化显示
     pub fn display(&self) -> String {
         let day_name = match self.day_of_week {
@@ -88,18 +137,19 @@ impl SimulationTime {
             7 => "周日",
             _ => "未知",
         };
-        format!(
+        let mut text = format!(
             "第{}学期 第{}周 {} {:02}:00",
             self.semester, self.week, day_name, self.hour
-        )
+        );
+        if let Some(datetime) = self.datetime {
+            text.push_str(&format!(" ({})", datetime.date()));
+        }
+        text
     }
 
-    /// 获取总天数（用于计算时间差）
+    /// 获取总小时数（用于计算时间差）：从开学第一天零点起算
     pub fn total_hours(&self) -> u64 {
-        let semester_hours = (self.semester as u64 - 1) * 20 * 7 * 24;
-        let week_hours = (self.week as u64 - 1) * 7 * 24;
-        let day_hours = (self.day_of_week as u64 - 1) * 24;
-        semester_hours + week_hours + day_hours + self.hour as u64
+        self.elapsed_hours
     }
 }
 
@@ -124,6 +174,18 @@ pub struct Relationship {
     pub last_interaction: Option<SimulationTime>,
 }
 
+/// Agent 在一个仿真步内沿校园图的移动
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AgentMovement {
+    pub agent_id: AgentId,
+    /// 本步途经的位置（含出发点与本步结束时所在位置）
+    pub path: Vec<LocationId>,
+    /// 本步行进的分钟数
+    pub travel_minutes: u32,
+    /// 是否已到达目的地
+    pub arrived: bool,
+}
+
 /// 世界状态快照 — 用于可视化和回放
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorldSnapshot {
@@ -131,6 +193,9 @@ pub struct WorldSnapshot {
     pub agents: Vec<AgentState>,
     pub relationships: Vec<Relationship>,
     pub active_events: Vec<String>,
+    /// 本步发生的移动（供前端动画）
+    #[serde(default)]
+    pub movements: Vec<AgentMovement>,
 }
 
 /// 仿真速度
@@ -11,8 +11,16 @@ pub enum SimulationUpdate {
     /// 仿真步进
     Tick {
         time: SimulationTime,
-        snapshot: WorldSnapshot,
+        snapshot: Box<WorldSnapshot>,
         events: Vec<SimulationEvent>,
+        /// 本步的警告（状态变更失败、叙事-数据不一致等）
+        warnings: Vec<String>,
+    },
+    /// 用户干预（两步之间触发的事件）
+    Intervention {
+        time: SimulationTime,
+        events: Vec<SimulationEvent>,
+        warnings: Vec<String>,
     },
     /// 速度变更
     SpeedChanged { speed: SimulationSpeed },
@@ -1,8 +1,40 @@
 //! ADR-0004 一致性检测
 //!
-//! 叙事 vs 数据变更的一致性校验。
+//! 叙事 vs 数据变更的一致性校验：规则检查 + 可选的 LLM 复核。
 
-use ai_school_core::types::SimulationEvent;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use ai_school_core::traits::llm::{ChatMessage, CompletionRequest, LlmProvider, MessageRole};
+use ai_school_core::types::{EventType, SimulationEvent};
+use ai_school_llm::prompt::PromptEngine;
+
+use ai_school_world::changes::{AgentField, ChangeOp, ChangeTarget, RelationshipField, TypedChange};
+use ai_school_world::state::WorldState;
+
+/// 叙事中表示积极的词
+const POSITIVE_WORDS: &[&str] = &[
+    "开心", "高兴", "愉快", "友好", "融洽", "笑", "感激", "兴奋", "满意", "和好", "鼓励", "表扬", "温暖", "默契",
+];
+/// 叙事中表示消极的词
+const NEGATIVE_WORDS: &[&str] = &[
+    "难过", "生气", "争吵", "吵架", "冲突", "失落", "沮丧", "焦虑", "委屈", "冷淡", "批评", "矛盾", "伤心", "不满",
+];
+
+/// LLM 复核使用的模板
+const NARRATIVE_CHECK: &str = "consistency/narrative_data_check";
+
+fn prompts() -> &'static PromptEngine {
+    static ENGINE: OnceLock<PromptEngine> = OnceLock::new();
+    ENGINE.get_or_init(|| {
+        PromptEngine::from_sources(&[(
+            NARRATIVE_CHECK,
+            include_str!("../../../prompts/consistency/narrative_data_check.j2"),
+        )])
+        .expect("bundled consistency template must compile")
+    })
+}
 
 /// 一致性警告
 #[derive(Debug, Clone)]
@@ -12,6 +44,22 @@ pub struct ConsistencyWarning {
     pub description: String,
 }
 
+impl ConsistencyWarning {
+    /// 数据变更是否与叙事相矛盾（策略只处理这类警告）
+    pub fn contradicts(&self) -> bool {
+        matches!(
+            self.warning_type,
+            WarningType::NarrativeDataMismatch | WarningType::CausalAnomaly
+        )
+    }
+}
+
+impl std::fmt::Display for ConsistencyWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Event {} {:?}: {}", self.event_id, self.warning_type, self.description)
+    }
+}
+
 /// 警告类型
 #[derive(Debug, Clone)]
 pub enum WarningType {
@@ -23,27 +71,264 @@ pub enum WarningType {
     CausalAnomaly,
 }
 
+/// 叙事的情感倾向：正数积极，负数消极，0 为中性或混杂
+fn narrative_tone(narrative: &str) -> i32 {
+    let positive = POSITIVE_WORDS.iter().any(|w| narrative.contains(w));
+    let negative = NEGATIVE_WORDS.iter().any(|w| narrative.contains(w));
+    match (positive, negative) {
+        (true, false) => 1,
+        (false, true) => -1,
+        _ => 0,
+    }
+}
+
 /// 检查事件一致性
-pub fn check_consistency(event: &SimulationEvent) -> Vec<ConsistencyWarning> {
+pub fn check_consistency(event: &SimulationEvent, world: &WorldState) -> Vec<ConsistencyWarning> {
     let mut warnings = Vec::new();
-
-    // 检查叙事与状态变更是否匹配
-    if event.narrative.is_empty() && !event.state_changes.is_empty() {
+    let mut warn = |warning_type: WarningType, description: String| {
         warnings.push(ConsistencyWarning {
             event_id: event.id.to_string(),
-            warning_type: WarningType::NarrativeDataMismatch,
-            description: "有状态变更但无叙事描述".to_string(),
+            warning_type,
+            description,
         });
+    };
+
+    // 检查叙事与状态变更是否匹配
+    if event.narrative.is_empty() && !event.state_changes.is_empty() {
+        warn(WarningType::NarrativeDataMismatch, "有状态变更但无叙事描述".to_string());
     }
 
     // 检查强度与情感变更是否匹配
     if event.intensity > 0.7 && event.state_changes.is_empty() {
-        warnings.push(ConsistencyWarning {
-            event_id: event.id.to_string(),
-            warning_type: WarningType::EmotionIntensityMismatch,
-            description: "高强度事件但无状态变更".to_string(),
-        });
+        warn(WarningType::EmotionIntensityMismatch, "高强度事件但无状态变更".to_string());
+    }
+
+    let tone = narrative_tone(&event.narrative);
+    for change in &event.state_changes {
+        // 无法解析的变更由 apply_state_changes 报告
+        let Ok(TypedChange { target, op }) = TypedChange::parse(change) else {
+            continue;
+        };
+
+        let agents: Vec<&str> = match &target {
+            ChangeTarget::Agent { agent, .. } => vec![agent.as_str()],
+            ChangeTarget::Relationship { agent_a, agent_b, .. } => vec![agent_a.as_str(), agent_b.as_str()],
+        };
+        for key in agents {
+            let involved = world
+                .resolve_agent(key)
+                .is_some_and(|id| event.involved_agents.contains(&id));
+            if !involved {
+                warn(
+                    WarningType::CausalAnomaly,
+                    format!("{} 变更了未参与事件的 {key}", change.target),
+                );
+            }
+        }
+
+        let ChangeOp::Delta(delta) = op else {
+            continue;
+        };
+        let affective = matches!(
+            target,
+            ChangeTarget::Agent { field: AgentField::Valence, .. }
+                | ChangeTarget::Relationship { field: RelationshipField::Closeness | RelationshipField::Trust, .. }
+        );
+        if !affective || delta == 0.0 {
+            continue;
+        }
+
+        if tone != 0 && tone.signum() != delta.signum() as i32 {
+            let description = if tone > 0 { "叙事积极" } else { "叙事消极" };
+            warn(
+                WarningType::NarrativeDataMismatch,
+                format!("{description}，但 {} 变化 {delta:+.2}", change.target),
+            );
+        }
+        let relationship = matches!(target, ChangeTarget::Relationship { .. });
+        if relationship && event.event_type == EventType::Conflict && delta > 0.0 {
+            warn(
+                WarningType::CausalAnomaly,
+                format!("冲突事件中 {} 反而上升 {delta:+.2}", change.target),
+            );
+        }
+        if event.intensity < 0.3 && delta.abs() > 0.3 {
+            warn(
+                WarningType::EmotionIntensityMismatch,
+                format!("低强度事件（{:.2}）中 {} 变化 {delta:+.2} 过大", event.intensity, change.target),
+            );
+        }
     }
 
     warnings
 }
+
+/// LLM 复核结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JudgeVerdict {
+    consistent: bool,
+    #[serde(default)]
+    issues: Vec<String>,
+}
+
+/// LLM 复核叙事与数据变更（调用失败时视为无问题）
+pub async fn llm_judge<L: LlmProvider>(event: &SimulationEvent, llm: &L) -> Vec<ConsistencyWarning> {
+    if event.state_changes.is_empty() {
+        return Vec::new();
+    }
+
+    let context = serde_json::json!({
+        "narrative": event.narrative,
+        "state_changes": event.state_changes,
+    });
+    let content = match prompts().render(NARRATIVE_CHECK, &context) {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::debug!(error = %e, "Failed to render consistency prompt");
+            return Vec::new();
+        }
+    };
+    let request = CompletionRequest {
+        system: "你负责检查仿真事件的叙事描述与数据变更是否一致。".to_string(),
+        messages: vec![ChatMessage {
+            role: MessageRole::User,
+            content,
+        }],
+        temperature: Some(0.0),
+        max_tokens: Some(300),
+    };
+    let schema = serde_json::json!({
+        "type": "object",
+        "properties": {
+            "consistent": {"type": "boolean"},
+            "issues": {"type": "array", "items": {"type": "string"}}
+        },
+        "required": ["consistent"]
+    });
+
+    match llm.complete_structured::<JudgeVerdict>(&request, &schema).await {
+        Ok(verdict) if !verdict.consistent => {
+            let issues = if verdict.issues.is_empty() {
+                vec!["LLM 判定叙事与数据不一致".to_string()]
+            } else {
+                verdict.issues
+            };
+            issues
+                .into_iter()
+                .map(|description| ConsistencyWarning {
+                    event_id: event.id.to_string(),
+                    warning_type: WarningType::NarrativeDataMismatch,
+                    description,
+                })
+                .collect()
+        }
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            tracing::debug!(error = %e, "LLM consistency judge failed");
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_school_agent::builder::AgentBuilder;
+    use ai_school_core::types::{AgentId, ChangeType, EventId, EventTrigger, StateChange};
+
+    fn change(target: &str, value: f64) -> StateChange {
+        StateChange {
+            target: target.to_string(),
+            change_type: ChangeType::Delta,
+            value: value.into(),
+        }
+    }
+
+    fn event(narrative: &str, involved: Vec<AgentId>, state_changes: Vec<StateChange>) -> SimulationEvent {
+        SimulationEvent {
+            id: EventId::new(),
+            event_type: EventType::SocialInteraction,
+            trigger: EventTrigger::AgentAction,
+            timestamp: Default::default(),
+            involved_agents: involved,
+            narrative: narrative.to_string(),
+            state_changes,
+            intensity: 0.5,
+            location: None,
+            causes: Vec::new(),
+        }
+    }
+
+    fn world() -> (WorldState, AgentId, AgentId) {
+        let mut world = WorldState::new(1);
+        let [ming, hong, _] = ["小明", "小红", "小刚"].map(|name| {
+            let agent = AgentBuilder::new().name(name).build(world.clock.current_time());
+            let id = agent.id.clone();
+            world.add_agent(agent);
+            id
+        });
+        (world, ming, hong)
+    }
+
+    fn kinds(warnings: &[ConsistencyWarning]) -> Vec<String> {
+        warnings.iter().map(|w| format!("{:?}", w.warning_type)).collect()
+    }
+
+    #[test]
+    fn test_tone_mismatch() {
+        let (world, ming, hong) = world();
+        let involved = vec![ming, hong];
+
+        let friendly = event(
+            "两人聊得很开心",
+            involved.clone(),
+            vec![change("relationship[小明,小红].closeness", -0.1), change("agent:小明.emotion.valence", 0.1)],
+        );
+        let warnings = check_consistency(&friendly, &world);
+        assert_eq!(kinds(&warnings), vec!["NarrativeDataMismatch"]);
+        assert!(warnings[0].contradicts());
+        assert!(warnings[0].description.contains("relationship[小明,小红].closeness"));
+
+        // 情绪方向一致、或叙事中性时不报警
+        let consistent = event("两人聊得很开心", involved.clone(), vec![change("agent:小红.emotion.valence", 0.2)]);
+        assert!(check_consistency(&consistent, &world).is_empty());
+        let mixed = event("又开心又难过", involved, vec![change("agent:小红.emotion.valence", -0.2)]);
+        assert!(check_consistency(&mixed, &world).is_empty());
+    }
+
+    #[test]
+    fn test_uninvolved_agent() {
+        let (world, ming, hong) = world();
+
+        let outsider = event(
+            "小明和小红一起复习",
+            vec![ming.clone(), hong.clone()],
+            vec![change("agent:小刚.emotion.stress", 0.1), change("relationship[小明,小刚].trust", 0.1)],
+        );
+        let warnings = check_consistency(&outsider, &world);
+        assert_eq!(kinds(&warnings), vec!["CausalAnomaly", "CausalAnomaly"]);
+        assert!(warnings.iter().all(|w| w.description.contains("小刚")));
+
+        // 按 ID 指代参与者同样算参与；不存在的 Agent 也算未参与
+        let by_id = event(
+            "小明和小红一起复习",
+            vec![ming.clone(), hong],
+            vec![change(&format!("agent:{}.emotion.stress", ming.0), 0.1), change("agent:小李.emotion.stress", 0.1)],
+        );
+        let warnings = check_consistency(&by_id, &world);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].description.contains("小李"));
+    }
+
+    #[test]
+    fn test_judge_prompt_renders_changes() {
+        let changes = event("两人吵了一架", Vec::new(), vec![change("relationship[小明,小红].trust", -0.2)]);
+        let context = serde_json::json!({
+            "narrative": changes.narrative,
+            "state_changes": changes.state_changes,
+        });
+        let prompt = prompts().render(NARRATIVE_CHECK, &context).unwrap();
+        assert!(prompt.contains("两人吵了一架"));
+        assert!(prompt.contains("- relationship[小明,小红].trust: Delta -0.2"));
+    }
+}
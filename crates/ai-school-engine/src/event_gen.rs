@@ -3,7 +3,7 @@
 use rand::Rng;
 
 use ai_school_core::types::{
-    AgentId, EventId, EventTrigger, EventType, SimulationEvent, SimulationTime,
+    AgentId, EventId, EventTrigger, EventType, LocationId, SimulationEvent, SimulationTime,
 };
 use ai_school_world::state::WorldState;
 
@@ -24,9 +24,12 @@ impl EventGenerator {
         let mut events = Vec::new();
         let current_time = world.clock.current_time().clone();
 
-        // 检查关系阈值触发
+        // 检查关系阈值触发（原因是两人最近一次共同参与的事件）
         for rel in world.relationships.all_relationships() {
             if rel.closeness < -0.7 {
+                let last_shared = world.event_log.iter().rev().find(|e| {
+                    e.involved_agents.contains(&rel.agent_a) && e.involved_agents.contains(&rel.agent_b)
+                });
                 events.push(SimulationEvent {
                     id: EventId::new(),
                     event_type: EventType::Conflict,
@@ -39,6 +42,9 @@ impl EventGenerator {
                     ),
                     state_changes: Vec::new(),
                     intensity: 0.7,
+                    location: world
+                        .common_location(&[rel.agent_a.clone(), rel.agent_b.clone()]),
+                    causes: last_shared.map(|e| e.id.clone()).into_iter().collect(),
                 });
             }
         }
@@ -50,7 +56,8 @@ impl EventGenerator {
             if !agent_ids.is_empty() {
                 let idx = rng.r#gen::<usize>() % agent_ids.len();
                 let random_agent = agent_ids[idx].clone();
-                events.push(self.generate_random_event(&random_agent, &current_time));
+                let location = world.agents.get(&random_agent).map(|a| a.location.clone());
+                events.push(self.generate_random_event(&random_agent, location, &current_time));
             }
         }
 
@@ -60,6 +67,7 @@ impl EventGenerator {
     fn generate_random_event(
         &self,
         agent_id: &AgentId,
+        location: Option<LocationId>,
         time: &SimulationTime,
     ) -> SimulationEvent {
         let mut rng = rand::thread_rng();
@@ -83,6 +91,8 @@ impl EventGenerator {
             narrative: narrative.to_string(),
             state_changes: Vec::new(),
             intensity: 0.3,
+            location,
+            causes: Vec::new(),
         }
     }
 }
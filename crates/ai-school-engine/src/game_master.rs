@@ -1,13 +1,19 @@
 //! Game Master 仲裁层 — ADR-0003 LLM 调用点 #2
 //!
 //! 验证行为合理性、仲裁多 Agent 交互、翻译自然语言→结构化 StateChange。
+//! 意图按地点和交互对象聚成场景，各场景独立（并发）仲裁。
 
+use std::collections::{HashMap, VecDeque};
+
+use futures::future::join_all;
 use serde::{Deserialize, Serialize};
 
 use ai_school_core::error::SimulationError;
 use ai_school_core::traits::llm::{ChatMessage, CompletionRequest, LlmProvider, MessageRole};
-use ai_school_core::types::{BehaviorIntent, EventType, StateChange};
+use ai_school_core::types::{AgentId, BehaviorIntent, EventType, LocationId, SimulationTime, StateChange};
 
+use ai_school_world::changes::{state_changes_schema, target_grammar};
+use ai_school_world::relationships::relationship_description;
 use ai_school_world::state::WorldState;
 
 /// Game Master 仲裁输出
@@ -19,18 +25,235 @@ pub struct GameMasterOutput {
     pub narrative: String,
 }
 
+/// 场景：同一地点或彼此有交互的 Agent 的意图
+#[derive(Debug, Clone)]
+pub struct Scene {
+    /// 场景地点（跨地点的交互为 None）
+    pub location: Option<LocationId>,
+    pub intents: Vec<BehaviorIntent>,
+}
+
+impl Scene {
+    pub fn agents(&self) -> Vec<AgentId> {
+        self.intents.iter().map(|i| i.agent_id.clone()).collect()
+    }
+}
+
+/// 把意图聚成场景：同一地点的 Agent 同场，意图指向的交互对象也并入同一场景
+pub fn cluster_scenes(intents: &[BehaviorIntent], world: &WorldState) -> Vec<Scene> {
+    let index: HashMap<&AgentId, usize> = intents
+        .iter()
+        .enumerate()
+        .map(|(i, intent)| (&intent.agent_id, i))
+        .collect();
+    let mut parent: Vec<usize> = (0..intents.len()).collect();
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[i] = root;
+        root
+    }
+    fn union(parent: &mut [usize], a: usize, b: usize) {
+        let (ra, rb) = (find(parent, a), find(parent, b));
+        // 以先出现的意图为根，保持场景顺序稳定
+        parent[ra.max(rb)] = ra.min(rb);
+    }
+
+    let mut by_location: HashMap<&LocationId, usize> = HashMap::new();
+    for (i, intent) in intents.iter().enumerate() {
+        if let Some(agent) = world.agents.get(&intent.agent_id) {
+            let first = *by_location.entry(&agent.location).or_insert(i);
+            union(&mut parent, first, i);
+        }
+        for target in &intent.target_agents {
+            if let Some(&j) = index.get(target) {
+                union(&mut parent, i, j);
+            }
+        }
+    }
+
+    let mut scenes: Vec<(usize, Vec<BehaviorIntent>)> = Vec::new();
+    for (i, intent) in intents.iter().enumerate() {
+        let root = find(&mut parent, i);
+        match scenes.iter_mut().find(|(r, _)| *r == root) {
+            Some((_, scene)) => scene.push(intent.clone()),
+            None => scenes.push((root, vec![intent.clone()])),
+        }
+    }
+    scenes
+        .into_iter()
+        .map(|(_, intents)| {
+            let agents: Vec<AgentId> = intents.iter().map(|i| i.agent_id.clone()).collect();
+            Scene {
+                location: world.common_location(&agents),
+                intents,
+            }
+        })
+        .collect()
+}
+
+/// 剧情记忆中的一个已仲裁场景
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoryEntry {
+    pub time: SimulationTime,
+    pub location: Option<LocationId>,
+    pub agents: Vec<AgentId>,
+    pub narrative: String,
+}
+
 /// Game Master
 pub struct GameMaster {
     /// 是否使用 LLM 进行仲裁（关闭时使用简单规则）
     use_llm: bool,
+    /// 最近仲裁过的场景，保证叙事前后连贯
+    story: VecDeque<StoryEntry>,
+    /// 剧情记忆保留的场景数
+    pub story_capacity: usize,
+    /// 每个场景提示中附带的前情条数
+    pub recap_limit: usize,
 }
 
 impl GameMaster {
     pub fn new() -> Self {
-        Self { use_llm: true }
+        Self {
+            use_llm: true,
+            story: VecDeque::new(),
+            story_capacity: 50,
+            recap_limit: 5,
+        }
+    }
+
+    /// 切换 LLM 仲裁（关闭后所有场景按简单规则仲裁）
+    pub fn set_use_llm(&mut self, use_llm: bool) {
+        self.use_llm = use_llm;
+    }
+
+    /// 记住一个已仲裁的场景（独处的日常和空叙事不记）
+    pub fn remember(&mut self, scene: &Scene, narrative: &str, time: &SimulationTime) {
+        if narrative.is_empty() || scene.intents.len() < 2 {
+            return;
+        }
+        self.story.push_back(StoryEntry {
+            time: time.clone(),
+            location: scene.location.clone(),
+            agents: scene.agents(),
+            narrative: narrative.to_string(),
+        });
+        while self.story.len() > self.story_capacity {
+            self.story.pop_front();
+        }
+    }
+
+    /// 剧情记忆（从旧到新）
+    pub fn story(&self) -> impl Iterator<Item = &StoryEntry> {
+        self.story.iter()
+    }
+
+    /// 与场景有关的前情：涉及在场人物或发生在同一地点，从旧到新
+    fn recap(&self, agents: &[AgentId], location: Option<&LocationId>) -> Vec<&StoryEntry> {
+        let mut recap: Vec<&StoryEntry> = self
+            .story
+            .iter()
+            .rev()
+            .filter(|e| {
+                e.agents.iter().any(|a| agents.contains(a))
+                    || (location.is_some() && e.location.as_ref() == location)
+            })
+            .take(self.recap_limit)
+            .collect();
+        recap.reverse();
+        recap
     }
 
-    /// 仲裁 Agent 行为意图
+    /// 场景的世界摘要：在场人物、彼此关系、前情提要与可用的目标名字
+    fn scene_context(&self, agents: &[AgentId], world: &WorldState) -> String {
+        let location = world.common_location(agents);
+        let mut people = Vec::new();
+        for agent in agents.iter().filter_map(|id| world.agents.get(id)) {
+            let mut line = format!(
+                "- {}（{}，在{}）：{}；心情{}（效价 {:.2}，压力 {:.2}）",
+                agent.config.name,
+                agent.config.role.title(),
+                world.location_name(&agent.location),
+                world.describe_activity(&agent.activity),
+                agent.emotion.mood_label(),
+                agent.emotion.valence,
+                agent.emotion.stress,
+            );
+            if let Some(thought) = &agent.current_thought {
+                line.push_str(&format!("；在想：{thought}"));
+            }
+            people.push(line);
+        }
+
+        let mut relations = Vec::new();
+        for (i, a) in agents.iter().enumerate() {
+            for b in &agents[i + 1..] {
+                let Some(rel) = world.relationships.get(a, b) else {
+                    continue;
+                };
+                relations.push(format!(
+                    "- {} ↔ {}：{}（亲密度 {:.2}，信任度 {:.2}）",
+                    world.agent_name(a),
+                    world.agent_name(b),
+                    relationship_description(rel),
+                    rel.closeness,
+                    rel.trust,
+                ));
+            }
+        }
+
+        let recap: Vec<String> = self
+            .recap(agents, location.as_ref())
+            .into_iter()
+            .map(|e| format!("- [{}] {}", e.time.display(), e.narrative))
+            .collect();
+
+        let names: Vec<&str> = agents.iter().map(|id| world.agent_name(id)).collect();
+        let mut sections = vec![format!("在场人物:\n{}", people.join("\n"))];
+        if !relations.is_empty() {
+            sections.push(format!("他们之间的关系:\n{}", relations.join("\n")));
+        }
+        if !recap.is_empty() {
+            sections.push(format!("前情提要:\n{}", recap.join("\n")));
+        }
+        sections.push(format!(
+            "state_changes 只能针对在场人物：{}（target 中直接使用这些名字）",
+            names.join("、")
+        ));
+        sections.join("\n\n")
+    }
+
+    /// 逐场景并发仲裁，输出与场景一一对应
+    pub async fn arbitrate_scenes<L: LlmProvider>(
+        &self,
+        scenes: &[Scene],
+        world: &WorldState,
+        llm: &L,
+    ) -> Result<Vec<GameMasterOutput>, SimulationError> {
+        join_all(scenes.iter().map(|scene| self.arbitrate(&scene.intents, world, llm)))
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    /// 带着上一次仲裁的问题重新仲裁一个场景
+    pub async fn rearbitrate<L: LlmProvider>(
+        &self,
+        scene: &Scene,
+        issues: &[String],
+        world: &WorldState,
+        llm: &L,
+    ) -> Result<GameMasterOutput, SimulationError> {
+        if !self.use_llm || scene.intents.len() < 2 {
+            return self.arbitrate(&scene.intents, world, llm).await;
+        }
+        self.llm_arbitrate(&scene.intents, world, llm, issues).await
+    }
+
+    /// 仲裁一个场景的行为意图（独处的 Agent 按规则处理，不调用 LLM）
     pub async fn arbitrate<L: LlmProvider>(
         &self,
         intents: &[BehaviorIntent],
@@ -47,7 +270,7 @@ impl GameMaster {
         }
 
         if self.use_llm && intents.len() > 1 {
-            self.llm_arbitrate(intents, world, llm).await
+            self.llm_arbitrate(intents, world, llm, &[]).await
         } else {
             Ok(self.simple_arbitrate(intents, world))
         }
@@ -104,43 +327,68 @@ impl GameMaster {
         intents: &[BehaviorIntent],
         world: &WorldState,
         llm: &L,
+        issues: &[String],
     ) -> Result<GameMasterOutput, SimulationError> {
         let time_desc = world.clock.current_time().display();
+        let agents: Vec<AgentId> = intents.iter().map(|i| i.agent_id.clone()).collect();
+        let location_desc = world
+            .common_location(&agents)
+            .and_then(|id| world.get_location(&id).ok().map(|l| l.name.clone()))
+            .unwrap_or_else(|| "多个地点".to_string());
 
         let intents_desc: String = intents
             .iter()
-            .map(|i| {
-                let name = world
-                    .agents
-                    .get(&i.agent_id)
-                    .map(|a| a.config.name.as_str())
-                    .unwrap_or("未知");
-                format!("- {name}: {}", i.description)
-            })
+            .map(|i| format!("- {}: {}", world.agent_name(&i.agent_id), i.description))
             .collect::<Vec<_>>()
             .join("\n");
+        let context = self.scene_context(&agents, world);
 
-        let system = r#"你是 AI School 的 Game Master（游戏主持人）。你的职责是：
+        let system = format!(
+            r#"你是 AI School 的 Game Master（游戏主持人）。你的职责是：
 1. 评估所有 Agent 的行为意图是否合理
 2. 当多个 Agent 的行为产生交互时，仲裁结果
 3. 将结果翻译为结构化的状态变更
 
 请以 JSON 格式输出：
-{
+{{
   "event_type": "Routine|SocialInteraction|Academic|Conflict|Cooperation|SpecialEvent",
   "intensity": 0.0-1.0,
   "state_changes": [
-    {"target": "agent:名字.emotion.valence", "change_type": "Delta", "value": -0.1}
+    {{"target": "agent:名字.emotion.valence", "change_type": "Delta", "value": -0.1}},
+    {{"target": "relationship[名字A,名字B].closeness", "change_type": "Delta", "value": 0.05}},
+    {{"target": "agent:名字.location", "change_type": "Set", "value": "library"}}
   ],
   "narrative": "描述发生了什么（1-2句话）"
-}"#;
+}}
 
-        let user_msg = format!(
-            "当前时间: {time_desc}\n\nAgent 行为意图:\n{intents_desc}\n\n请仲裁这些行为的结果。"
+state_changes 的 target 只能使用以下字段（Delta 为增量，Set 为设定值，Append 为追加）：
+{}
+
+叙事要与前情提要保持一致，不要编造不在场的人物。
+location 变更的 value 必须是"可前往的位置"中列出的位置 ID。"#,
+            target_grammar()
         );
 
+        let locations_desc: String = world
+            .available_locations()
+            .iter()
+            .map(|l| format!("- {}（{}，{}/{} 人）", l.id, l.name, world.occupancy(&l.id), l.capacity))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut user_msg = format!(
+            "当前时间: {time_desc}\n地点: {location_desc}\n\n{context}\n\nAgent 行为意图:\n{intents_desc}\n\n可前往的位置（未满）:\n{locations_desc}\n\n请仲裁这些行为的结果。"
+        );
+        if !issues.is_empty() {
+            let issues: Vec<String> = issues.iter().map(|i| format!("- {i}")).collect();
+            user_msg.push_str(&format!(
+                "\n\n上一次仲裁的叙事与状态变更不一致，请修正：\n{}",
+                issues.join("\n")
+            ));
+        }
+
         let request = CompletionRequest {
-            system: system.to_string(),
+            system,
             messages: vec![ChatMessage {
                 role: MessageRole::User,
                 content: user_msg,
@@ -154,17 +402,7 @@ impl GameMaster {
             "properties": {
                 "event_type": {"type": "string"},
                 "intensity": {"type": "number"},
-                "state_changes": {
-                    "type": "array",
-                    "items": {
-                        "type": "object",
-                        "properties": {
-                            "target": {"type": "string"},
-                            "change_type": {"type": "string"},
-                            "value": {}
-                        }
-                    }
-                },
+                "state_changes": state_changes_schema(),
                 "narrative": {"type": "string"}
             },
             "required": ["event_type", "intensity", "narrative"]
@@ -185,3 +423,72 @@ impl Default for GameMaster {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_school_agent::builder::AgentBuilder;
+    use ai_school_core::types::IntentType;
+
+    fn place(world: &mut WorldState, name: &str, location: &str) -> AgentId {
+        let mut agent = AgentBuilder::new().name(name).build(world.clock.current_time());
+        agent.location = LocationId(location.to_string());
+        let id = agent.id.clone();
+        world.add_agent(agent);
+        id
+    }
+
+    fn intent(agent: &AgentId, targets: &[&AgentId]) -> BehaviorIntent {
+        BehaviorIntent {
+            agent_id: agent.clone(),
+            description: String::new(),
+            target_location: None,
+            target_agents: targets.iter().map(|t| (*t).clone()).collect(),
+            intent_type: if targets.is_empty() { IntentType::Rest } else { IntentType::Talk },
+        }
+    }
+
+    #[test]
+    fn test_cluster_scenes_by_location_and_target() {
+        let mut world = WorldState::new(1);
+        let a = place(&mut world, "甲", "library");
+        let b = place(&mut world, "乙", "library");
+        let c = place(&mut world, "丙", "playground");
+        let d = place(&mut world, "丁", "cafeteria");
+        let e = place(&mut world, "戊", "dormitory");
+
+        // 甲乙同在图书馆；丁隔着地点找丙说话；戊独处
+        let intents = vec![
+            intent(&a, &[]),
+            intent(&c, &[]),
+            intent(&b, &[]),
+            intent(&d, &[&c]),
+            intent(&e, &[]),
+        ];
+        let scenes = cluster_scenes(&intents, &world);
+
+        let agents: Vec<Vec<AgentId>> = scenes.iter().map(|s| s.agents()).collect();
+        assert_eq!(agents, vec![vec![a, b], vec![c, d], vec![e]]);
+        assert_eq!(scenes[0].location, Some(LocationId("library".to_string())));
+        assert_eq!(scenes[1].location, None);
+        assert_eq!(scenes[2].location, Some(LocationId("dormitory".to_string())));
+    }
+
+    #[test]
+    fn test_cluster_scenes_merges_transitively() {
+        let mut world = WorldState::new(1);
+        let a = place(&mut world, "甲", "library");
+        let b = place(&mut world, "乙", "playground");
+        let c = place(&mut world, "丙", "playground");
+        let d = place(&mut world, "丁", "cafeteria");
+
+        // 甲找乙，乙与丙同场，丁找甲：四人合为一个场景；不在本轮的交互对象被忽略
+        let absent = AgentId::new();
+        let intents = vec![intent(&a, &[&b]), intent(&c, &[]), intent(&b, &[]), intent(&d, &[&a, &absent])];
+        let scenes = cluster_scenes(&intents, &world);
+
+        assert_eq!(scenes.len(), 1);
+        assert_eq!(scenes[0].agents(), vec![a, c, b, d]);
+        assert_eq!(scenes[0].location, None);
+    }
+}
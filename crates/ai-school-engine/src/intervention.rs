@@ -2,10 +2,13 @@
 
 use serde::{Deserialize, Serialize};
 
+use ai_school_core::config::SimulationConfig;
+use ai_school_core::error::SimulationError;
 use ai_school_core::types::{
-    AgentId, EventId, EventTrigger, EventType, PresetEvent,
+    AgentId, ChangeType, EventId, EventScope, EventTrigger, EventType, PresetEvent,
     SimulationEvent, SimulationTime, StateChange,
 };
+use ai_school_world::state::WorldState;
 
 /// 干预类型
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,6 +43,28 @@ pub enum EnvironmentParameter {
     RandomEventFrequency,
 }
 
+impl EnvironmentParameter {
+    /// 按名称解析（如 `CourseDifficulty`）
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "CourseDifficulty" => Some(Self::CourseDifficulty),
+            "SocialDensity" => Some(Self::SocialDensity),
+            "CompetitivePressure" => Some(Self::CompetitivePressure),
+            "RandomEventFrequency" => Some(Self::RandomEventFrequency),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::CourseDifficulty => "课程难度",
+            Self::SocialDensity => "社交密度",
+            Self::CompetitivePressure => "竞争压力",
+            Self::RandomEventFrequency => "随机事件频率",
+        }
+    }
+}
+
 /// 干预日志记录
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InterventionLog {
@@ -48,6 +73,9 @@ pub struct InterventionLog {
     pub intervention_type: InterventionType,
     pub affected_agents: Vec<AgentId>,
     pub description: String,
+    /// 计划干预触发时满足的条件（手动触发为 None）
+    #[serde(default)]
+    pub condition: Option<String>,
 }
 
 /// 干预管理器
@@ -60,107 +88,158 @@ impl InterventionManager {
         Self { logs: Vec::new() }
     }
 
-    /// 应用参数调整
+    /// 应用参数调整 (0.0 ~ 1.0) 并记录，返回调整前的取值
+    ///
+    /// 环境参数写入世界状态，随机事件频率写入仿真配置。
     pub fn apply_parameter_change(
         &mut self,
         parameter: &EnvironmentParameter,
         value: f32,
-        timestamp: &SimulationTime,
-    ) -> Vec<StateChange> {
-        let log = InterventionLog {
+        world: &mut WorldState,
+        config: &mut SimulationConfig,
+    ) -> Result<f32, SimulationError> {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(SimulationError::Intervention(format!(
+                "{} must be between 0 and 1, got {value}",
+                parameter.label()
+            )));
+        }
+
+        let mut params = world.environment;
+        let slot = match parameter {
+            EnvironmentParameter::CourseDifficulty => &mut params.course_difficulty,
+            EnvironmentParameter::SocialDensity => &mut params.social_density,
+            EnvironmentParameter::CompetitivePressure => &mut params.competitive_pressure,
+            EnvironmentParameter::RandomEventFrequency => &mut config.random_event_frequency,
+        };
+        let previous = std::mem::replace(slot, value);
+        if params != world.environment {
+            world.set_environment(params);
+        }
+
+        let timestamp = world.clock.current_time().clone();
+        self.logs.push(InterventionLog {
             id: EventId::new(),
-            timestamp: timestamp.clone(),
+            timestamp,
             intervention_type: InterventionType::ParameterChange {
                 parameter: parameter.clone(),
                 value,
             },
             affected_agents: Vec::new(),
-            description: format!("参数调整: {:?} = {value}", parameter),
-        };
-        self.logs.push(log);
-
-        // Return state changes based on parameter type
-        match parameter {
-            EnvironmentParameter::CourseDifficulty => {
-                // Modify all subjects' difficulty
-                vec![] // TODO: implement
-            }
-            _ => vec![],
-        }
+            description: format!("参数调整: {} {previous:.2} → {value:.2}", parameter.label()),
+            condition: None,
+        });
+        Ok(previous)
     }
 
-    /// 创建预设事件的仿真事件
+    /// 创建预设事件的仿真事件：`affected` 为受影响的 Agent，状态变更随事件给出
+    ///
+    /// `condition` 为计划干预触发时满足的条件。
     pub fn trigger_preset_event(
         &mut self,
         event: &PresetEvent,
+        affected: Vec<AgentId>,
+        condition: Option<String>,
+        world: &WorldState,
         timestamp: &SimulationTime,
     ) -> SimulationEvent {
-        let (narrative, involved, event_type) = match event {
+        let name = |id: &AgentId| world.agent_name(id).to_string();
+        let (narrative, event_type, location) = match event {
             PresetEvent::MidtermExam => (
                 "期中考试开始了！所有同学紧张地准备着。".to_string(),
-                vec![],
                 EventType::Academic,
+                None,
             ),
             PresetEvent::ClubRecruitment => (
                 "社团招新活动开始了，各个社团在操场设立了展位。".to_string(),
-                vec![],
                 EventType::SpecialEvent,
+                None,
             ),
             PresetEvent::SportsMeet => (
                 "学校运动会拉开帷幕！同学们热情高涨。".to_string(),
-                vec![],
                 EventType::SpecialEvent,
+                None,
             ),
             PresetEvent::FriendshipConflict { agent_a, agent_b } => (
-                "两位同学之间产生了矛盾。".to_string(),
-                vec![agent_a.clone(), agent_b.clone()],
+                format!("{}和{}之间产生了矛盾。", name(agent_a), name(agent_b)),
                 EventType::Conflict,
+                world.common_location(&[agent_a.clone(), agent_b.clone()]),
             ),
             PresetEvent::TeacherPraise { target } => (
-                "老师在全班面前表扬了一位同学。".to_string(),
-                vec![target.clone()],
+                format!("老师在全班面前表扬了{}。", name(target)),
                 EventType::Academic,
+                None,
             ),
             PresetEvent::TeacherCriticism { target } => (
-                "老师批评了一位同学的表现。".to_string(),
-                vec![target.clone()],
+                format!("老师批评了{}的表现。", name(target)),
                 EventType::Academic,
+                None,
             ),
             PresetEvent::NewStudent { name } => (
                 format!("班级来了一位新同学：{name}。"),
-                vec![],
                 EventType::SpecialEvent,
+                None,
             ),
-            PresetEvent::Custom { description, .. } => (
+            PresetEvent::Custom { description, scope } => (
                 description.clone(),
-                vec![],
                 EventType::SpecialEvent,
+                match scope {
+                    EventScope::Location(location) => Some(location.clone()),
+                    _ => None,
+                },
             ),
         };
 
+        let id = EventId::new();
         let log = InterventionLog {
-            id: EventId::new(),
+            id: id.clone(),
             timestamp: timestamp.clone(),
             intervention_type: InterventionType::EventTriggerIntervention {
                 event: event.clone(),
             },
-            affected_agents: involved.clone(),
+            affected_agents: affected.clone(),
             description: narrative.clone(),
+            condition,
         };
         self.logs.push(log);
 
         SimulationEvent {
-            id: EventId::new(),
+            id,
             event_type,
             trigger: EventTrigger::UserIntervention,
             timestamp: timestamp.clone(),
-            involved_agents: involved,
+            state_changes: preset_effects(event, &affected),
+            involved_agents: affected,
             narrative,
-            state_changes: Vec::new(),
             intensity: 0.6,
+            location,
+            causes: Vec::new(),
         }
     }
 
+    /// 记录一次以角色身份进行的对话干预
+    pub fn record_chat(
+        &mut self,
+        agent_id: &AgentId,
+        role: &str,
+        message: &str,
+        condition: Option<String>,
+        timestamp: &SimulationTime,
+    ) {
+        self.logs.push(InterventionLog {
+            id: EventId::new(),
+            timestamp: timestamp.clone(),
+            intervention_type: InterventionType::Chat {
+                agent_id: agent_id.clone(),
+                role: role.to_string(),
+                message: message.to_string(),
+            },
+            affected_agents: vec![agent_id.clone()],
+            description: message.to_string(),
+            condition,
+        });
+    }
+
     /// 导出干预日志
     pub fn export_logs(&self) -> serde_json::Value {
         serde_json::json!(self.logs)
@@ -172,3 +251,179 @@ impl Default for InterventionManager {
         Self::new()
     }
 }
+
+/// 预设事件的影响范围（新同学转入时受影响的是新同学及其同班同学，由调用方确定）
+pub fn preset_scope(event: &PresetEvent) -> EventScope {
+    match event {
+        PresetEvent::MidtermExam
+        | PresetEvent::ClubRecruitment
+        | PresetEvent::SportsMeet
+        | PresetEvent::NewStudent { .. } => EventScope::Global,
+        PresetEvent::FriendshipConflict { agent_a, agent_b } => {
+            EventScope::Agents(vec![agent_a.clone(), agent_b.clone()])
+        }
+        PresetEvent::TeacherPraise { target } | PresetEvent::TeacherCriticism { target } => {
+            EventScope::Agents(vec![target.clone()])
+        }
+        PresetEvent::Custom { scope, .. } => scope.clone(),
+    }
+}
+
+/// 预设事件对受影响 Agent 的状态变更
+///
+/// 自定义事件不给出变更，由情绪评估按事件强度产生反应；新同学转入只影响新同学本人（`affected` 的第一个）。
+pub fn preset_effects(event: &PresetEvent, affected: &[AgentId]) -> Vec<StateChange> {
+    let delta = |agent: &AgentId, field: &str, value: f32| StateChange {
+        target: format!("agent:{agent}.{field}"),
+        change_type: ChangeType::Delta,
+        value: serde_json::json!(value),
+    };
+    let each = |effects: &[(&str, f32)]| -> Vec<StateChange> {
+        affected
+            .iter()
+            .flat_map(|agent| effects.iter().map(|(field, value)| delta(agent, field, *value)))
+            .collect()
+    };
+
+    match event {
+        PresetEvent::MidtermExam => each(&[("emotion.stress", 0.15), ("emotion.arousal", 0.1)]),
+        PresetEvent::ClubRecruitment => each(&[("emotion.arousal", 0.1), ("emotion.valence", 0.05)]),
+        PresetEvent::SportsMeet => each(&[
+            ("emotion.valence", 0.15),
+            ("emotion.arousal", 0.2),
+            ("emotion.stress", -0.1),
+        ]),
+        PresetEvent::FriendshipConflict { agent_a, agent_b } => {
+            let mut changes = each(&[("emotion.valence", -0.2), ("emotion.stress", 0.1)]);
+            let relationship = |field: &str, value: f32| StateChange {
+                target: format!("relationship[{agent_a},{agent_b}].{field}"),
+                change_type: ChangeType::Delta,
+                value: serde_json::json!(value),
+            };
+            changes.push(relationship("closeness", -0.2));
+            changes.push(relationship("trust", -0.1));
+            changes
+        }
+        PresetEvent::TeacherPraise { .. } => each(&[
+            ("emotion.valence", 0.2),
+            ("emotion.stress", -0.05),
+            ("ability.academic", 0.01),
+        ]),
+        PresetEvent::TeacherCriticism { .. } => each(&[("emotion.valence", -0.2), ("emotion.stress", 0.15)]),
+        PresetEvent::NewStudent { .. } => affected
+            .first()
+            .map(|student| {
+                vec![
+                    delta(student, "emotion.stress", 0.2),
+                    delta(student, "emotion.arousal", 0.2),
+                ]
+            })
+            .unwrap_or_default(),
+        PresetEvent::Custom { .. } => Vec::new(),
+    }
+}
+
+/// 预设事件执行结果
+#[derive(Debug, Default)]
+pub struct InterventionOutcome {
+    /// 干预事件及其引发的后续事件（考试成绩公布、入社等）
+    pub events: Vec<SimulationEvent>,
+    /// 状态变更失败等警告
+    pub warnings: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_school_core::types::LocationId;
+
+    fn targets(changes: &[StateChange]) -> Vec<&str> {
+        changes.iter().map(|c| c.target.as_str()).collect()
+    }
+
+    #[test]
+    fn test_preset_scope() {
+        let (a, b) = (AgentId::new(), AgentId::new());
+        assert!(matches!(preset_scope(&PresetEvent::MidtermExam), EventScope::Global));
+        assert!(matches!(
+            preset_scope(&PresetEvent::NewStudent { name: "小新".to_string() }),
+            EventScope::Global
+        ));
+        let conflict = PresetEvent::FriendshipConflict { agent_a: a.clone(), agent_b: b.clone() };
+        assert!(matches!(preset_scope(&conflict), EventScope::Agents(ids) if ids == vec![a.clone(), b]));
+        let praise = PresetEvent::TeacherPraise { target: a.clone() };
+        assert!(matches!(preset_scope(&praise), EventScope::Agents(ids) if ids == vec![a]));
+        let custom = PresetEvent::Custom {
+            description: "停电".to_string(),
+            scope: EventScope::Location(LocationId("library".to_string())),
+        };
+        assert!(matches!(preset_scope(&custom), EventScope::Location(l) if l.0 == "library"));
+    }
+
+    #[test]
+    fn test_course_difficulty_stacks_on_subject_modifier() {
+        let mut scenario = ai_school_world::scenario::Scenario::bundled();
+        scenario.subjects[0].difficulty_modifier = 0.1;
+        let mut world = WorldState::from_scenario(&scenario, 1).unwrap();
+        let mut config = SimulationConfig::default();
+        let mut manager = InterventionManager::new();
+        let modifier = |world: &WorldState, i: usize| world.subjects[i].difficulty_modifier;
+
+        // 其他参数的调整不碰学科难度
+        manager
+            .apply_parameter_change(&EnvironmentParameter::SocialDensity, 0.9, &mut world, &mut config)
+            .unwrap();
+        assert_eq!(modifier(&world, 0), 0.1);
+        assert_eq!(modifier(&world, 1), 0.0);
+
+        manager
+            .apply_parameter_change(&EnvironmentParameter::CourseDifficulty, 1.0, &mut world, &mut config)
+            .unwrap();
+        assert!((modifier(&world, 0) - 0.4).abs() < 1e-6);
+        assert!((modifier(&world, 1) - 0.3).abs() < 1e-6);
+        world.record_network_metrics();
+        world.clock.advance();
+
+        manager
+            .apply_parameter_change(&EnvironmentParameter::CourseDifficulty, 0.5, &mut world, &mut config)
+            .unwrap();
+        assert!((modifier(&world, 0) - 0.1).abs() < 1e-6);
+
+        // 导出的网络时间序列附带当时的环境参数
+        world.record_network_metrics();
+        let timeline = world.network_timeline();
+        assert_eq!(timeline.len(), 2);
+        assert_eq!(timeline[0].environment.course_difficulty, 1.0);
+        assert_eq!(timeline[1].environment.course_difficulty, 0.5);
+    }
+
+    #[test]
+    fn test_preset_effects() {
+        let (a, b) = (AgentId::new(), AgentId::new());
+        let both = [a.clone(), b.clone()];
+
+        let exam = preset_effects(&PresetEvent::MidtermExam, &both);
+        assert_eq!(exam.len(), 4);
+        assert!(exam.iter().all(|c| c.change_type == ChangeType::Delta));
+        assert!(targets(&exam).contains(&format!("agent:{b}.emotion.stress").as_str()));
+
+        // 冲突双方各自情绪受挫，外加一对关系变更
+        let conflict = PresetEvent::FriendshipConflict { agent_a: a.clone(), agent_b: b.clone() };
+        let changes = preset_effects(&conflict, &both);
+        assert_eq!(changes.len(), 6);
+        let closeness = changes
+            .iter()
+            .find(|c| c.target == format!("relationship[{a},{b}].closeness"))
+            .unwrap();
+        assert_eq!(closeness.value, serde_json::json!(-0.2f32));
+
+        // 新同学转入只影响名单中的第一个
+        let arrival = preset_effects(&PresetEvent::NewStudent { name: "小新".to_string() }, &both);
+        let expected = [format!("agent:{a}.emotion.stress"), format!("agent:{a}.emotion.arousal")];
+        assert_eq!(targets(&arrival), expected.iter().map(String::as_str).collect::<Vec<_>>());
+        assert!(preset_effects(&PresetEvent::NewStudent { name: "小新".to_string() }, &[]).is_empty());
+
+        let custom = PresetEvent::Custom { description: "停电".to_string(), scope: EventScope::Global };
+        assert!(preset_effects(&custom, &both).is_empty());
+    }
+}
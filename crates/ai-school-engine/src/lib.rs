@@ -3,5 +3,6 @@ pub mod consistency;
 pub mod event_gen;
 pub mod game_master;
 pub mod intervention;
+pub mod scheduler;
 pub mod simulation;
 pub mod snapshot;
@@ -0,0 +1,597 @@
+//! 计划干预
+//!
+//! 按时间或条件触发的干预：如“第 3 周周五 14:00 举办运动会”、
+//! “任一学生压力持续 24 小时高于 0.8 时安排辅导员谈心”。仿真每步检查一次。
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use ai_school_core::types::{AgentId, PresetEvent, SimulationTime};
+use ai_school_world::changes::{AgentField, ValueKind, FIELDS};
+use ai_school_world::state::WorldState;
+
+/// 数值比较方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Comparison {
+    Above,
+    Below,
+}
+
+impl Comparison {
+    fn holds(self, value: f32, threshold: f32) -> bool {
+        match self {
+            Comparison::Above => value > threshold,
+            Comparison::Below => value < threshold,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Comparison::Above => ">",
+            Comparison::Below => "<",
+        }
+    }
+}
+
+/// 触发条件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum TriggerCondition {
+    /// 到达某一时刻（只触发一次）
+    At {
+        #[serde(default = "first_semester")]
+        semester: u32,
+        week: u32,
+        /// 星期几 (1-7)
+        day_of_week: u32,
+        hour: u32,
+    },
+    /// Agent 的数值字段（如 `emotion.stress`）持续越过阈值；未指定 Agent 时检查所有学生
+    AgentState {
+        field: String,
+        comparison: Comparison,
+        threshold: f32,
+        #[serde(default)]
+        for_hours: u32,
+        #[serde(default)]
+        agent: Option<AgentId>,
+    },
+}
+
+fn first_semester() -> u32 {
+    1
+}
+
+const WEEKDAYS: [&str; 7] = ["一", "二", "三", "四", "五", "六", "日"];
+
+impl TriggerCondition {
+    /// 条件描述，`agents` 为满足条件的 Agent
+    pub fn describe(&self, agents: &[AgentId], world: &WorldState) -> String {
+        match self {
+            TriggerCondition::At {
+                semester,
+                week,
+                day_of_week,
+                hour,
+            } => {
+                let day = WEEKDAYS.get(*day_of_week as usize - 1).unwrap_or(&"?");
+                format!("第{semester}学期第{week}周周{day} {hour:02}:00")
+            }
+            TriggerCondition::AgentState {
+                field,
+                comparison,
+                threshold,
+                for_hours,
+                ..
+            } => {
+                let names: Vec<&str> = agents.iter().map(|id| world.agent_name(id)).collect();
+                format!(
+                    "{field} {} {threshold} 持续 {for_hours} 小时：{}",
+                    comparison.symbol(),
+                    names.join("、")
+                )
+            }
+        }
+    }
+}
+
+/// 触发后执行的动作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ScheduledAction {
+    /// 预设事件
+    Preset { event: PresetEvent },
+    /// 辅导员找学生谈心：指定的学生与满足条件的学生
+    CounselorChat {
+        #[serde(default)]
+        agent: Option<AgentId>,
+    },
+}
+
+/// 计划状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScheduleStatus {
+    Pending,
+    Fired,
+    Cancelled,
+}
+
+/// 一次触发记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FiredRecord {
+    pub timestamp: SimulationTime,
+    pub agents: Vec<AgentId>,
+    /// 触发时的条件描述
+    pub condition: String,
+}
+
+/// 计划干预
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledIntervention {
+    pub id: u64,
+    pub name: String,
+    pub condition: TriggerCondition,
+    pub action: ScheduledAction,
+    /// 条件触发后是否继续等待下一次：条件解除后再次满足才会再触发（时间触发总是只执行一次）
+    pub repeat: bool,
+    pub status: ScheduleStatus,
+    pub created_at: SimulationTime,
+    pub fired: Vec<FiredRecord>,
+    /// 各 Agent 条件持续的情况
+    #[serde(skip)]
+    streaks: HashMap<AgentId, Streak>,
+}
+
+/// 某个 Agent 的条件持续成立的一段
+#[derive(Debug, Clone, Copy, Default)]
+struct Streak {
+    /// 自首次观察到条件成立起经过的小时数
+    hours: u32,
+    /// 这一段是否已经触发过
+    fired: bool,
+}
+
+/// 本步到期的干预
+#[derive(Debug, Clone)]
+pub struct DueIntervention {
+    pub id: u64,
+    pub action: ScheduledAction,
+    /// 满足条件的 Agent（时间触发为空）
+    pub agents: Vec<AgentId>,
+    pub condition: String,
+}
+
+/// 计划干预错误
+#[derive(Debug, thiserror::Error)]
+pub enum SchedulerError {
+    #[error("scheduled intervention {0} not found")]
+    NotFound(u64),
+
+    #[error("scheduled intervention {0} is no longer pending")]
+    NotPending(u64),
+
+    #[error("invalid schedule: {0}")]
+    Invalid(String),
+}
+
+/// 计划干预调度器
+#[derive(Debug, Default)]
+pub struct InterventionScheduler {
+    entries: Vec<ScheduledIntervention>,
+    next_id: u64,
+}
+
+impl InterventionScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 新增计划，返回其 ID
+    pub fn schedule(
+        &mut self,
+        name: String,
+        condition: TriggerCondition,
+        action: ScheduledAction,
+        repeat: bool,
+        world: &WorldState,
+    ) -> Result<u64, SchedulerError> {
+        validate(&condition, &action, world)?;
+        self.next_id += 1;
+        self.entries.push(ScheduledIntervention {
+            id: self.next_id,
+            name,
+            condition,
+            action,
+            repeat,
+            status: ScheduleStatus::Pending,
+            created_at: world.clock.current_time().clone(),
+            fired: Vec::new(),
+            streaks: HashMap::new(),
+        });
+        Ok(self.next_id)
+    }
+
+    pub fn list(&self) -> &[ScheduledIntervention] {
+        &self.entries
+    }
+
+    pub fn get(&self, id: u64) -> Option<&ScheduledIntervention> {
+        self.entries.iter().find(|e| e.id == id)
+    }
+
+    /// 修改尚未执行的计划（条件的持续时间重新计算）
+    pub fn update(
+        &mut self,
+        id: u64,
+        name: String,
+        condition: TriggerCondition,
+        action: ScheduledAction,
+        repeat: bool,
+        world: &WorldState,
+    ) -> Result<(), SchedulerError> {
+        validate(&condition, &action, world)?;
+        let entry = self.pending_mut(id)?;
+        entry.name = name;
+        entry.condition = condition;
+        entry.action = action;
+        entry.repeat = repeat;
+        entry.streaks.clear();
+        Ok(())
+    }
+
+    /// 取消尚未执行的计划（保留记录）
+    pub fn cancel(&mut self, id: u64) -> Result<(), SchedulerError> {
+        self.pending_mut(id)?.status = ScheduleStatus::Cancelled;
+        Ok(())
+    }
+
+    /// 删除计划
+    pub fn remove(&mut self, id: u64) -> Result<ScheduledIntervention, SchedulerError> {
+        let index = self
+            .entries
+            .iter()
+            .position(|e| e.id == id)
+            .ok_or(SchedulerError::NotFound(id))?;
+        Ok(self.entries.remove(index))
+    }
+
+    fn pending_mut(&mut self, id: u64) -> Result<&mut ScheduledIntervention, SchedulerError> {
+        let entry = self
+            .entries
+            .iter_mut()
+            .find(|e| e.id == id)
+            .ok_or(SchedulerError::NotFound(id))?;
+        if entry.status != ScheduleStatus::Pending {
+            return Err(SchedulerError::NotPending(id));
+        }
+        Ok(entry)
+    }
+
+    /// 检查本步到期的计划并记录触发；每步调用一次，`step_hours` 为一步的时长
+    pub fn due(&mut self, world: &WorldState, step_hours: u32) -> Vec<DueIntervention> {
+        let now = world.clock.current_time();
+        let mut due = Vec::new();
+
+        for entry in self.entries.iter_mut().filter(|e| e.status == ScheduleStatus::Pending) {
+            let agents = match &entry.condition {
+                TriggerCondition::At {
+                    semester,
+                    week,
+                    day_of_week,
+                    hour,
+                } => {
+                    if (now.semester, now.week, now.day_of_week, now.hour) < (*semester, *week, *day_of_week, *hour) {
+                        continue;
+                    }
+                    entry.status = ScheduleStatus::Fired;
+                    Vec::new()
+                }
+                TriggerCondition::AgentState {
+                    field,
+                    comparison,
+                    threshold,
+                    for_hours,
+                    agent,
+                } => {
+                    let Some(field) = AgentField::from_path(field) else {
+                        continue;
+                    };
+                    let candidates: Vec<AgentId> = match agent {
+                        Some(agent) => vec![agent.clone()],
+                        None => world
+                            .agents
+                            .values()
+                            .filter(|a| !a.config.role.is_staff())
+                            .map(|a| a.id.clone())
+                            .collect(),
+                    };
+                    let mut matched = Vec::new();
+                    for agent_id in candidates {
+                        let holds = world
+                            .agent_value(&agent_id, field)
+                            .is_some_and(|value| comparison.holds(value, *threshold));
+                        if !holds {
+                            entry.streaks.remove(&agent_id);
+                            continue;
+                        }
+                        // 首次观察到成立时从 0 开始计时
+                        let streak = match entry.streaks.get_mut(&agent_id) {
+                            Some(streak) => {
+                                streak.hours += step_hours;
+                                streak
+                            }
+                            None => entry.streaks.entry(agent_id.clone()).or_default(),
+                        };
+                        if !streak.fired && streak.hours >= *for_hours {
+                            matched.push(agent_id);
+                        }
+                    }
+                    if matched.is_empty() {
+                        continue;
+                    }
+                    matched.sort_by_key(|id| id.0);
+                    // 同一段持续越界只触发一次，条件解除后重新计时
+                    for agent_id in &matched {
+                        if let Some(streak) = entry.streaks.get_mut(agent_id) {
+                            streak.fired = true;
+                        }
+                    }
+                    if !entry.repeat {
+                        entry.status = ScheduleStatus::Fired;
+                    }
+                    matched
+                }
+            };
+
+            let condition = entry.condition.describe(&agents, world);
+            entry.fired.push(FiredRecord {
+                timestamp: now.clone(),
+                agents: agents.clone(),
+                condition: condition.clone(),
+            });
+            due.push(DueIntervention {
+                id: entry.id,
+                action: entry.action.clone(),
+                agents,
+                condition,
+            });
+        }
+        due
+    }
+}
+
+/// 校验条件与动作
+fn validate(condition: &TriggerCondition, action: &ScheduledAction, world: &WorldState) -> Result<(), SchedulerError> {
+    let invalid = |reason: String| Err(SchedulerError::Invalid(reason));
+    let known = |agent: &AgentId| world.agents.contains_key(agent);
+
+    match condition {
+        TriggerCondition::At {
+            semester,
+            week,
+            day_of_week,
+            hour,
+        } => {
+            if *semester == 0 || *week == 0 {
+                return invalid("semester and week start at 1".to_string());
+            }
+            if !(1..=7).contains(day_of_week) {
+                return invalid(format!("day_of_week {day_of_week} must be 1-7"));
+            }
+            if *hour > 23 {
+                return invalid(format!("hour {hour} must be 0-23"));
+            }
+            if matches!(action, ScheduledAction::CounselorChat { agent: None }) {
+                return invalid("a time-triggered counselor chat needs an agent".to_string());
+            }
+        }
+        TriggerCondition::AgentState {
+            field,
+            threshold,
+            agent,
+            ..
+        } => {
+            let numeric = FIELDS
+                .iter()
+                .any(|f| f.path == field && matches!(f.kind, ValueKind::Number { .. }))
+                && AgentField::from_path(field).is_some();
+            if !numeric {
+                return invalid(format!("`{field}` is not a numeric agent field"));
+            }
+            if !threshold.is_finite() {
+                return invalid(format!("threshold {threshold} is not a finite number"));
+            }
+            if let Some(agent) = agent.as_ref().filter(|a| !known(a)) {
+                return invalid(format!("agent {agent} not found"));
+            }
+        }
+    }
+
+    if let ScheduledAction::CounselorChat { agent: Some(agent) } = action {
+        if !known(agent) {
+            return invalid(format!("agent {agent} not found"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_school_agent::builder::AgentBuilder;
+
+    fn world_with_student() -> (WorldState, AgentId) {
+        let mut world = WorldState::new(1);
+        let agent = AgentBuilder::new().name("小明").build(world.clock.current_time());
+        let id = agent.id.clone();
+        world.add_agent(agent);
+        (world, id)
+    }
+
+    fn sports_meet() -> ScheduledAction {
+        ScheduledAction::Preset {
+            event: PresetEvent::SportsMeet,
+        }
+    }
+
+    fn stressed(agent: Option<AgentId>, for_hours: u32) -> TriggerCondition {
+        TriggerCondition::AgentState {
+            field: "emotion.stress".to_string(),
+            comparison: Comparison::Above,
+            threshold: 0.8,
+            for_hours,
+            agent,
+        }
+    }
+
+    fn set_stress(world: &mut WorldState, id: &AgentId, stress: f32) {
+        world.agents.get_mut(id).unwrap().emotion.stress = stress;
+    }
+
+    #[test]
+    fn test_at_compares_whole_time() {
+        let (mut world, _) = world_with_student();
+        let mut scheduler = InterventionScheduler::new();
+        let at = TriggerCondition::At {
+            semester: 1,
+            week: 1,
+            day_of_week: 2,
+            hour: 9,
+        };
+        let id = scheduler.schedule("运动会".to_string(), at, sports_meet(), true, &world).unwrap();
+
+        // 周一 10 点之后的小时数更大，但日期还没到
+        let mut fired_at = Vec::new();
+        for _ in 0..48 {
+            world.clock.advance();
+            for due in scheduler.due(&world, 1) {
+                assert_eq!(due.id, id);
+                assert!(due.agents.is_empty());
+                let now = world.clock.current_time();
+                fired_at.push((now.day_of_week, now.hour));
+            }
+        }
+        // 时间触发即使设为重复也只执行一次
+        assert_eq!(fired_at, vec![(2, 9)]);
+        let entry = scheduler.get(id).unwrap();
+        assert_eq!(entry.status, ScheduleStatus::Fired);
+        assert_eq!(entry.fired[0].condition, "第1学期第1周周二 09:00");
+    }
+
+    #[test]
+    fn test_streak_accumulates_and_resets() {
+        let (mut world, student) = world_with_student();
+        let mut scheduler = InterventionScheduler::new();
+        let id = scheduler
+            .schedule("压力预警".to_string(), stressed(None, 4), sports_meet(), true, &world)
+            .unwrap();
+
+        // 首次观察到越界时从 0 开始计时
+        set_stress(&mut world, &student, 0.9);
+        assert!(scheduler.due(&world, 2).is_empty());
+        assert!(scheduler.due(&world, 2).is_empty());
+        // 中途回落，重新计时
+        set_stress(&mut world, &student, 0.5);
+        assert!(scheduler.due(&world, 2).is_empty());
+        set_stress(&mut world, &student, 0.9);
+        assert!(scheduler.due(&world, 2).is_empty());
+        assert!(scheduler.due(&world, 2).is_empty());
+        let due = scheduler.due(&world, 2);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].agents, vec![student.clone()]);
+
+        // 同一段持续越界只触发一次
+        for _ in 0..10 {
+            assert!(scheduler.due(&world, 2).is_empty());
+        }
+        // 解除后再次持续满 4 小时，重复计划会再次触发
+        set_stress(&mut world, &student, 0.5);
+        assert!(scheduler.due(&world, 2).is_empty());
+        set_stress(&mut world, &student, 0.9);
+        assert!(scheduler.due(&world, 2).is_empty());
+        assert!(scheduler.due(&world, 2).is_empty());
+        assert_eq!(scheduler.due(&world, 2).len(), 1);
+        let entry = scheduler.get(id).unwrap();
+        assert_eq!(entry.status, ScheduleStatus::Pending);
+        assert_eq!(entry.fired.len(), 2);
+    }
+
+    #[test]
+    fn test_one_shot_fires_once() {
+        let (mut world, student) = world_with_student();
+        let mut scheduler = InterventionScheduler::new();
+        let action = ScheduledAction::CounselorChat { agent: None };
+        let id = scheduler
+            .schedule("谈心".to_string(), stressed(Some(student.clone()), 0), action, false, &world)
+            .unwrap();
+
+        set_stress(&mut world, &student, 0.9);
+        assert_eq!(scheduler.due(&world, 1).len(), 1);
+        assert!(scheduler.due(&world, 1).is_empty());
+        assert_eq!(scheduler.get(id).unwrap().status, ScheduleStatus::Fired);
+    }
+
+    #[test]
+    fn test_only_pending_entries_change() {
+        let (mut world, student) = world_with_student();
+        let mut scheduler = InterventionScheduler::new();
+        let fired = scheduler
+            .schedule("谈心".to_string(), stressed(Some(student.clone()), 0), sports_meet(), false, &world)
+            .unwrap();
+        let pending = scheduler
+            .schedule("预警".to_string(), stressed(None, 24), sports_meet(), true, &world)
+            .unwrap();
+        set_stress(&mut world, &student, 0.9);
+        scheduler.due(&world, 1);
+
+        assert!(matches!(scheduler.cancel(fired), Err(SchedulerError::NotPending(_))));
+        let update = scheduler.update(fired, "改".to_string(), stressed(None, 1), sports_meet(), false, &world);
+        assert!(matches!(update, Err(SchedulerError::NotPending(_))));
+        assert!(matches!(scheduler.cancel(99), Err(SchedulerError::NotFound(99))));
+
+        scheduler.cancel(pending).unwrap();
+        assert_eq!(scheduler.get(pending).unwrap().status, ScheduleStatus::Cancelled);
+        assert!(matches!(scheduler.cancel(pending), Err(SchedulerError::NotPending(_))));
+        // 已取消的计划不再检查，但可以删除
+        for _ in 0..30 {
+            assert!(scheduler.due(&world, 1).is_empty());
+        }
+        assert_eq!(scheduler.remove(pending).unwrap().name, "预警");
+        assert_eq!(scheduler.list().len(), 1);
+    }
+
+    #[test]
+    fn test_validate() {
+        let (world, student) = world_with_student();
+        let check = |condition: TriggerCondition, action: ScheduledAction| validate(&condition, &action, &world);
+        let at = |week, day_of_week, hour| TriggerCondition::At {
+            semester: 1,
+            week,
+            day_of_week,
+            hour,
+        };
+        let field = |field: &str, threshold: f32| TriggerCondition::AgentState {
+            field: field.to_string(),
+            comparison: Comparison::Below,
+            threshold,
+            for_hours: 0,
+            agent: None,
+        };
+        let chat = |agent: Option<AgentId>| ScheduledAction::CounselorChat { agent };
+
+        assert!(check(at(3, 5, 14), sports_meet()).is_ok());
+        assert!(check(at(0, 5, 14), sports_meet()).is_err());
+        assert!(check(at(3, 8, 14), sports_meet()).is_err());
+        assert!(check(at(3, 5, 24), sports_meet()).is_err());
+        assert!(check(at(3, 5, 14), chat(None)).is_err());
+        assert!(check(at(3, 5, 14), chat(Some(student.clone()))).is_ok());
+        assert!(check(at(3, 5, 14), chat(Some(AgentId::new()))).is_err());
+
+        assert!(check(field("ability.academic", 0.3), chat(None)).is_ok());
+        assert!(check(field("emotion.joy", 0.3), sports_meet()).is_err());
+        assert!(check(field("location", 0.3), sports_meet()).is_err());
+        assert!(check(field("emotion.stress", f32::NAN), sports_meet()).is_err());
+        assert!(check(stressed(Some(AgentId::new()), 0), sports_meet()).is_err());
+    }
+}
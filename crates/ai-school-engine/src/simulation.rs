@@ -2,27 +2,46 @@
 //!
 //! 核心编排层：协调 Agent 决策、GM 仲裁、状态更新、记忆写入。
 
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use tokio::sync::broadcast;
 use tracing::{debug, error, info, instrument, warn};
 
-use ai_school_core::config::SimulationConfig;
+use ai_school_core::config::{ConsistencyPolicy, DecisionMode, SimulationConfig};
 use ai_school_core::error::SimulationError;
 use ai_school_core::traits::llm::LlmProvider;
 use ai_school_core::traits::MemoryStore;
 use ai_school_core::types::{
-    AgentId, AgentState, BehaviorIntent, EventId, EventTrigger, Memory, MemoryId,
-    MemoryLayer, MemoryQuery, SimulationEvent, SimulationSpeed, SimulationTime,
+    AgentActivity, AgentId, AgentRole, AgentState, BehaviorIntent, CareerAspiration, ChangeType, EventId,
+    EventTrigger,
+    EventType, IntentType, LocationId, Memory, MemoryId, MemoryLayer, MemoryQuery,
+    PersonalityModelKind, PresetEvent, SimulationEvent, SimulationSpeed, SimulationTime, StateChange,
 };
 
+use ai_school_agent::builder::AgentBuilder;
+use ai_school_agent::career::{default_subject_preferences, CareerEvolution, CareerExperience};
 use ai_school_agent::cognition::CognitionProcessor;
+use ai_school_agent::emotion::{emotion_description, Appraisal, EmotionModel};
+use ai_school_agent::personality::{drift_from_events, generate_diverse_big_five, generate_diverse_personalities};
+use ai_school_agent::policy::{HeuristicPolicy, NearbyAgent, ObservedStudent};
 use ai_school_memory::reflection::ReflectionTrigger;
+use ai_school_world::academics::StudySession;
+use ai_school_world::changes::{AgentField, ChangeTarget, TypedChange};
+use ai_school_world::exams::{ExamKind, ExamRecord};
+use ai_school_world::movement::Encounter;
+use ai_school_world::relationships::{LabelChange, RelationshipLabel, TEACHER_STUDENT_TAG};
+use ai_school_world::scenario::{RosterEntry, Scenario};
+use ai_school_world::social::ClubMeeting;
 use ai_school_world::state::WorldState;
+use ai_school_world::time::TimeEvent;
 
 use crate::broadcast::SimulationUpdate;
-use crate::game_master::GameMaster;
+use crate::consistency::{check_consistency, llm_judge, ConsistencyWarning};
+use crate::game_master::{cluster_scenes, GameMaster, GameMasterOutput, Scene};
+use crate::intervention::{preset_scope, EnvironmentParameter, InterventionManager, InterventionOutcome};
+use crate::scheduler::{DueIntervention, InterventionScheduler, ScheduledAction};
 
 /// 仿真步骤结果
 #[derive(Debug)]
@@ -40,7 +59,14 @@ pub struct SimulationRunner<L: LlmProvider, M: MemoryStore> {
     pub config: SimulationConfig,
     pub speed: SimulationSpeed,
     pub game_master: GameMaster,
+    pub emotion_model: EmotionModel,
+    pub career_evolution: CareerEvolution,
+    pub heuristic_policy: HeuristicPolicy,
     pub reflection_trigger: ReflectionTrigger,
+    /// 用户干预记录
+    pub interventions: InterventionManager,
+    /// 计划干预
+    pub scheduler: InterventionScheduler,
     pub event_tx: broadcast::Sender<SimulationUpdate>,
     /// Shared atomic flag — can be set from outside without holding the RwLock
     pub running: Arc<AtomicBool>,
@@ -53,23 +79,77 @@ impl<L: LlmProvider, M: MemoryStore> SimulationRunner<L, M> {
         config: SimulationConfig,
     ) -> Self {
         let (event_tx, _) = broadcast::channel(1024);
+        let mut world = WorldState::new(config.time_step_hours);
+        world.change_limits = config.change_limits.clone();
+        world.audit_capacity = config.audit_capacity;
 
         Self {
             llm: llm.clone(),
             memory_store,
-            world: WorldState::new(config.time_step_hours),
+            world,
             config: config.clone(),
             speed: SimulationSpeed::Paused,
             game_master: GameMaster::new(),
+            emotion_model: EmotionModel::new(config.emotion.clone()),
+            career_evolution: CareerEvolution::default(),
+            heuristic_policy: HeuristicPolicy::default(),
             reflection_trigger: ReflectionTrigger::new(config.reflection_threshold),
+            interventions: InterventionManager::new(),
+            scheduler: InterventionScheduler::new(),
             event_tx,
             running: Arc::new(AtomicBool::new(false)),
         }
     }
 
-    /// 添加 Agent 到仿真
+    /// 从场景创建仿真：使用场景的配置、校园与课程，并加入名单中的学生
+    pub fn from_scenario(
+        llm: Arc<L>,
+        memory_store: Arc<M>,
+        scenario: &Scenario,
+    ) -> Result<Self, SimulationError> {
+        let mut runner = Self::new(llm, memory_store, scenario.config.clone());
+        runner.world = WorldState::from_scenario(scenario, scenario.config.time_step_hours)?;
+
+        let start_time = runner.world.clock.current_time().clone();
+        for entry in &scenario.roster {
+            let mut agent = roster_agent(entry, &start_time, runner.config.personality_model);
+            if let (true, None, Some(room)) =
+                (entry.role.is_staff(), &entry.location, &runner.world.staff_room)
+            {
+                agent.location = room.clone();
+            }
+            let agent_id = agent.id.clone();
+            runner.world.add_agent(agent);
+            if let Some(group) = &entry.class_group {
+                runner.world.join_class_group(&agent_id, group)?;
+            }
+            for group in &entry.teaches {
+                runner.world.assign_teacher(&agent_id, group)?;
+            }
+            if let Some(group) = &entry.head_teacher_of {
+                runner.world.set_head_teacher(&agent_id, group)?;
+            }
+            for elective in &entry.electives {
+                runner.world.enroll_elective(&agent_id, elective)?;
+            }
+            for club in &entry.clubs {
+                runner.world.join_club(&agent_id, club)?;
+            }
+        }
+        // 名单指定的选修课占好名额后，再为其余时段自动分配
+        let agent_ids: Vec<AgentId> = runner.world.agents.keys().cloned().collect();
+        for agent_id in &agent_ids {
+            runner.world.assign_electives(agent_id);
+        }
+
+        Ok(runner)
+    }
+
+    /// 添加 Agent 到仿真（自动分班并选修课）
     pub fn add_agent(&mut self, agent: AgentState) {
+        let agent_id = agent.id.clone();
         self.world.add_agent(agent);
+        self.world.assign_electives(&agent_id);
     }
 
     /// 获取事件订阅
@@ -91,16 +171,129 @@ impl<L: LlmProvider, M: MemoryStore> SimulationRunner<L, M> {
             debug!(event = ?te, "Time event triggered");
         }
 
-        // 1b. 处理时间事件 → 移动 Agent 到对应位置
-        self.world.process_time_events(&time_events);
+        // 1b. 处理时间事件 → Agent 沿校园图前往对应位置，途中可能偶遇
+        let encounters = self.world.process_time_events(&time_events);
+        for encounter in &encounters {
+            let mut event = self.encounter_event(encounter, &current_time);
+            warnings.extend(self.appraise_event(&mut event));
+            events.push(event.clone());
+            self.world.event_log.push(event);
+        }
+        self.record_redirects(&current_time, &mut events, &mut warnings);
+
+        // 1b'. 到期的计划干预（其事件在触发时已写入记忆）
+        let mut remembered: Vec<EventId> = Vec::new();
+        for due in self.scheduler.due(&self.world, self.config.time_step_hours) {
+            info!(id = due.id, condition = %due.condition, "Scheduled intervention fired");
+            match self.fire_scheduled(due).await {
+                Ok(outcome) => {
+                    remembered.extend(outcome.events.iter().map(|e| e.id.clone()));
+                    events.extend(outcome.events);
+                    warnings.extend(outcome.warnings);
+                }
+                Err(e) => warnings.push(format!("Scheduled intervention failed: {e}")),
+            }
+        }
+
+        // 1b''. 社团活动日的课外活动时间 → 社团活动
+        if time_events.contains(&TimeEvent::FreeTime) {
+            for meeting in self.world.hold_club_meetings() {
+                let mut event = self.club_meeting_event(&meeting, &current_time);
+                warnings.extend(self.appraise_event(&mut event));
+                events.push(event.clone());
+                self.world.event_log.push(event);
+            }
+        }
+
+        // 1b'''. 考试日放学 → 公布成绩与排名
+        if time_events.contains(&TimeEvent::FreeTime) {
+            if let Some(records) = self.world.hold_scheduled_exam() {
+                if let Some(event) =
+                    self.exam_event(&records, EventTrigger::TimeSchedule, Vec::new(), &current_time)
+                {
+                    events.push(event.clone());
+                    self.world.event_log.push(event);
+                }
+            }
+        }
+
+        // 1c. 情绪动力学：向人格基线回归
+        for agent in self.world.agents.values_mut() {
+            self.emotion_model.decay(
+                &mut agent.emotion,
+                &agent.config.personality,
+                &agent.abilities,
+                self.config.time_step_hours,
+            );
+        }
+
+        // 1c'. 拥挤 → 压力
+        warnings.extend(self.record_crowding(&current_time, &mut events)?);
+
+        // 1d. 每周：一周经历带来人格漂移，再重新评估职业志向（漂移可能改变最佳匹配）
+        if time_events.contains(&TimeEvent::NewWeek) {
+            self.weekly_drift(&current_time);
+        }
+
+        // 1d'. 每天：久未互动的关系向中性回归，并记录社交网络指标
+        if time_events.contains(&TimeEvent::NewDay) {
+            self.world
+                .relationships
+                .decay(&current_time, self.config.time_step_hours);
+            self.world.record_network_metrics();
+        }
+        self.record_label_changes(&current_time, &mut events);
+
+        // 1e. 课堂学习 → 学业反馈
+        if time_events
+            .iter()
+            .any(|e| matches!(e, TimeEvent::ClassStart { .. }))
+        {
+            let attending: Vec<(AgentId, String)> = self
+                .world
+                .agents
+                .values()
+                .filter_map(|a| match &a.activity {
+                    AgentActivity::Studying { subject } => Some((a.id.clone(), subject.clone())),
+                    _ => None,
+                })
+                .collect();
+            warnings.extend(self.record_study(
+                &attending,
+                StudySession::Class,
+                Vec::new(),
+                &current_time,
+                &mut events,
+            )?);
+        }
 
         // 2. 为每个活跃 Agent 构建 SituationContext 并执行决策
+        //    同时记录每个决策的原因：感知到的事件与检索到的记忆所关联的事件
         let agent_ids: Vec<AgentId> = self.world.agents.keys().cloned().collect();
         let mut intents = Vec::new();
+        let mut decision_causes: HashMap<AgentId, Vec<EventId>> = HashMap::new();
 
         for agent_id in &agent_ids {
+            if self.config.decision_mode == DecisionMode::Heuristic {
+                intents.push(self.heuristic_decision(agent_id, &current_time)?);
+                decision_causes.insert(agent_id.clone(), self.perceived_events(agent_id));
+                continue;
+            }
+
             match self.agent_decision(agent_id, &current_time).await {
-                Ok(intent) => intents.push(intent),
+                Ok((intent, causes)) => {
+                    intents.push(intent);
+                    decision_causes.insert(agent_id.clone(), causes);
+                }
+                Err(e) if self.config.heuristic_fallback => {
+                    warn!(agent = %agent_id, error = %e, "Agent decision failed, using heuristic policy");
+                    warnings.push(format!(
+                        "Agent {} decision failed, fell back to heuristic policy: {e}",
+                        agent_id
+                    ));
+                    intents.push(self.heuristic_decision(agent_id, &current_time)?);
+                    decision_causes.insert(agent_id.clone(), self.perceived_events(agent_id));
+                }
                 Err(e) => {
                     warn!(agent = %agent_id, error = %e, "Agent decision failed");
                     warnings.push(format!("Agent {} decision failed: {e}", agent_id));
@@ -108,41 +301,121 @@ impl<L: LlmProvider, M: MemoryStore> SimulationRunner<L, M> {
             }
         }
 
-        // 3. Game Master 仲裁
-        let gm_output = self
+        // 2b. 自主学习意图 → 学业反馈
+        let mut self_study = Vec::new();
+        let mut study_causes: Vec<EventId> = Vec::new();
+        for intent in intents.iter().filter(|i| i.intent_type == IntentType::Study) {
+            if self.world.is_staff(&intent.agent_id) {
+                continue;
+            }
+            if let Some(subject) = self.world.study_subject_for(&intent.agent_id) {
+                self_study.push((intent.agent_id.clone(), subject));
+                for cause in decision_causes.get(&intent.agent_id).into_iter().flatten() {
+                    if !study_causes.contains(cause) {
+                        study_causes.push(cause.clone());
+                    }
+                }
+            }
+        }
+        warnings.extend(self.record_study(
+            &self_study,
+            StudySession::SelfStudy,
+            study_causes,
+            &current_time,
+            &mut events,
+        )?);
+
+        // 2c. 想参加活动但尚无合适社团 → 加入兴趣最高的社团
+        for intent in intents.iter().filter(|i| i.intent_type == IntentType::JoinActivity) {
+            let causes = decision_causes.get(&intent.agent_id).cloned().unwrap_or_default();
+            if let Some(event) = self.join_best_club(&intent.agent_id, causes, &current_time) {
+                events.push(event.clone());
+                self.world.event_log.push(event);
+            }
+        }
+
+        // 2d. 教职工表扬/批评/谈心 → 直接作用于学生
+        for intent in &intents {
+            let causes = decision_causes.get(&intent.agent_id).cloned().unwrap_or_default();
+            if let Some(event) = self.staff_action(intent, causes, &current_time) {
+                events.push(event.clone());
+                self.world.event_log.push(event);
+            }
+        }
+
+        // 3. 意图聚成场景 → Game Master 逐场景仲裁（启发式模式下不调用 LLM）
+        self.game_master.set_use_llm(self.config.decision_mode == DecisionMode::Llm);
+        let scenes = cluster_scenes(&intents, &self.world);
+        let gm_outputs = self
             .game_master
-            .arbitrate(&intents, &self.world, &*self.llm)
+            .arbitrate_scenes(&scenes, &self.world, &*self.llm)
             .await?;
 
-        // 4. 应用状态变更
-        let change_warnings = self.world.apply_state_changes(&gm_output.state_changes)?;
-        warnings.extend(change_warnings);
+        for (scene, gm_output) in scenes.iter().zip(gm_outputs) {
+            // 4. 叙事-数据一致性检查 → 按策略处理 → 应用状态变更
+            let causes = scene_causes(scene, &decision_causes);
+            let mut event = self.scene_event(scene, gm_output, causes.clone(), &current_time);
+            let issues = self.check_event(&event).await;
+            warnings.extend(issues.iter().map(|w| w.to_string()));
+            if issues.iter().any(|w| w.contradicts()) {
+                match self.config.consistency_policy {
+                    ConsistencyPolicy::Warn => {}
+                    ConsistencyPolicy::DropChanges => {
+                        warnings.push(format!("Event {}: dropped contradicting state changes", event.id));
+                        event.state_changes.clear();
+                    }
+                    ConsistencyPolicy::Rearbitrate => {
+                        let feedback: Vec<String> = issues.iter().map(|w| w.description.clone()).collect();
+                        let retry = self
+                            .game_master
+                            .rearbitrate(scene, &feedback, &self.world, &*self.llm)
+                            .await?;
+                        event = self.scene_event(scene, retry, causes, &current_time);
+                        let issues = self.check_event(&event).await;
+                        warnings.extend(issues.iter().map(|w| format!("{w} (after re-arbitration)")));
+                        if issues.iter().any(|w| w.contradicts()) {
+                            warnings.push(format!("Event {}: dropped contradicting state changes", event.id));
+                            event.state_changes.clear();
+                        }
+                    }
+                }
+            }
+            let change_warnings = self.world.apply_state_changes(&event.state_changes, Some(&event.id))?;
+            warnings.extend(change_warnings);
 
-        // 5. 创建并记录事件
-        let event = SimulationEvent {
-            id: EventId::new(),
-            event_type: gm_output.event_type,
-            trigger: EventTrigger::AgentAction,
-            timestamp: current_time.clone(),
-            involved_agents: intents.iter().map(|i| i.agent_id.clone()).collect(),
-            narrative: gm_output.narrative,
-            state_changes: gm_output.state_changes,
-            intensity: gm_output.intensity,
-        };
-        events.push(event.clone());
-        self.world.event_log.push(event.clone());
+            // 5. 每个场景一个事件，只涉及场景中的 Agent
+            self.game_master.remember(scene, &event.narrative, &current_time);
+            warnings.extend(self.appraise_event(&mut event));
+            self.record_activity_experiences(&scene.intents, &event);
+            events.push(event.clone());
+            self.world.event_log.push(event);
+        }
+        self.record_redirects(&current_time, &mut events, &mut warnings);
+        self.record_label_changes(&current_time, &mut events);
+        warnings.extend(
+            self.world
+                .check_invariants()
+                .into_iter()
+                .map(|problem| format!("Invariant violated: {problem}")),
+        );
 
-        // 6. 写入记忆 + 检查反思
-        for agent_id in &agent_ids {
-            self.update_agent_memory(agent_id, &event).await;
+        // 6. 本步事件写入参与者的记忆 + 检查反思（上课、拥挤等日常事件不写）
+        let memorable = events
+            .iter()
+            .filter(|e| e.event_type != EventType::Routine && !remembered.contains(&e.id));
+        for event in memorable {
+            for agent_id in &event.involved_agents {
+                self.update_agent_memory(agent_id, event).await;
+            }
         }
 
         // 7. 广播更新
-        let snapshot = self.world.snapshot();
+        let snapshot = Box::new(self.world.snapshot());
         let _ = self.event_tx.send(SimulationUpdate::Tick {
             time: current_time.clone(),
             snapshot,
             events: events.clone(),
+            warnings: warnings.clone(),
         });
 
         let tick = current_time.tick;
@@ -155,14 +428,954 @@ impl<L: LlmProvider, M: MemoryStore> SimulationRunner<L, M> {
         })
     }
 
-    /// 单个 Agent 的决策过程
-    async fn agent_decision(
+    /// 场景仲裁结果 → 仿真事件
+    fn scene_event(
+        &self,
+        scene: &Scene,
+        gm_output: GameMasterOutput,
+        causes: Vec<EventId>,
+        time: &SimulationTime,
+    ) -> SimulationEvent {
+        SimulationEvent {
+            id: EventId::new(),
+            event_type: gm_output.event_type,
+            trigger: EventTrigger::AgentAction,
+            timestamp: time.clone(),
+            involved_agents: scene.agents(),
+            narrative: gm_output.narrative,
+            state_changes: gm_output.state_changes,
+            intensity: gm_output.intensity,
+            location: scene.location.clone(),
+            causes,
+        }
+    }
+
+    /// 规则检查，按配置再用 LLM 复核
+    async fn check_event(&self, event: &SimulationEvent) -> Vec<ConsistencyWarning> {
+        let mut issues = check_consistency(event, &self.world);
+        if self.config.consistency_llm_judge {
+            issues.extend(llm_judge(event, &*self.llm).await);
+        }
+        issues
+    }
+
+    /// 一批学习 → 一个日常事件：学业能力随学习更新，情绪与职业清晰度的变化随事件落地
+    ///
+    /// 没有人学习时不产生事件。
+    fn record_study(
+        &mut self,
+        sessions: &[(AgentId, String)],
+        session: StudySession,
+        causes: Vec<EventId>,
+        time: &SimulationTime,
+        events: &mut Vec<SimulationEvent>,
+    ) -> Result<Vec<String>, SimulationError> {
+        let event_id = EventId::new();
+        let mut involved = Vec::new();
+        let mut state_changes = Vec::new();
+        for (agent_id, subject) in sessions {
+            if let Some(changes) = self.study_session(agent_id, subject, session, time, &event_id) {
+                involved.push(agent_id.clone());
+                state_changes.extend(changes);
+            }
+        }
+        if involved.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (narrative, trigger) = match session {
+            StudySession::Class => (format!("{}名学生按课表上课", involved.len()), EventTrigger::TimeSchedule),
+            StudySession::SelfStudy => {
+                let names: Vec<&str> = involved.iter().map(|id| self.world.agent_name(id)).collect();
+                (format!("{}在自习", names.join("、")), EventTrigger::AgentAction)
+            }
+        };
+        let event = SimulationEvent {
+            id: event_id,
+            event_type: EventType::Routine,
+            trigger,
+            timestamp: time.clone(),
+            involved_agents: involved,
+            narrative,
+            state_changes,
+            intensity: 0.2,
+            location: None,
+            causes,
+        };
+        let warnings = self.world.apply_state_changes(&event.state_changes, Some(&event.id))?;
+        events.push(event.clone());
+        self.world.event_log.push(event);
+        Ok(warnings)
+    }
+
+    /// 一次学习：更新学业能力，返回按满意度与竞争压力评估出的情绪与职业清晰度变更
+    ///
+    /// 无法学习（如学科不存在）时为 None。
+    fn study_session(
+        &mut self,
+        agent_id: &AgentId,
+        subject: &str,
+        session: StudySession,
+        time: &SimulationTime,
+        event_id: &EventId,
+    ) -> Option<Vec<StateChange>> {
+        let feedback = match self.world.study(agent_id, subject, session) {
+            Ok(feedback) => feedback,
+            Err(e) => {
+                debug!(agent = %agent_id, subject, error = %e, "Study session skipped");
+                return None;
+            }
+        };
+
+        let appraisals = [
+            Appraisal::academic(feedback.satisfaction).scaled(session.weight()),
+            Appraisal::competition(self.world.environment.pressure_excess(), feedback.performance)
+                .scaled(0.3 * session.weight()),
+        ];
+        let mut changes = self.emotion_changes(agent_id, &appraisals);
+        let experience = CareerExperience::SubjectPerformance {
+            subject: subject.to_string(),
+            performance: feedback.performance,
+        };
+        changes.extend(self.career_changes(agent_id, &[experience], time, event_id));
+        Some(changes)
+    }
+
+    /// 拥挤 → 压力：每个挤得让人不适的地点一个日常事件，情绪变化随事件落地
+    fn record_crowding(
+        &mut self,
+        time: &SimulationTime,
+        events: &mut Vec<SimulationEvent>,
+    ) -> Result<Vec<String>, SimulationError> {
+        let mut warnings = Vec::new();
+        let locations: Vec<(LocationId, String)> =
+            self.world.locations.iter().map(|l| (l.id.clone(), l.name.clone())).collect();
+        for (location, name) in locations {
+            let crowding = self.world.crowding(&location);
+            let present: Vec<(AgentId, Appraisal)> = self
+                .world
+                .agents_at_location(&location)
+                .iter()
+                .map(|a| (a.id.clone(), Appraisal::crowding(crowding, &a.config.personality)))
+                .collect();
+            let mut involved = Vec::new();
+            let mut state_changes = Vec::new();
+            for (agent_id, appraisal) in present {
+                let changes = self.emotion_changes(&agent_id, &[appraisal]);
+                if !changes.is_empty() {
+                    involved.push(agent_id);
+                    state_changes.extend(changes);
+                }
+            }
+            if involved.is_empty() {
+                continue;
+            }
+
+            let event = SimulationEvent {
+                id: EventId::new(),
+                event_type: EventType::Routine,
+                trigger: EventTrigger::ThresholdReached,
+                timestamp: time.clone(),
+                involved_agents: involved,
+                narrative: format!("{name}人满为患，挤得人心烦"),
+                state_changes,
+                intensity: 0.2,
+                location: Some(location),
+                causes: Vec::new(),
+            };
+            warnings.extend(self.world.apply_state_changes(&event.state_changes, Some(&event.id))?);
+            events.push(event.clone());
+            self.world.event_log.push(event);
+        }
+        Ok(warnings)
+    }
+
+    /// 学生过去一周参与的事件 → 有界的人格漂移 → 重新评估职业志向
+    fn weekly_drift(&mut self, time: &SimulationTime) {
+        let week_start = time.elapsed_hours.saturating_sub(7 * 24);
+        let week: Vec<&SimulationEvent> = self
+            .world
+            .event_log
+            .iter()
+            .rev()
+            .take_while(|e| e.timestamp.elapsed_hours > week_start)
+            .collect();
+
+        for agent in self.world.agents.values_mut().filter(|a| !a.config.role.is_staff()) {
+            let involved: Vec<&SimulationEvent> =
+                week.iter().rev().filter(|e| e.involved_agents.contains(&agent.id)).copied().collect();
+            let drifts = drift_from_events(&involved);
+            for drift in &drifts {
+                agent.config.personality.apply_shift(
+                    drift.dimension,
+                    drift.signal,
+                    self.config.personality_decay_factor,
+                    time.clone(),
+                    drift.event_id.clone(),
+                    drift.reason.clone(),
+                );
+            }
+
+            let trigger = drifts.last().map(|d| d.event_id.clone());
+            if let Some(change) = self.career_evolution.reevaluate(
+                &mut agent.config.career_aspiration,
+                &agent.config.personality,
+                time,
+                trigger,
+            ) {
+                info!(agent = %agent.id, reason = %change.reason, "Career aspiration changed");
+            }
+        }
+    }
+
+    /// 关系标签变化 → 社交事件（“小明和小红成了朋友”）
+    ///
+    /// 原因是本步中两人共同参与的事件。
+    fn record_label_changes(&mut self, time: &SimulationTime, events: &mut Vec<SimulationEvent>) {
+        for change in self.world.relationships.take_label_changes() {
+            let Some(mut event) = self.label_change_event(&change, time) else {
+                continue;
+            };
+            event.causes = events
+                .iter()
+                .filter(|e| {
+                    e.involved_agents.contains(&change.agent_a) && e.involved_agents.contains(&change.agent_b)
+                })
+                .map(|e| e.id.clone())
+                .collect();
+            events.push(event.clone());
+            self.world.event_log.push(event);
+        }
+    }
+
+    fn label_change_event(&self, change: &LabelChange, time: &SimulationTime) -> Option<SimulationEvent> {
+        let name = |id: &AgentId| self.world.agents.get(id).map(|a| a.config.name.clone());
+        let (a, b) = (name(&change.agent_a)?, name(&change.agent_b)?);
+        let (narrative, event_type) = match (change.from, change.to) {
+            (_, Some(RelationshipLabel::BestFriend)) => {
+                (format!("{a}和{b}成了无话不谈的挚友"), EventType::SocialInteraction)
+            }
+            (Some(RelationshipLabel::BestFriend), Some(RelationshipLabel::Friend)) => {
+                (format!("{a}和{b}不再像从前那样形影不离"), EventType::SocialInteraction)
+            }
+            (_, Some(RelationshipLabel::Friend)) => (format!("{a}和{b}成了朋友"), EventType::SocialInteraction),
+            (_, Some(RelationshipLabel::Rival)) => (format!("{a}和{b}暗暗较起了劲"), EventType::Conflict),
+            (_, Some(RelationshipLabel::Estranged)) => (format!("{a}和{b}渐渐疏远了"), EventType::Conflict),
+            (Some(from), None) if from.is_friendly() => {
+                (format!("{a}和{b}的友谊渐渐淡了"), EventType::SocialInteraction)
+            }
+            (Some(_), None) => (format!("{a}和{b}之间的隔阂消除了"), EventType::SocialInteraction),
+            (None, None) => return None,
+        };
+        debug!(a = %a, b = %b, from = ?change.from, to = ?change.to, "Relationship label changed");
+
+        let involved = vec![change.agent_a.clone(), change.agent_b.clone()];
+        Some(SimulationEvent {
+            id: EventId::new(),
+            event_type,
+            trigger: EventTrigger::ThresholdReached,
+            timestamp: time.clone(),
+            location: self.world.common_location(&involved),
+            involved_agents: involved,
+            narrative,
+            state_changes: Vec::new(),
+            intensity: 0.4,
+            causes: Vec::new(),
+        })
+    }
+
+    /// 容量已满导致的改道 → 系统提示事件（按原目的地与改道地点归并）
+    fn record_redirects(
+        &mut self,
+        time: &SimulationTime,
+        events: &mut Vec<SimulationEvent>,
+        warnings: &mut Vec<String>,
+    ) {
+        let mut groups: Vec<(LocationId, LocationId, Vec<AgentId>)> = Vec::new();
+        for redirect in self.world.take_redirects() {
+            match groups
+                .iter_mut()
+                .find(|(requested, assigned, _)| {
+                    *requested == redirect.requested && *assigned == redirect.assigned
+                }) {
+                Some((_, _, agents)) => agents.push(redirect.agent_id),
+                None => groups.push((redirect.requested, redirect.assigned, vec![redirect.agent_id])),
+            }
+        }
+
+        for (requested, assigned, agents) in groups {
+            let names: Vec<String> = agents
+                .iter()
+                .filter_map(|id| self.world.agents.get(id).map(|a| a.config.name.clone()))
+                .collect();
+            let narrative = format!(
+                "{}已满，{}改去了{}",
+                self.world.location_name(&requested),
+                names.join("、"),
+                self.world.location_name(&assigned)
+            );
+            warn!(requested = %requested, assigned = %assigned, count = agents.len(), "Location full, agents redirected");
+            warnings.push(narrative.clone());
+
+            let event = SimulationEvent {
+                id: EventId::new(),
+                event_type: EventType::System,
+                trigger: EventTrigger::ThresholdReached,
+                timestamp: time.clone(),
+                involved_agents: agents,
+                narrative,
+                state_changes: Vec::new(),
+                intensity: 0.1,
+                location: Some(assigned),
+                causes: Vec::new(),
+            };
+            events.push(event.clone());
+            self.world.event_log.push(event);
+        }
+    }
+
+    /// 教职工对学生的表扬/批评/谈心：学生情绪与师生关系随之变化
+    ///
+    /// 对象须是同处一地的学生；辅导员谈心同时计入学生的职业规划经历。
+    fn staff_action(
+        &mut self,
+        intent: &BehaviorIntent,
+        causes: Vec<EventId>,
+        time: &SimulationTime,
+    ) -> Option<SimulationEvent> {
+        if !matches!(
+            intent.intent_type,
+            IntentType::Praise | IntentType::Criticize | IntentType::Counsel
+        ) {
+            return None;
+        }
+        let staff = self.world.get_agent(&intent.agent_id).ok()?;
+        if !staff.config.role.is_staff() {
+            return None;
+        }
+        let student_id = intent.target_agents.first()?;
+        if self.world.is_staff(student_id) || self.world.get_agent(student_id).ok()?.location != staff.location {
+            return None;
+        }
+        let (staff_id, student_id) = (staff.id.clone(), student_id.clone());
+        self.staff_effect(&staff_id, &student_id, &intent.intent_type, EventTrigger::AgentAction, causes, time)
+    }
+
+    /// 表扬/批评/谈心的效果（不检查双方位置）
+    fn staff_effect(
+        &mut self,
+        staff_id: &AgentId,
+        student_id: &AgentId,
+        intent_type: &IntentType,
+        trigger: EventTrigger,
+        causes: Vec<EventId>,
+        time: &SimulationTime,
+    ) -> Option<SimulationEvent> {
+        let staff = self.world.get_agent(staff_id).ok()?;
+        let staff_name = staff.config.name.clone();
+        let is_counselor = staff.config.role == AgentRole::Counselor;
+        let location = self.world.common_location(&[staff_id.clone(), student_id.clone()]);
+
+        let (appraisal, closeness, trust, event_type, verb) = {
+            let student = self.world.get_agent(student_id).ok()?;
+            match intent_type {
+                IntentType::Praise => (Appraisal::praise(), 0.05, 0.05, EventType::Academic, "表扬了"),
+                IntentType::Criticize => (
+                    Appraisal::criticism(&student.config.personality),
+                    -0.03,
+                    -0.02,
+                    EventType::Academic,
+                    "批评了",
+                ),
+                _ => (Appraisal::counseling(), 0.04, 0.08, EventType::Cooperation, "找"),
+            }
+        };
+
+        let event_id = EventId::new();
+        let student_name = self.world.agent_name(student_id).to_string();
+        let mut state_changes = self.emotion_changes(student_id, &[appraisal]);
+        if is_counselor && *intent_type == IntentType::Counsel {
+            state_changes.extend(self.career_changes(student_id, &[CareerExperience::CounselorChat], time, &event_id));
+        }
+        for (field, delta) in [("closeness", closeness), ("trust", trust)] {
+            state_changes.push(StateChange {
+                target: format!("relationship[{staff_id},{student_id}].{field}"),
+                change_type: ChangeType::Delta,
+                value: serde_json::json!(delta),
+            });
+        }
+
+        self.world.relationships.add_tag(staff_id, student_id, TEACHER_STUDENT_TAG);
+        match self.world.apply_state_changes(&state_changes, Some(&event_id)) {
+            Ok(warnings) => warnings.iter().for_each(|w| warn!(warning = %w, "Staff action change")),
+            Err(e) => warn!(error = %e, "Staff action changes failed"),
+        }
+
+        let narrative = if *intent_type == IntentType::Counsel {
+            format!("{staff_name}{verb}{student_name}谈心")
+        } else {
+            format!("{staff_name}{verb}{student_name}")
+        };
+        info!(staff = %staff_name, student = %student_name, intent = ?intent_type, "Staff action");
+
+        Some(SimulationEvent {
+            id: event_id,
+            event_type,
+            trigger,
+            timestamp: time.clone(),
+            involved_agents: vec![staff_id.clone(), student_id.clone()],
+            narrative,
+            state_changes,
+            intensity: 0.4,
+            location,
+            causes,
+        })
+    }
+
+    /// 社团活动 → 合作事件，到场成员积累社团经历
+    fn club_meeting_event(&mut self, meeting: &ClubMeeting, time: &SimulationTime) -> SimulationEvent {
+        let event_id = EventId::new();
+        let mut names = Vec::new();
+        for agent_id in &meeting.attendees {
+            let Some(agent) = self.world.agents.get_mut(agent_id) else {
+                continue;
+            };
+            names.push(agent.config.name.clone());
+            self.career_evolution.record_experience(
+                &mut agent.config.career_aspiration,
+                &CareerExperience::ClubActivity {
+                    name: meeting.club.clone(),
+                },
+                time,
+                Some(event_id.clone()),
+            );
+        }
+        info!(club = %meeting.club, attendees = meeting.attendees.len(), "Club meeting held");
+
+        SimulationEvent {
+            id: event_id,
+            event_type: EventType::Cooperation,
+            trigger: EventTrigger::TimeSchedule,
+            timestamp: time.clone(),
+            involved_agents: meeting.attendees.clone(),
+            narrative: format!(
+                "{}在{}开展活动，{}参加了",
+                meeting.club,
+                self.world.location_name(&meeting.location),
+                names.join("、")
+            ),
+            state_changes: Vec::new(),
+            intensity: 0.3,
+            location: Some(meeting.location.clone()),
+            causes: Vec::new(),
+        }
+    }
+
+    /// 加入兴趣最高的社团（无合适社团时为 None）
+    fn join_best_club(
+        &mut self,
+        agent_id: &AgentId,
+        causes: Vec<EventId>,
+        time: &SimulationTime,
+    ) -> Option<SimulationEvent> {
+        let club = self.world.best_club_for(agent_id)?.name.clone();
+        if let Err(e) = self.world.join_club(agent_id, &club) {
+            debug!(agent = %agent_id, club, error = %e, "Join club failed");
+            return None;
+        }
+        self.club_join_event(&[(agent_id.clone(), club)], EventTrigger::AgentAction, causes, time)
+    }
+
+    /// 立即举行一次考试（预设事件），返回成绩公布事件（没有学生参加时为 None）
+    ///
+    /// `causes` 为触发考试的干预事件。
+    pub fn hold_exam(&mut self, name: &str, kind: ExamKind, causes: Vec<EventId>) -> Option<SimulationEvent> {
+        let records = self.world.sit_exam(name, kind);
+        let time = self.world.clock.current_time().clone();
+        let event = self.exam_event(&records, EventTrigger::UserIntervention, causes, &time)?;
+        self.world.event_log.push(event.clone());
+        Some(event)
+    }
+
+    /// 成绩公布：学生按排名及名次变化产生情绪反应，各科成绩反馈到职业志向
+    fn exam_event(
+        &mut self,
+        records: &[ExamRecord],
+        trigger: EventTrigger,
+        causes: Vec<EventId>,
+        time: &SimulationTime,
+    ) -> Option<SimulationEvent> {
+        let first = records.first()?;
+        let event_id = EventId::new();
+        let weight = first.kind.weight();
+
+        let mut state_changes = Vec::new();
+        for record in records {
+            let transcript = self.world.gradebook.transcript(&record.agent_id);
+            let previous = transcript
+                .len()
+                .checked_sub(2)
+                .map(|i| transcript[i].percentile());
+            let appraisals = [
+                Appraisal::exam_result(record.percentile(), previous).scaled(weight),
+                Appraisal::competition(self.world.environment.pressure_excess(), record.percentile()).scaled(weight),
+            ];
+            state_changes.extend(self.emotion_changes(&record.agent_id, &appraisals));
+            let experiences: Vec<CareerExperience> = record
+                .scores
+                .iter()
+                .map(|score| CareerExperience::SubjectPerformance {
+                    subject: score.subject.clone(),
+                    performance: score.score / 100.0,
+                })
+                .collect();
+            state_changes.extend(self.career_changes(&record.agent_id, &experiences, time, &event_id));
+        }
+
+        let top: Vec<String> = records
+            .iter()
+            .take(3)
+            .filter_map(|r| {
+                let agent = self.world.agents.get(&r.agent_id)?;
+                Some(format!("{}（{:.1}分）", agent.config.name, r.average))
+            })
+            .collect();
+        info!(exam = %first.exam, students = records.len(), "Exam results published");
+
+        let mut narrative = format!("{}成绩公布，年级前三名：{}", first.exam, top.join("、"));
+        let rivalry = rivalry_changes(records, &self.world);
+        if !rivalry.is_empty() {
+            narrative.push_str("。竞争氛围浓厚，名次相邻的同班同学暗暗较劲");
+            state_changes.extend(rivalry);
+        }
+        match self.world.apply_state_changes(&state_changes, Some(&event_id)) {
+            Ok(warnings) => warnings.iter().for_each(|w| warn!(warning = %w, "Exam result change")),
+            Err(e) => warn!(error = %e, "Exam result changes failed"),
+        }
+
+        Some(SimulationEvent {
+            id: event_id,
+            event_type: EventType::Academic,
+            trigger,
+            timestamp: time.clone(),
+            involved_agents: records.iter().map(|r| r.agent_id.clone()).collect(),
+            narrative,
+            state_changes,
+            intensity: 0.5 * weight,
+            location: None,
+            causes,
+        })
+    }
+
+    /// 社团招新：按兴趣批量入社，返回入社事件（无人加入时为 None）
+    pub fn recruit_club_members(&mut self, causes: Vec<EventId>) -> Option<SimulationEvent> {
+        let joined = self.world.recruit_club_members();
+        let time = self.world.clock.current_time().clone();
+        let event = self.club_join_event(&joined, EventTrigger::UserIntervention, causes, &time)?;
+        self.world.event_log.push(event.clone());
+        Some(event)
+    }
+
+    /// 调整环境参数 (0.0 ~ 1.0)：立即作用于世界状态与启发式策略，并记入干预日志
+    pub fn set_parameter(&mut self, parameter: &EnvironmentParameter, value: f32) -> Result<f32, SimulationError> {
+        let previous = self
+            .interventions
+            .apply_parameter_change(parameter, value, &mut self.world, &mut self.config)?;
+        let environment = self.world.environment;
+        self.heuristic_policy.social_factor = environment.social_factor();
+        self.heuristic_policy.competition_factor = 1.0 + environment.pressure_excess();
+        info!(parameter = parameter.label(), previous, value, "Environment parameter changed");
+        Ok(previous)
+    }
+
+    /// 手动触发预设事件，并广播干预结果
+    pub async fn trigger_preset(&mut self, preset: &PresetEvent) -> Result<InterventionOutcome, SimulationError> {
+        let outcome = self.run_preset(preset, None).await?;
+        let _ = self.event_tx.send(SimulationUpdate::Intervention {
+            time: self.world.clock.current_time().clone(),
+            events: outcome.events.clone(),
+            warnings: outcome.warnings.clone(),
+        });
+        Ok(outcome)
+    }
+
+    /// 执行预设事件干预：确定受影响的 Agent → 应用状态变更与情绪评估 → 引发后续事件 → 写入记忆
+    ///
+    /// 面向学生的全校活动（考试、招新、运动会）只影响学生；新同学转入会真正加入一名学生。
+    async fn run_preset(
+        &mut self,
+        preset: &PresetEvent,
+        condition: Option<String>,
+    ) -> Result<InterventionOutcome, SimulationError> {
+        let time = self.world.clock.current_time().clone();
+        let affected = match preset {
+            PresetEvent::NewStudent { name } => {
+                let personality = match self.config.personality_model {
+                    PersonalityModelKind::Mbti => generate_diverse_personalities(1).remove(0),
+                    PersonalityModelKind::BigFive => generate_diverse_big_five(1).remove(0),
+                };
+                let student = AgentBuilder::new().name(name).personality(personality).build(&time);
+                let student_id = student.id.clone();
+                self.add_agent(student);
+                info!(name = %name, "New student joined");
+
+                let mut affected = vec![student_id.clone()];
+                if let Some(group) = self.world.class_group_of(&student_id) {
+                    affected.extend(group.members.iter().filter(|id| **id != student_id).cloned());
+                }
+                affected
+            }
+            PresetEvent::MidtermExam | PresetEvent::ClubRecruitment | PresetEvent::SportsMeet => self
+                .world
+                .agents_in_scope(&preset_scope(preset))
+                .into_iter()
+                .filter(|id| !self.world.is_staff(id))
+                .collect(),
+            _ => self.world.agents_in_scope(&preset_scope(preset)),
+        };
+
+        let mut event = self
+            .interventions
+            .trigger_preset_event(preset, affected, condition, &self.world, &time);
+        let mut warnings = self.world.apply_state_changes(&event.state_changes, Some(&event.id))?;
+        warnings.extend(self.appraise_event(&mut event));
+        self.world.event_log.push(event.clone());
+        info!(event = ?preset, agents = event.involved_agents.len(), "Intervention triggered");
+
+        let causes = vec![event.id.clone()];
+        let mut events = vec![event];
+        match preset {
+            PresetEvent::MidtermExam => events.extend(self.hold_exam("期中考试", ExamKind::Midterm, causes)),
+            PresetEvent::ClubRecruitment => events.extend(self.recruit_club_members(causes)),
+            _ => {}
+        }
+
+        for event in &events {
+            for agent_id in &event.involved_agents {
+                self.update_agent_memory(agent_id, event).await;
+            }
+        }
+
+        Ok(InterventionOutcome { events, warnings })
+    }
+
+    /// 执行到期的计划干预，干预日志中记下触发条件
+    async fn fire_scheduled(&mut self, due: DueIntervention) -> Result<InterventionOutcome, SimulationError> {
+        let students = match due.action {
+            ScheduledAction::Preset { event } => return self.run_preset(&event, Some(due.condition)).await,
+            ScheduledAction::CounselorChat { agent } => {
+                let mut students: Vec<AgentId> = agent.into_iter().collect();
+                for agent_id in due.agents {
+                    if !students.contains(&agent_id) {
+                        students.push(agent_id);
+                    }
+                }
+                students
+            }
+        };
+
+        let mut outcome = InterventionOutcome::default();
+        let mut counselors: Vec<&AgentState> = self
+            .world
+            .agents
+            .values()
+            .filter(|a| a.config.role == AgentRole::Counselor)
+            .collect();
+        counselors.sort_by(|a, b| a.config.name.cmp(&b.config.name));
+        let Some(counselor_id) = counselors.first().map(|a| a.id.clone()) else {
+            outcome
+                .warnings
+                .push(format!("No counselor for scheduled chat ({})", due.condition));
+            return Ok(outcome);
+        };
+
+        let time = self.world.clock.current_time().clone();
+        for student_id in students {
+            // 原因是学生最近参与的事件
+            let causes: Vec<EventId> = self
+                .world
+                .event_log
+                .iter()
+                .rev()
+                .filter(|e| e.involved_agents.contains(&student_id))
+                .take(3)
+                .map(|e| e.id.clone())
+                .collect();
+            let Some(event) = self.staff_effect(
+                &counselor_id,
+                &student_id,
+                &IntentType::Counsel,
+                EventTrigger::UserIntervention,
+                causes,
+                &time,
+            ) else {
+                continue;
+            };
+            self.interventions.record_chat(
+                &student_id,
+                "counselor",
+                &event.narrative,
+                Some(due.condition.clone()),
+                &time,
+            );
+            self.world.event_log.push(event.clone());
+            for agent_id in &event.involved_agents {
+                self.update_agent_memory(agent_id, &event).await;
+            }
+            outcome.events.push(event);
+        }
+        Ok(outcome)
+    }
+
+    /// 入社 → 社交事件（按社团归并：“小红、小明加入了编程社”）
+    fn club_join_event(
+        &self,
+        joined: &[(AgentId, String)],
+        trigger: EventTrigger,
+        causes: Vec<EventId>,
+        time: &SimulationTime,
+    ) -> Option<SimulationEvent> {
+        if joined.is_empty() {
+            return None;
+        }
+        let mut by_club: Vec<(&str, Vec<String>)> = Vec::new();
+        for (agent_id, club) in joined {
+            let name = self
+                .world
+                .agents
+                .get(agent_id)
+                .map(|a| a.config.name.clone())
+                .unwrap_or_default();
+            match by_club.iter_mut().find(|(c, _)| c == club) {
+                Some((_, names)) => names.push(name),
+                None => by_club.push((club, vec![name])),
+            }
+        }
+        let narrative = by_club
+            .iter()
+            .map(|(club, names)| format!("{}加入了{club}", names.join("、")))
+            .collect::<Vec<_>>()
+            .join("；");
+        info!(count = joined.len(), "Agents joined clubs");
+
+        Some(SimulationEvent {
+            id: EventId::new(),
+            event_type: EventType::SocialInteraction,
+            trigger,
+            timestamp: time.clone(),
+            involved_agents: joined.iter().map(|(id, _)| id.clone()).collect(),
+            narrative,
+            state_changes: Vec::new(),
+            intensity: 0.3,
+            location: None,
+            causes,
+        })
+    }
+
+    /// 途中偶遇 → 社交事件，两人略微熟悉
+    fn encounter_event(&mut self, encounter: &Encounter, time: &SimulationTime) -> SimulationEvent {
+        let (a, b) = &encounter.agents;
+        self.world.relationships.update_closeness(a, b, 0.02, time);
+
+        let name = |id: &AgentId| {
+            self.world
+                .agents
+                .get(id)
+                .map(|agent| agent.config.name.clone())
+                .unwrap_or_default()
+        };
+        let narrative = format!(
+            "{}和{}在{}偶遇，顺路聊了几句",
+            name(a),
+            name(b),
+            self.world.location_name(&encounter.location)
+        );
+
+        SimulationEvent {
+            id: EventId::new(),
+            event_type: EventType::SocialInteraction,
+            trigger: EventTrigger::Random,
+            timestamp: time.clone(),
+            involved_agents: vec![a.clone(), b.clone()],
+            narrative,
+            state_changes: Vec::new(),
+            intensity: 0.2,
+            location: Some(encounter.location.clone()),
+            causes: Vec::new(),
+        }
+    }
+
+    /// 参加活动的意图 → 职业志向经历
+    fn record_activity_experiences(&mut self, intents: &[BehaviorIntent], event: &SimulationEvent) {
+        for intent in intents
+            .iter()
+            .filter(|i| i.intent_type == IntentType::JoinActivity)
+        {
+            let Some(agent) = self.world.agents.get_mut(&intent.agent_id) else {
+                continue;
+            };
+            let name = match &agent.activity {
+                AgentActivity::Activity { name } => name.clone(),
+                _ => "课外活动".to_string(),
+            };
+            self.career_evolution.record_experience(
+                &mut agent.config.career_aspiration,
+                &CareerExperience::ClubActivity { name },
+                &event.timestamp,
+                Some(event.id.clone()),
+            );
+        }
+    }
+
+    /// 事件情绪评估 — GM 已显式给出情绪变更的 Agent 不重复评估
+    ///
+    /// 评估结果追加到事件的状态变更中并随事件落地，返回应用时的警告。
+    fn appraise_event(&mut self, event: &mut SimulationEvent) -> Vec<String> {
+        let explicit: Vec<AgentId> = event
+            .state_changes
+            .iter()
+            .filter_map(|c| match TypedChange::parse(c).ok()?.target {
+                ChangeTarget::Agent {
+                    agent,
+                    field: AgentField::Valence | AgentField::Arousal | AgentField::Stress,
+                } => self.world.resolve_agent(&agent),
+                _ => None,
+            })
+            .collect();
+
+        let appraisal = Appraisal::for_event_type(&event.event_type).scaled(event.intensity.clamp(0.0, 1.0));
+        let changes: Vec<StateChange> = event
+            .involved_agents
+            .iter()
+            .filter(|id| !explicit.contains(id))
+            .flat_map(|id| self.emotion_changes(id, &[appraisal]))
+            .collect();
+        let warnings = match self.world.apply_state_changes(&changes, Some(&event.id)) {
+            Ok(warnings) => warnings,
+            Err(e) => vec![format!("Event {}: appraisal changes failed: {e}", event.id)],
+        };
+        event.state_changes.extend(changes);
+        warnings
+    }
+
+    /// 情绪冲击 → 情绪字段的增量变更
+    ///
+    /// 冲击在情绪副本上依次评估，实际落地（每步上限与审计）交给 `apply_state_changes`。
+    fn emotion_changes(&self, agent_id: &AgentId, appraisals: &[Appraisal]) -> Vec<StateChange> {
+        let Some(agent) = self.world.agents.get(agent_id) else {
+            return Vec::new();
+        };
+        let mut emotion = agent.emotion.clone();
+        for appraisal in appraisals {
+            self.emotion_model
+                .apply(&mut emotion, &agent.config.personality, &agent.abilities, *appraisal);
+        }
+        [
+            ("valence", emotion.valence - agent.emotion.valence),
+            ("arousal", emotion.arousal - agent.emotion.arousal),
+            ("stress", emotion.stress - agent.emotion.stress),
+        ]
+        .into_iter()
+        .filter(|(_, delta)| delta.abs() > f32::EPSILON)
+        .map(|(field, delta)| StateChange {
+            target: format!("agent:{agent_id}.emotion.{field}"),
+            change_type: ChangeType::Delta,
+            value: serde_json::json!(delta),
+        })
+        .collect()
+    }
+
+    /// 职业经历 → 清晰度的增量变更
+    ///
+    /// 跨档位的历史记录直接写入志向，清晰度本身随事件落地。
+    fn career_changes(
+        &mut self,
+        agent_id: &AgentId,
+        experiences: &[CareerExperience],
+        time: &SimulationTime,
+        event_id: &EventId,
+    ) -> Vec<StateChange> {
+        let Some(agent) = self.world.agents.get_mut(agent_id) else {
+            return Vec::new();
+        };
+        let mut aspiration = agent.config.career_aspiration.clone();
+        for experience in experiences {
+            self.career_evolution
+                .record_experience(&mut aspiration, experience, time, Some(event_id.clone()));
+        }
+        let delta = aspiration.clarity - agent.config.career_aspiration.clarity;
+        agent.config.career_aspiration.history = aspiration.history;
+        if delta.abs() <= f32::EPSILON {
+            return Vec::new();
+        }
+        vec![StateChange {
+            target: format!("agent:{agent_id}.career.clarity"),
+            change_type: ChangeType::Delta,
+            value: serde_json::json!(delta),
+        }]
+    }
+
+    /// 单个 Agent 的启发式决策（不调用 LLM）
+    fn heuristic_decision(
         &self,
         agent_id: &AgentId,
         current_time: &SimulationTime,
     ) -> Result<BehaviorIntent, SimulationError> {
         let agent = self.world.get_agent(agent_id)?;
 
+        if agent.config.role.is_staff() {
+            let students: Vec<ObservedStudent> = self
+                .world
+                .agents_at_location(&agent.location)
+                .into_iter()
+                .filter(|a| !a.config.role.is_staff())
+                .map(|a| ObservedStudent {
+                    id: a.id.clone(),
+                    name: a.config.name.clone(),
+                    stress: a.emotion.stress,
+                    valence: a.emotion.valence,
+                    recent_performance: self.world.academics.recent_performance(&a.id, 5),
+                })
+                .collect();
+            return Ok(self.heuristic_policy.decide_staff(agent, &students, current_time));
+        }
+
+        let nearby: Vec<NearbyAgent> = self
+            .world
+            .agents_at_location(&agent.location)
+            .into_iter()
+            .filter(|a| a.id != *agent_id)
+            .map(|a| NearbyAgent {
+                id: a.id.clone(),
+                name: a.config.name.clone(),
+                closeness: self
+                    .world
+                    .relationships
+                    .get(agent_id, &a.id)
+                    .map(|r| r.closeness)
+                    .unwrap_or(0.0),
+            })
+            .collect();
+
+        Ok(self.heuristic_policy.decide(agent, &nearby, current_time))
+    }
+
+    /// 感知到的事件（启发式决策的原因）
+    fn perceived_events(&self, agent_id: &AgentId) -> Vec<EventId> {
+        self.world
+            .perceive(agent_id)
+            .map(|p| p.event_ids)
+            .unwrap_or_default()
+    }
+
+    /// 单个 Agent 的决策过程，返回意图及其原因
+    async fn agent_decision(
+        &self,
+        agent_id: &AgentId,
+        current_time: &SimulationTime,
+    ) -> Result<(BehaviorIntent, Vec<EventId>), SimulationError> {
+        let agent = self.world.get_agent(agent_id)?;
+
         // 检索相关记忆
         let query = MemoryQuery {
             query_text: self.world.describe_situation(agent_id),
@@ -182,33 +1395,19 @@ impl<L: LlmProvider, M: MemoryStore> SimulationRunner<L, M> {
         let memory_texts: Vec<String> = memories.iter().map(|m| m.memory.content.clone()).collect();
 
         // 构建情境上下文
+        let perception = self.world.perceive(agent_id)?;
+        let mut causes = perception.event_ids.clone();
+        for event_id in memories.iter().filter_map(|m| m.memory.event_id.clone()) {
+            if !causes.contains(&event_id) {
+                causes.push(event_id);
+            }
+        }
         let context = ai_school_core::types::SituationContext {
             agent_id: agent_id.clone(),
             time: current_time.clone(),
-            perception: ai_school_core::types::Perception {
-                nearby_agents: self
-                    .world
-                    .agents_at_location(&agent.location)
-                    .iter()
-                    .filter(|a| a.id != *agent_id)
-                    .map(|a| a.id.clone())
-                    .collect(),
-                observable_activities: Vec::new(),
-                environment_description: self.world.describe_situation(agent_id),
-                recent_events: self
-                    .world
-                    .event_log
-                    .iter()
-                    .rev()
-                    .take(3)
-                    .map(|e| e.narrative.clone())
-                    .collect(),
-            },
+            perception,
             relevant_memories: memory_texts,
-            emotional_summary: format!(
-                "情绪: 效价={:.1}, 唤醒={:.1}, 压力={:.1}",
-                agent.emotion.valence, agent.emotion.arousal, agent.emotion.stress
-            ),
+            emotional_summary: emotion_description(&agent.emotion),
             personality_description: ai_school_agent::personality::personality_description(
                 &agent.config.personality,
             ),
@@ -224,9 +1423,9 @@ impl<L: LlmProvider, M: MemoryStore> SimulationRunner<L, M> {
         let response = self.llm.complete(&request).await?;
 
         // 解析行为意图
-        let intent = CognitionProcessor::act(agent, &response.content);
+        let intent = CognitionProcessor::act(agent, &context, &response.content);
 
-        Ok(intent)
+        Ok((intent, causes))
     }
 
     /// 更新 Agent 记忆
@@ -310,3 +1509,333 @@ impl<L: LlmProvider, M: MemoryStore> SimulationRunner<L, M> {
         let _ = self.event_tx.send(SimulationUpdate::SpeedChanged { speed });
     }
 }
+
+/// 按名单条目构建 Agent，未指定的人格按配置的人格模型生成
+fn roster_agent(
+    entry: &RosterEntry,
+    start_time: &SimulationTime,
+    model: PersonalityModelKind,
+) -> AgentState {
+    let personality = entry.personality().unwrap_or_else(|| match model {
+        PersonalityModelKind::Mbti => generate_diverse_personalities(1).remove(0),
+        PersonalityModelKind::BigFive => generate_diverse_big_five(1).remove(0),
+    });
+
+    let mut builder = AgentBuilder::new()
+        .name(&entry.name)
+        .personality(personality)
+        .role(entry.role.clone());
+    if let Some(career) = &entry.career {
+        let mut aspiration = CareerAspiration {
+            ideal_career: career.ideal_career.clone(),
+            category: career.category.clone(),
+            subject_preferences: Vec::new(),
+            clarity: 0.5,
+            history: Vec::new(),
+        };
+        aspiration.subject_preferences = default_subject_preferences(&aspiration);
+        builder = builder.career(aspiration);
+    }
+    if let Some(background) = &entry.background {
+        builder = builder.background(background);
+    }
+    if let Some(age) = entry.age {
+        builder = builder.age(age);
+    }
+    if let Some(location) = &entry.location {
+        builder = builder.location(location.clone());
+    }
+
+    builder.build(start_time)
+}
+
+/// 场景中各 Agent 决策原因的并集
+fn scene_causes(scene: &Scene, decision_causes: &HashMap<AgentId, Vec<EventId>>) -> Vec<EventId> {
+    let mut causes: Vec<EventId> = Vec::new();
+    for agent_id in scene.agents() {
+        for cause in decision_causes.get(&agent_id).into_iter().flatten() {
+            if !causes.contains(cause) {
+                causes.push(cause.clone());
+            }
+        }
+    }
+    causes
+}
+
+/// 竞争压力高于默认水平时，成绩名次相邻的同班同学亲密度下降
+fn rivalry_changes(records: &[ExamRecord], world: &WorldState) -> Vec<StateChange> {
+    let excess = world.environment.pressure_excess();
+    if excess <= 0.0 {
+        return Vec::new();
+    }
+    let group = |id: &AgentId| world.class_group_of(id).map(|g| g.name.as_str());
+    records
+        .windows(2)
+        .filter(|pair| group(&pair[0].agent_id).is_some() && group(&pair[0].agent_id) == group(&pair[1].agent_id))
+        .map(|pair| StateChange {
+            target: format!("relationship[{},{}].closeness", pair[0].agent_id, pair[1].agent_id),
+            change_type: ChangeType::Delta,
+            value: serde_json::json!(-0.1 * excess),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_school_agent::builder::generate_random_agents;
+    use ai_school_llm::providers::mock::MockLlmProvider;
+    use ai_school_memory::store::InMemoryStore;
+    use ai_school_world::causality::TraceQuery;
+    use ai_school_world::changes::{ChangeAudit, ChangeOutcome};
+
+    fn runner(config: SimulationConfig) -> SimulationRunner<MockLlmProvider, InMemoryStore> {
+        let mut scenario = Scenario::bundled();
+        scenario.config = config;
+        let mut runner = SimulationRunner::from_scenario(
+            Arc::new(MockLlmProvider::default()),
+            Arc::new(InMemoryStore::new()),
+            &scenario,
+        )
+        .unwrap();
+        let time = runner.world.clock.current_time().clone();
+        for agent in generate_random_agents(6, &time) {
+            runner.add_agent(agent);
+        }
+        runner
+    }
+
+    fn audit_of<'a>(
+        runner: &'a SimulationRunner<MockLlmProvider, InMemoryStore>,
+        event: &SimulationEvent,
+    ) -> Vec<&'a ChangeAudit> {
+        runner
+            .world
+            .change_audit
+            .iter()
+            .filter(|a| a.source_event.as_ref() == Some(&event.id))
+            .collect()
+    }
+
+    #[test]
+    fn test_agents_spawn_at_scenario_spawn_location() {
+        let mut scenario = Scenario::bundled();
+        scenario.routines.spawn_location = LocationId("library".to_string());
+        let mut runner = SimulationRunner::from_scenario(
+            Arc::new(MockLlmProvider::default()),
+            Arc::new(InMemoryStore::new()),
+            &scenario,
+        )
+        .unwrap();
+        let time = runner.world.clock.current_time().clone();
+        runner.add_agent(AgentBuilder::new().name("新同学").build(&time));
+
+        for agent in runner.world.agents.values() {
+            let expected = if agent.config.role.is_staff() { "staff_office" } else { "library" };
+            assert_eq!(agent.location.0, expected, "{}", agent.config.name);
+        }
+    }
+
+    #[test]
+    fn test_exam_results_go_through_change_limits() {
+        let mut config = SimulationConfig::default();
+        config.change_limits.per_tick.insert("emotion.arousal".to_string(), 0.01);
+        let mut runner = runner(config);
+        assert_eq!(runner.world.change_limits.limit("emotion.arousal"), Some(0.01));
+
+        let event = runner.hold_exam("期中考试", ExamKind::Midterm, Vec::new()).unwrap();
+        let audit = audit_of(&runner, &event);
+        assert!(!event.state_changes.is_empty());
+        assert_eq!(audit.len(), event.state_changes.len());
+
+        // 考试让每个学生都更紧张，但一步内最多变化 0.01
+        let arousal: Vec<&&ChangeAudit> = audit.iter().filter(|a| a.target.ends_with("emotion.arousal")).collect();
+        assert_eq!(arousal.len(), 6);
+        for entry in arousal {
+            assert_eq!(entry.outcome, ChangeOutcome::Clamped);
+            let delta = entry.new_value.as_f64().unwrap() - entry.old_value.as_f64().unwrap();
+            assert!(delta <= 0.01 + 1e-6);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_heuristic_mode_makes_no_llm_calls() {
+        let mut runner = runner(SimulationConfig {
+            decision_mode: DecisionMode::Heuristic,
+            ..Default::default()
+        });
+        for _ in 0..24 {
+            runner.step().await.unwrap();
+        }
+        assert_eq!(runner.llm.call_count(), 0);
+
+        // 切回 LLM 模式后多人场景照常由 LLM 仲裁
+        runner.config.decision_mode = DecisionMode::Llm;
+        runner.step().await.unwrap();
+        assert!(runner.llm.call_count() > 0);
+    }
+
+    #[test]
+    fn test_study_emotion_goes_through_state_changes() {
+        let mut runner = runner(SimulationConfig::default());
+        let student = runner.world.agents.values().find(|a| !a.config.role.is_staff()).unwrap().id.clone();
+        let subject = runner.world.subjects[0].name.clone();
+
+        let time = runner.world.clock.current_time().clone();
+        let mut events = Vec::new();
+        runner
+            .record_study(&[(student.clone(), subject)], StudySession::Class, Vec::new(), &time, &mut events)
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        let audit = runner.world.audit_for(&student, 0, usize::MAX);
+        assert!(audit.iter().any(|a| a.target.contains(".emotion.")));
+        assert!(audit
+            .iter()
+            .all(|a| a.outcome != ChangeOutcome::Rejected && a.source_event.as_ref() == Some(&events[0].id)));
+    }
+
+    #[tokio::test]
+    async fn test_stress_changes_trace_back_to_their_events() {
+        let mut runner = runner(SimulationConfig {
+            decision_mode: DecisionMode::Heuristic,
+            ..SimulationConfig::default()
+        });
+        for _ in 0..24 {
+            runner.step().await.unwrap();
+        }
+        // 拥挤与学习带来的情绪变化都有来源事件
+        assert!(runner.world.change_audit.iter().any(|a| a.target.ends_with(".emotion.stress")));
+        assert!(runner
+            .world
+            .change_audit
+            .iter()
+            .all(|a| a.source_event.as_ref().is_some_and(|id| runner.world.event(id).is_some())));
+
+        let exam = runner.hold_exam("期中考试", ExamKind::Midterm, Vec::new()).unwrap();
+        let stressed = runner
+            .world
+            .change_audit
+            .iter()
+            .rev()
+            .find(|a| a.source_event.as_ref() == Some(&exam.id) && a.target.ends_with(".emotion.stress"))
+            .map(|a| a.agents[0].clone())
+            .unwrap();
+        let query = TraceQuery {
+            field: Some("emotion.stress".to_string()),
+            since_tick: Some(exam.timestamp.tick),
+            ..TraceQuery::default()
+        };
+        let trace = runner.world.explain(&stressed, &query);
+        assert!(trace.roots.contains(&exam.id));
+        assert!(trace.changes.iter().any(|a| a.source_event.as_ref() == Some(&exam.id)));
+    }
+
+    #[tokio::test]
+    async fn test_change_audit_is_capped_and_paged() {
+        let mut runner = runner(SimulationConfig {
+            decision_mode: DecisionMode::Heuristic,
+            audit_capacity: 40,
+            ..SimulationConfig::default()
+        });
+        for _ in 0..24 {
+            runner.step().await.unwrap();
+        }
+        runner.hold_exam("期中考试", ExamKind::Midterm, Vec::new()).unwrap();
+        assert_eq!(runner.world.change_audit.len(), 40);
+        // 最早的记录被丢弃，最新的考试记录保留
+        assert!(runner.world.change_audit.iter().all(|a| a.timestamp.tick > 0));
+
+        let agent = runner.world.change_audit.back().unwrap().agents[0].clone();
+        let page = |offset, limit| -> Vec<*const ChangeAudit> {
+            runner.world.audit_for(&agent, offset, limit).into_iter().map(|a| a as *const _).collect()
+        };
+        let all = page(0, usize::MAX);
+        assert_eq!(page(0, 1), all[..1]);
+        assert_eq!(page(1, usize::MAX), all[1..]);
+        assert!(page(all.len(), 10).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_step_events_are_written_to_memory() {
+        let mut runner = runner(SimulationConfig {
+            decision_mode: DecisionMode::Heuristic,
+            ..SimulationConfig::default()
+        });
+        let mut events = Vec::new();
+        for _ in 0..24 {
+            events.extend(runner.step().await.unwrap().events);
+        }
+        // 不只是场景事件：偶遇、社团活动、关系变化等也会写入
+        assert!(events
+            .iter()
+            .any(|e| e.trigger != EventTrigger::AgentAction && e.event_type != EventType::Routine));
+
+        let mut remembered: HashMap<AgentId, Vec<EventId>> = HashMap::new();
+        for agent_id in runner.world.agents.keys() {
+            let memories = runner
+                .memory_store
+                .get_recent(agent_id, MemoryLayer::ShortTerm, usize::MAX)
+                .await
+                .unwrap();
+            remembered.insert(agent_id.clone(), memories.into_iter().filter_map(|m| m.event_id).collect());
+        }
+        for event in &events {
+            for agent_id in &event.involved_agents {
+                let stored = remembered[agent_id].contains(&event.id);
+                assert_eq!(stored, event.event_type != EventType::Routine, "{}", event.narrative);
+            }
+        }
+    }
+
+    #[test]
+    fn test_weekly_drift_shifts_personality_from_events() {
+        let mut runner = runner(SimulationConfig::default());
+        let student = runner.world.agents.values().find(|a| !a.config.role.is_staff()).unwrap().id.clone();
+        runner.world.agents.get_mut(&student).unwrap().config.personality.e_i = 0.0;
+        let time = runner.world.clock.current_time().clone();
+        let mut chat = runner.encounter_event(
+            &Encounter {
+                agents: (student.clone(), runner.world.agents.keys().find(|id| **id != student).unwrap().clone()),
+                location: LocationId("library".to_string()),
+            },
+            &time,
+        );
+        chat.timestamp.elapsed_hours += 1;
+        runner.world.event_log.push(chat.clone());
+
+        let mut next_week = time.clone();
+        next_week.elapsed_hours += 7 * 24;
+        runner.weekly_drift(&next_week);
+        let personality = &runner.world.agents[&student].config.personality;
+        assert!(personality.e_i < 0.0);
+        assert_eq!(personality.shift_history.len(), 1);
+        assert_eq!(personality.shift_history[0].trigger_event_id, chat.id);
+
+        // 一周以前的事件不再计入
+        next_week.elapsed_hours += 7 * 24;
+        runner.weekly_drift(&next_week);
+        assert_eq!(runner.world.agents[&student].config.personality.shift_history.len(), 1);
+    }
+
+    #[test]
+    fn test_staff_effect_is_recorded_on_event() {
+        let mut runner = runner(SimulationConfig::default());
+        let time = runner.world.clock.current_time().clone();
+        let staff = runner.world.agents.values().find(|a| a.config.role.is_staff()).unwrap().id.clone();
+        let student = runner.world.agents.values().find(|a| !a.config.role.is_staff()).unwrap().id.clone();
+        let valence = runner.world.agents[&student].emotion.valence;
+
+        let event = runner
+            .staff_effect(&staff, &student, &IntentType::Praise, EventTrigger::AgentAction, Vec::new(), &time)
+            .unwrap();
+        let targets: Vec<&str> = event.state_changes.iter().map(|c| c.target.as_str()).collect();
+        assert!(targets.contains(&format!("agent:{student}.emotion.valence").as_str()));
+        assert!(targets.contains(&format!("relationship[{staff},{student}].closeness").as_str()));
+        assert_eq!(audit_of(&runner, &event).len(), event.state_changes.len());
+
+        assert!(runner.world.agents[&student].emotion.valence > valence);
+        let relationship = runner.world.relationships.get(&staff, &student).unwrap();
+        assert!(relationship.closeness > 0.0);
+        assert!(relationship.tags.iter().any(|t| t == TEACHER_STUDENT_TAG));
+    }
+}
@@ -24,6 +24,16 @@ impl PromptEngine {
         Ok(Self { env })
     }
 
+    /// 从内嵌的模板源码构建（模板名 → 内容）
+    pub fn from_sources(templates: &[(&str, &str)]) -> Result<Self, LlmError> {
+        let mut env = Environment::new();
+        for (name, content) in templates {
+            env.add_template_owned(name.to_string(), content.to_string())
+                .map_err(|e| LlmError::PromptError(format!("Failed to compile template {name}: {e}")))?;
+        }
+        Ok(Self { env })
+    }
+
     fn load_templates_recursive(
         env: &mut Environment<'static>,
         base_dir: &Path,
@@ -118,4 +128,13 @@ mod tests {
         assert!(result.contains("小明"));
         assert!(result.contains("INTJ"));
     }
+
+    #[test]
+    fn test_prompt_engine_from_sources() {
+        let engine = PromptEngine::from_sources(&[("greeting", "你好，{{ name }}")]).unwrap();
+        let result = engine.render("greeting", &serde_json::json!({"name": "小红"})).unwrap();
+        assert_eq!(result, "你好，小红");
+        assert!(engine.render("missing", &serde_json::json!({})).is_err());
+        assert!(PromptEngine::from_sources(&[("broken", "{% if %}")]).is_err());
+    }
 }
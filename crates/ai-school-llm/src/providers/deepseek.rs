@@ -84,11 +84,7 @@ impl DeepSeekProvider {
                     ChatCompletionRequestMessage::Assistant(
                         async_openai::types::ChatCompletionRequestAssistantMessage {
                             content: Some(async_openai::types::ChatCompletionRequestAssistantMessageContent::Text(msg.content.clone())),
-                            name: None,
-                            tool_calls: None,
-                            refusal: None,
-                            audio: None,
-                            function_call: None,
+                            ..Default::default()
                         },
                     )
                 }
@@ -2,7 +2,10 @@
 //!
 //! 反思结论 → 人格微调信号 → 更新人格参数
 
-use ai_school_core::types::{EventId, PersonalityDimension, PersonalityParams, SimulationTime};
+use ai_school_agent::personality::model_for;
+use ai_school_core::types::{
+    EventId, PersonalityDimension, PersonalityModelKind, PersonalityParams, SimulationTime,
+};
 
 /// 人格演化控制器
 pub struct PersonalityEvolution {
@@ -22,22 +25,18 @@ impl PersonalityEvolution {
 
     /// 评估反思结论是否应该触发人格微调
     ///
-    /// 返回 (维度, 信号强度) 如果应该微调
+    /// 返回 (维度, 信号强度) 如果应该微调。维度代码按 Agent 的人格模型解析，
+    /// 不属于该模型的代码（如 MBTI Agent 的 "O"）忽略，避免切换人格模型。
     pub fn evaluate_reflection(
         &self,
         reflection_impact: &ReflectionImpact,
+        model: PersonalityModelKind,
     ) -> Option<(PersonalityDimension, f32)> {
         if reflection_impact.magnitude.abs() < self.min_delta {
             return None;
         }
 
-        let dimension = match reflection_impact.dimension.as_str() {
-            "EI" => PersonalityDimension::EI,
-            "SN" => PersonalityDimension::SN,
-            "TF" => PersonalityDimension::TF,
-            "JP" => PersonalityDimension::JP,
-            _ => return None,
-        };
+        let dimension = model_for(model).parse_dimension(&reflection_impact.dimension)?;
 
         let signal = if reflection_impact.direction == "positive" {
             reflection_impact.magnitude
@@ -83,3 +82,34 @@ pub struct ReflectionImpact {
     pub direction: String,
     pub magnitude: f32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_school_core::types::BigFiveTrait;
+
+    fn impact(dimension: &str) -> ReflectionImpact {
+        ReflectionImpact {
+            dimension: dimension.to_string(),
+            direction: "negative".to_string(),
+            magnitude: 0.03,
+        }
+    }
+
+    #[test]
+    fn test_codes_resolve_through_agent_model() {
+        let evolution = PersonalityEvolution::default();
+
+        assert_eq!(
+            evolution.evaluate_reflection(&impact("EI"), PersonalityModelKind::Mbti),
+            Some((PersonalityDimension::EI, -0.03))
+        );
+        assert_eq!(
+            evolution.evaluate_reflection(&impact("N"), PersonalityModelKind::BigFive),
+            Some((PersonalityDimension::BigFive(BigFiveTrait::Neuroticism), -0.03))
+        );
+        // 其他模型的代码不会让 Agent 换模型
+        assert_eq!(evolution.evaluate_reflection(&impact("O"), PersonalityModelKind::Mbti), None);
+        assert_eq!(evolution.evaluate_reflection(&impact("EI"), PersonalityModelKind::BigFive), None);
+    }
+}
@@ -2,8 +2,11 @@
 //!
 //! 累积经历评估 → 触发反思 → 生成语义记忆
 
+use ai_school_agent::personality::model_for;
 use ai_school_core::traits::llm::{ChatMessage, CompletionRequest, MessageRole};
-use ai_school_core::types::{AgentId, Memory, MemoryLayer, MemoryId, SimulationTime};
+use ai_school_core::types::{
+    AgentId, Memory, MemoryLayer, MemoryId, PersonalityModelKind, SimulationTime,
+};
 
 /// 反思触发器
 pub struct ReflectionTrigger {
@@ -36,9 +39,12 @@ impl ReflectionTrigger {
 }
 
 /// 构建反思 Prompt
+///
+/// 只列出 Agent 人格模型的演化维度代码（MBTI: EI/SN/TF/JP，大五: O/C/E/A/N）。
 pub fn build_reflection_request(
     agent_name: &str,
     personality_desc: &str,
+    model: PersonalityModelKind,
     recent_memories: &[Memory],
     current_time: &SimulationTime,
 ) -> CompletionRequest {
@@ -66,12 +72,13 @@ pub fn build_reflection_request(
   "summary": "反思总结（2-3句话）",
   "insight": "核心洞察",
   "personality_impact": {{
-    "dimension": "EI/SN/TF/JP 或 null",
+    "dimension": "{dimensions} 或 null",
     "direction": "positive/negative 或 null",
     "magnitude": 0.01-0.05 或 0
   }}
 }}"#,
         current_time.display(),
+        dimensions = model_for(model).dimension_codes().join("/"),
     );
 
     CompletionRequest {
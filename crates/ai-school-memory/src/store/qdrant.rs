@@ -12,7 +12,7 @@ use tracing::{debug, info};
 use ai_school_core::config::QdrantConfig;
 use ai_school_core::error::MemoryError;
 use ai_school_core::traits::MemoryStore;
-use ai_school_core::types::{AgentId, Memory, MemoryId, MemoryLayer, MemoryQuery, ScoredMemory};
+use ai_school_core::types::{AgentId, Memory, MemoryId, MemoryLayer, MemoryQuery, ScoredMemory, SimulationTime};
 
 /// Qdrant 记忆存储
 pub struct QdrantMemoryStore {
@@ -93,6 +93,11 @@ impl QdrantMemoryStore {
             "timestamp".to_string(),
             serde_json::json!(memory.timestamp.tick),
         );
+        // 完整的仿真时间，取回时还原（按开学以来的小时数计算时间衰减）
+        payload.insert(
+            "time".to_string(),
+            serde_json::Value::String(serde_json::to_string(&memory.timestamp).unwrap_or_default()),
+        );
         payload.insert(
             "importance".to_string(),
             serde_json::json!(memory.importance),
@@ -131,7 +136,7 @@ impl MemoryStore for QdrantMemoryStore {
 
         self.client
             .upsert_points(
-                UpsertPointsBuilder::new(&self.collection_name(), vec![point]),
+                UpsertPointsBuilder::new(self.collection_name(), vec![point]),
             )
             .await
             .map_err(|e| MemoryError::StoreError(format!("Failed to store memory: {e}")))?;
@@ -165,7 +170,7 @@ impl MemoryStore for QdrantMemoryStore {
             .client
             .search_points(
                 SearchPointsBuilder::new(
-                    &self.collection_name(),
+                    self.collection_name(),
                     query_embedding.to_vec(),
                     query.limit as u64,
                 )
@@ -186,6 +191,12 @@ impl MemoryStore for QdrantMemoryStore {
                     .and_then(|v| v.as_double())
                     .unwrap_or(0.5) as f32;
 
+                let timestamp: SimulationTime = payload
+                    .get("time")
+                    .and_then(|v| v.as_str())
+                    .and_then(|time| serde_json::from_str(time).ok())
+                    .unwrap_or_default();
+
                 let point_id_str = match &point.id {
                     Some(pid) => format!("{:?}", pid),
                     None => return None,
@@ -196,7 +207,7 @@ impl MemoryStore for QdrantMemoryStore {
                     agent_id: agent_id.clone(),
                     layer: MemoryLayer::LongTerm,
                     content,
-                    timestamp: ai_school_core::types::SimulationTime::new(),
+                    timestamp: timestamp.clone(),
                     importance,
                     emotion_valence: payload
                         .get("emotion_valence")
@@ -205,7 +216,7 @@ impl MemoryStore for QdrantMemoryStore {
                     event_id: None,
                     tags: Vec::new(),
                     access_count: 0,
-                    last_accessed: ai_school_core::types::SimulationTime::new(),
+                    last_accessed: timestamp,
                 };
 
                 Some(ScoredMemory {
@@ -255,7 +266,7 @@ impl MemoryStore for QdrantMemoryStore {
 
         self.client
             .delete_points(
-                DeletePointsBuilder::new(&self.collection_name())
+                DeletePointsBuilder::new(self.collection_name())
                     .points(PointsIdsList { ids: point_ids }),
             )
             .await
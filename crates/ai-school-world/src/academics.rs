@@ -0,0 +1,193 @@
+//! 学业成长追踪
+//!
+//! 上课/自习 → 学业反馈 → 学业能力成长，保留学科级表现历史供分析。
+
+use serde::{Deserialize, Serialize};
+
+use ai_school_core::types::{AgentId, SimulationTime};
+
+/// 学习场景
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StudySession {
+    /// 课堂学习
+    Class,
+    /// 自主学习（Study 意图）
+    SelfStudy,
+}
+
+impl StudySession {
+    /// 学习场景对能力成长的权重
+    pub fn weight(&self) -> f32 {
+        match self {
+            StudySession::Class => 1.0,
+            StudySession::SelfStudy => 0.6,
+        }
+    }
+}
+
+/// 单次学科表现记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubjectPerformance {
+    pub agent_id: AgentId,
+    pub subject: String,
+    pub timestamp: SimulationTime,
+    pub session: StudySession,
+    /// 有效难度（基础难度 + 调整）
+    pub difficulty: f32,
+    /// 表现 (0.0 ~ 1.0)
+    pub performance: f32,
+    /// 满意度 (-1.0 ~ 1.0)
+    pub satisfaction: f32,
+    /// 本次学习后的学业能力
+    pub academic_after: f32,
+}
+
+/// 学业追踪器
+pub struct AcademicTracker {
+    /// 学习速率（每次课堂学习的最大能力变化）
+    pub learning_rate: f32,
+    records: Vec<SubjectPerformance>,
+}
+
+impl AcademicTracker {
+    pub fn new(learning_rate: f32) -> Self {
+        Self {
+            learning_rate,
+            records: Vec::new(),
+        }
+    }
+
+    /// 计算学业能力变化量
+    ///
+    /// 表现高于 0.4 时能力提升（越接近上限越慢），低于 0.4 时轻微退步。
+    pub fn ability_delta(&self, session: StudySession, performance: f32, ability: f32) -> f32 {
+        let signal = performance - 0.4;
+        let headroom = if signal > 0.0 { 1.0 - ability } else { ability };
+        self.learning_rate * session.weight() * signal * headroom
+    }
+
+    /// 记录一次学科表现
+    pub fn record(&mut self, record: SubjectPerformance) {
+        self.records.push(record);
+    }
+
+    /// 获取某个 Agent 的全部学科表现
+    pub fn history(&self, agent_id: &AgentId) -> Vec<&SubjectPerformance> {
+        self.records
+            .iter()
+            .filter(|r| r.agent_id == *agent_id)
+            .collect()
+    }
+
+    /// 获取某个 Agent 在某学科的平均表现
+    pub fn average_performance(&self, agent_id: &AgentId, subject: &str) -> Option<f32> {
+        let scores: Vec<f32> = self
+            .records
+            .iter()
+            .filter(|r| r.agent_id == *agent_id && r.subject == subject)
+            .map(|r| r.performance)
+            .collect();
+
+        if scores.is_empty() {
+            None
+        } else {
+            Some(scores.iter().sum::<f32>() / scores.len() as f32)
+        }
+    }
+
+    /// 获取某个 Agent 最近 N 次学习的平均表现
+    pub fn recent_performance(&self, agent_id: &AgentId, limit: usize) -> Option<f32> {
+        let scores: Vec<f32> = self
+            .records
+            .iter()
+            .rev()
+            .filter(|r| r.agent_id == *agent_id)
+            .take(limit)
+            .map(|r| r.performance)
+            .collect();
+
+        if scores.is_empty() {
+            None
+        } else {
+            Some(scores.iter().sum::<f32>() / scores.len() as f32)
+        }
+    }
+
+    /// 获取某个 Agent 在某学科最近 N 次学习的平均表现
+    pub fn recent_subject_performance(
+        &self,
+        agent_id: &AgentId,
+        subject: &str,
+        limit: usize,
+    ) -> Option<f32> {
+        let scores: Vec<f32> = self
+            .records
+            .iter()
+            .rev()
+            .filter(|r| r.agent_id == *agent_id && r.subject == subject)
+            .take(limit)
+            .map(|r| r.performance)
+            .collect();
+
+        if scores.is_empty() {
+            None
+        } else {
+            Some(scores.iter().sum::<f32>() / scores.len() as f32)
+        }
+    }
+
+    /// 获取所有记录
+    pub fn all_records(&self) -> &[SubjectPerformance] {
+        &self.records
+    }
+
+    /// 导出所有记录为 JSON
+    pub fn export_json(&self) -> serde_json::Value {
+        serde_json::json!(self.records)
+    }
+}
+
+impl Default for AcademicTracker {
+    fn default() -> Self {
+        Self::new(0.02)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ability_delta_direction() {
+        let tracker = AcademicTracker::default();
+        assert!(tracker.ability_delta(StudySession::Class, 0.8, 0.5) > 0.0);
+        assert!(tracker.ability_delta(StudySession::Class, 0.1, 0.5) < 0.0);
+
+        // 自习成长慢于课堂
+        let class = tracker.ability_delta(StudySession::Class, 0.8, 0.5);
+        let self_study = tracker.ability_delta(StudySession::SelfStudy, 0.8, 0.5);
+        assert!(self_study < class);
+    }
+
+    #[test]
+    fn test_average_performance() {
+        let mut tracker = AcademicTracker::default();
+        let agent = AgentId::new();
+        for performance in [0.4, 0.6] {
+            tracker.record(SubjectPerformance {
+                agent_id: agent.clone(),
+                subject: "数学".to_string(),
+                timestamp: SimulationTime::new(),
+                session: StudySession::Class,
+                difficulty: 0.7,
+                performance,
+                satisfaction: 0.0,
+                academic_after: 0.5,
+            });
+        }
+
+        let avg = tracker.average_performance(&agent, "数学").unwrap();
+        assert!((avg - 0.5).abs() < 1e-6);
+        assert!(tracker.average_performance(&agent, "语文").is_none());
+    }
+}
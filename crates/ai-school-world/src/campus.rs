@@ -1,150 +1,8 @@
 //! 校园空间模型
 //!
-//! 定义校园功能区域、Agent 位置追踪、空间邻接关系。
+//! 校园功能区域与空间邻接关系。布局由场景文件定义（见 `scenario`）。
 
-use ai_school_core::types::{Location, LocationId, LocationType};
-
-/// 创建默认校园布局
-pub fn create_default_campus() -> Vec<Location> {
-    vec![
-        Location {
-            id: LocationId("classroom_math".to_string()),
-            name: "数学教室".to_string(),
-            location_type: LocationType::Classroom { subject: Some("数学".to_string()) },
-            capacity: 40,
-            position: (200.0, 100.0),
-            adjacent: vec![
-                LocationId("classroom_chinese".to_string()),
-                LocationId("hallway".to_string()),
-            ],
-        },
-        Location {
-            id: LocationId("classroom_chinese".to_string()),
-            name: "语文教室".to_string(),
-            location_type: LocationType::Classroom { subject: Some("语文".to_string()) },
-            capacity: 40,
-            position: (350.0, 100.0),
-            adjacent: vec![
-                LocationId("classroom_math".to_string()),
-                LocationId("classroom_english".to_string()),
-                LocationId("hallway".to_string()),
-            ],
-        },
-        Location {
-            id: LocationId("classroom_english".to_string()),
-            name: "英语教室".to_string(),
-            location_type: LocationType::Classroom { subject: Some("英语".to_string()) },
-            capacity: 40,
-            position: (500.0, 100.0),
-            adjacent: vec![
-                LocationId("classroom_chinese".to_string()),
-                LocationId("hallway".to_string()),
-            ],
-        },
-        Location {
-            id: LocationId("classroom_science".to_string()),
-            name: "理科实验室".to_string(),
-            location_type: LocationType::Classroom { subject: Some("科学".to_string()) },
-            capacity: 30,
-            position: (200.0, 200.0),
-            adjacent: vec![LocationId("hallway".to_string())],
-        },
-        Location {
-            id: LocationId("library".to_string()),
-            name: "图书馆".to_string(),
-            location_type: LocationType::Library,
-            capacity: 50,
-            position: (650.0, 150.0),
-            adjacent: vec![
-                LocationId("hallway".to_string()),
-                LocationId("study_room".to_string()),
-            ],
-        },
-        Location {
-            id: LocationId("study_room".to_string()),
-            name: "自习室".to_string(),
-            location_type: LocationType::StudyRoom,
-            capacity: 20,
-            position: (650.0, 250.0),
-            adjacent: vec![LocationId("library".to_string()), LocationId("hallway".to_string())],
-        },
-        Location {
-            id: LocationId("playground".to_string()),
-            name: "操场".to_string(),
-            location_type: LocationType::Playground,
-            capacity: 200,
-            position: (400.0, 400.0),
-            adjacent: vec![
-                LocationId("hallway".to_string()),
-                LocationId("cafeteria".to_string()),
-            ],
-        },
-        Location {
-            id: LocationId("cafeteria".to_string()),
-            name: "食堂".to_string(),
-            location_type: LocationType::Cafeteria,
-            capacity: 100,
-            position: (200.0, 400.0),
-            adjacent: vec![
-                LocationId("playground".to_string()),
-                LocationId("hallway".to_string()),
-            ],
-        },
-        Location {
-            id: LocationId("dormitory".to_string()),
-            name: "宿舍".to_string(),
-            location_type: LocationType::Dormitory,
-            capacity: 100,
-            position: (100.0, 500.0),
-            adjacent: vec![LocationId("hallway".to_string())],
-        },
-        Location {
-            id: LocationId("club_room".to_string()),
-            name: "社团活动室".to_string(),
-            location_type: LocationType::ClubRoom { club_name: None },
-            capacity: 30,
-            position: (500.0, 300.0),
-            adjacent: vec![LocationId("hallway".to_string()), LocationId("auditorium".to_string())],
-        },
-        Location {
-            id: LocationId("auditorium".to_string()),
-            name: "礼堂".to_string(),
-            location_type: LocationType::Auditorium,
-            capacity: 300,
-            position: (650.0, 350.0),
-            adjacent: vec![LocationId("club_room".to_string()), LocationId("hallway".to_string())],
-        },
-        Location {
-            id: LocationId("rest_area".to_string()),
-            name: "休息区".to_string(),
-            location_type: LocationType::RestArea,
-            capacity: 30,
-            position: (350.0, 300.0),
-            adjacent: vec![LocationId("hallway".to_string())],
-        },
-        Location {
-            id: LocationId("hallway".to_string()),
-            name: "走廊".to_string(),
-            location_type: LocationType::RestArea,
-            capacity: 500,
-            position: (350.0, 200.0),
-            adjacent: vec![
-                LocationId("classroom_math".to_string()),
-                LocationId("classroom_chinese".to_string()),
-                LocationId("classroom_english".to_string()),
-                LocationId("classroom_science".to_string()),
-                LocationId("library".to_string()),
-                LocationId("study_room".to_string()),
-                LocationId("playground".to_string()),
-                LocationId("cafeteria".to_string()),
-                LocationId("dormitory".to_string()),
-                LocationId("club_room".to_string()),
-                LocationId("auditorium".to_string()),
-                LocationId("rest_area".to_string()),
-            ],
-        },
-    ]
-}
+use ai_school_core::types::{Location, LocationId};
 
 /// 检查两个位置是否相邻
 pub fn are_adjacent(locations: &[Location], from: &LocationId, to: &LocationId) -> bool {
@@ -154,3 +12,88 @@ pub fn are_adjacent(locations: &[Location], from: &LocationId, to: &LocationId)
         .map(|l| l.adjacent.contains(to))
         .unwrap_or(false)
 }
+
+/// 两个位置之间的直线距离（地图坐标单位）
+pub fn distance(a: &Location, b: &Location) -> f32 {
+    let (dx, dy) = (a.position.0 - b.position.0, a.position.1 - b.position.1);
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// 路径总长度（地图坐标单位）；路径含未知位置时返回 None
+pub fn path_length(locations: &[Location], path: &[LocationId]) -> Option<f32> {
+    let find = |id: &LocationId| locations.iter().find(|l| &l.id == id);
+    path.windows(2)
+        .map(|pair| Some(distance(find(&pair[0])?, find(&pair[1])?)))
+        .sum()
+}
+
+/// 沿邻接关系的最短路径（按坐标距离加权），含起点与终点
+///
+/// 起点等于终点时返回只含起点的路径；不可达时返回 None。
+pub fn shortest_path(
+    locations: &[Location],
+    from: &LocationId,
+    to: &LocationId,
+) -> Option<Vec<LocationId>> {
+    let index = |id: &LocationId| locations.iter().position(|l| &l.id == id);
+    let (start, goal) = (index(from)?, index(to)?);
+
+    // 位置数量很少，直接 O(n²) Dijkstra
+    let mut dist = vec![f32::INFINITY; locations.len()];
+    let mut prev: Vec<Option<usize>> = vec![None; locations.len()];
+    let mut visited = vec![false; locations.len()];
+    dist[start] = 0.0;
+
+    while let Some(current) = (0..locations.len())
+        .filter(|&i| !visited[i] && dist[i].is_finite())
+        .min_by(|&a, &b| dist[a].total_cmp(&dist[b]))
+    {
+        if current == goal {
+            break;
+        }
+        visited[current] = true;
+
+        for next in locations[current].adjacent.iter().filter_map(index) {
+            let candidate = dist[current] + distance(&locations[current], &locations[next]);
+            if candidate < dist[next] {
+                dist[next] = candidate;
+                prev[next] = Some(current);
+            }
+        }
+    }
+
+    if !dist[goal].is_finite() {
+        return None;
+    }
+
+    let mut path = vec![locations[goal].id.clone()];
+    let mut node = goal;
+    while let Some(p) = prev[node] {
+        path.push(locations[p].id.clone());
+        node = p;
+    }
+    path.reverse();
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::Scenario;
+
+    fn id(s: &str) -> LocationId {
+        LocationId(s.to_string())
+    }
+
+    #[test]
+    fn test_shortest_path_goes_through_hallway() {
+        let locations = Scenario::bundled().locations;
+        let path = shortest_path(&locations, &id("dormitory"), &id("library")).unwrap();
+        assert_eq!(path, vec![id("dormitory"), id("hallway"), id("library")]);
+
+        let same = shortest_path(&locations, &id("library"), &id("library")).unwrap();
+        assert_eq!(same, vec![id("library")]);
+
+        assert!(shortest_path(&locations, &id("library"), &id("nowhere")).is_none());
+    }
+}
@@ -0,0 +1,158 @@
+//! 事件因果图
+//!
+//! 每个事件记录其直接原因（`SimulationEvent::causes`），由此沿因果边回溯：
+//! Agent 的状态变更 → 来源事件 → 导致该事件的感知、记忆与干预。
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use ai_school_core::types::{AgentId, EventId, EventTrigger, EventType, SimulationEvent, SimulationTime};
+
+use crate::changes::ChangeAudit;
+
+/// 因果图中的一个事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CausalNode {
+    pub event_id: EventId,
+    pub timestamp: SimulationTime,
+    pub event_type: EventType,
+    pub trigger: EventTrigger,
+    pub narrative: String,
+    pub involved_agents: Vec<AgentId>,
+    pub causes: Vec<EventId>,
+    /// 距回溯起点的步数（起点为 0）
+    pub depth: usize,
+    /// 是否为用户干预
+    pub intervention: bool,
+}
+
+/// 一次回溯的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CausalTrace {
+    /// 回溯起点
+    pub roots: Vec<EventId>,
+    /// 起点事件对应的状态变更
+    pub changes: Vec<ChangeAudit>,
+    /// 回溯到的事件（按深度、再按时间排列）
+    pub nodes: Vec<CausalNode>,
+    /// 链条上的用户干预
+    pub interventions: Vec<EventId>,
+}
+
+/// 回溯查询
+#[derive(Debug, Clone)]
+pub struct TraceQuery {
+    /// 只看某个字段的变更（如 `emotion.stress`）；为空时以 Agent 参与的所有事件为起点
+    pub field: Option<String>,
+    /// 只看该 tick 之后（含）的变更与事件
+    pub since_tick: Option<u64>,
+    /// 最多取最近的多少个起点
+    pub limit: usize,
+    pub max_depth: usize,
+}
+
+impl Default for TraceQuery {
+    fn default() -> Self {
+        Self {
+            field: None,
+            since_tick: None,
+            limit: 20,
+            max_depth: 5,
+        }
+    }
+}
+
+/// 从 `roots` 沿因果边广度优先回溯，最多 `max_depth` 步；日志中找不到的原因略过
+pub fn trace(events: &[SimulationEvent], roots: &[EventId], max_depth: usize) -> Vec<CausalNode> {
+    let index: HashMap<&EventId, &SimulationEvent> = events.iter().map(|e| (&e.id, e)).collect();
+    let mut visited: HashSet<&EventId> = HashSet::new();
+    let mut queue: VecDeque<(&EventId, usize)> = roots.iter().map(|id| (id, 0)).collect();
+    let mut nodes = Vec::new();
+
+    while let Some((id, depth)) = queue.pop_front() {
+        let Some(event) = index.get(id) else {
+            continue;
+        };
+        if !visited.insert(&event.id) {
+            continue;
+        }
+        nodes.push(CausalNode {
+            event_id: event.id.clone(),
+            timestamp: event.timestamp.clone(),
+            event_type: event.event_type.clone(),
+            trigger: event.trigger.clone(),
+            narrative: event.narrative.clone(),
+            involved_agents: event.involved_agents.clone(),
+            causes: event.causes.clone(),
+            depth,
+            intervention: event.trigger == EventTrigger::UserIntervention,
+        });
+        if depth < max_depth {
+            queue.extend(event.causes.iter().map(|cause| (cause, depth + 1)));
+        }
+    }
+
+    nodes.sort_by_key(|n| (n.depth, n.timestamp.tick));
+    nodes
+}
+
+/// 组装回溯结果
+pub fn causal_trace(
+    events: &[SimulationEvent],
+    roots: Vec<EventId>,
+    changes: Vec<ChangeAudit>,
+    max_depth: usize,
+) -> CausalTrace {
+    let nodes = trace(events, &roots, max_depth);
+    CausalTrace {
+        interventions: nodes
+            .iter()
+            .filter(|n| n.intervention)
+            .map(|n| n.event_id.clone())
+            .collect(),
+        roots,
+        changes,
+        nodes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(trigger: EventTrigger, causes: Vec<EventId>) -> SimulationEvent {
+        SimulationEvent {
+            id: EventId::new(),
+            event_type: EventType::SocialInteraction,
+            trigger,
+            timestamp: SimulationTime::new(),
+            involved_agents: Vec::new(),
+            narrative: String::new(),
+            state_changes: Vec::new(),
+            intensity: 0.5,
+            location: None,
+            causes,
+        }
+    }
+
+    #[test]
+    fn test_trace_walks_back_to_intervention() {
+        let intervention = event(EventTrigger::UserIntervention, Vec::new());
+        let exam = event(EventTrigger::UserIntervention, vec![intervention.id.clone()]);
+        let chat = event(EventTrigger::AgentAction, vec![exam.id.clone(), EventId::new()]);
+        // 环状引用不会死循环
+        let mut quarrel = event(EventTrigger::AgentAction, vec![chat.id.clone()]);
+        quarrel.causes.push(quarrel.id.clone());
+        let events = vec![intervention.clone(), exam.clone(), chat.clone(), quarrel.clone()];
+
+        let result = causal_trace(&events, vec![quarrel.id.clone()], Vec::new(), 5);
+        let depths: Vec<usize> = result.nodes.iter().map(|n| n.depth).collect();
+        assert_eq!(depths, vec![0, 1, 2, 3]);
+        assert_eq!(result.interventions, vec![exam.id.clone(), intervention.id.clone()]);
+
+        let shallow = trace(&events, &[quarrel.id.clone()], 1);
+        assert_eq!(shallow.len(), 2);
+        assert_eq!(shallow[1].event_id, chat.id);
+    }
+}
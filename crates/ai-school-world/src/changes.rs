@@ -0,0 +1,555 @@
+//! 状态变更目标语言
+//!
+//! GM 输出的 `StateChange::target` 文本 → 类型化的 `ChangeTarget`，
+//! 连同变更类型与取值一起校验；出错时指出是哪一段目标、哪个字段或哪个取值有问题。
+//! 应用时按字段限制每步的变化幅度，并把每条变更（含被截断、被拒绝的）记入审计日志。
+//!
+//! ```text
+//! agent:<名字或ID>.<字段>            如 agent:小明.emotion.valence
+//! relationship[<名字A>,<名字B>].<字段>  如 relationship[小明,小红].closeness
+//! ```
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+pub use ai_school_core::config::ChangeLimits;
+use ai_school_core::error::WorldError;
+use ai_school_core::types::{AgentActivity, AgentId, ChangeType, EventId, SimulationTime, StateChange};
+
+use crate::social::Ability;
+
+/// 变更目标
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeTarget {
+    /// `agent:<key>.<field>`，key 为名字或 UUID
+    Agent { agent: String, field: AgentField },
+    /// `relationship[<a>,<b>].<field>`
+    Relationship {
+        agent_a: String,
+        agent_b: String,
+        field: RelationshipField,
+    },
+}
+
+/// Agent 上可变更的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentField {
+    Valence,
+    Arousal,
+    Stress,
+    Ability(Ability),
+    Location,
+    Activity,
+    CurrentThought,
+    Clubs,
+    CareerClarity,
+}
+
+/// 关系上可变更的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelationshipField {
+    Closeness,
+    Trust,
+    Tags,
+}
+
+/// 字段取值的类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueKind {
+    /// 数值及其取值范围
+    Number { min: f32, max: f32 },
+    /// 位置 ID
+    Location,
+    /// 活动（AgentActivity 或活动名称）
+    Activity,
+    Text,
+    /// 社团名称
+    Club,
+    /// 关系标签
+    Tag,
+}
+
+/// 字段说明（同时用于解析和生成给 GM 的语法说明）
+pub struct FieldSpec {
+    pub path: &'static str,
+    pub scope: Scope,
+    pub kind: ValueKind,
+    pub change_types: &'static [ChangeType],
+    pub description: &'static str,
+}
+
+/// 字段所属的目标
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Agent,
+    Relationship,
+}
+
+const NUMERIC: &[ChangeType] = &[ChangeType::Delta, ChangeType::Set];
+const SET: &[ChangeType] = &[ChangeType::Set];
+const TEXT: &[ChangeType] = &[ChangeType::Set, ChangeType::Append];
+const APPEND: &[ChangeType] = &[ChangeType::Append];
+
+const UNIT: ValueKind = ValueKind::Number { min: 0.0, max: 1.0 };
+
+/// 所有可变更字段
+pub const FIELDS: &[FieldSpec] = &[
+    FieldSpec { path: "emotion.valence", scope: Scope::Agent, kind: ValueKind::Number { min: -1.0, max: 1.0 }, change_types: NUMERIC, description: "情绪效价" },
+    FieldSpec { path: "emotion.arousal", scope: Scope::Agent, kind: UNIT, change_types: NUMERIC, description: "情绪唤醒度" },
+    FieldSpec { path: "emotion.stress", scope: Scope::Agent, kind: UNIT, change_types: NUMERIC, description: "压力" },
+    FieldSpec { path: "ability.academic", scope: Scope::Agent, kind: UNIT, change_types: NUMERIC, description: "学业能力" },
+    FieldSpec { path: "ability.social", scope: Scope::Agent, kind: UNIT, change_types: NUMERIC, description: "社交能力" },
+    FieldSpec { path: "ability.resilience", scope: Scope::Agent, kind: UNIT, change_types: NUMERIC, description: "心理韧性" },
+    FieldSpec { path: "ability.creativity", scope: Scope::Agent, kind: UNIT, change_types: NUMERIC, description: "创造力" },
+    FieldSpec { path: "location", scope: Scope::Agent, kind: ValueKind::Location, change_types: SET, description: "前往的位置 ID" },
+    FieldSpec { path: "activity", scope: Scope::Agent, kind: ValueKind::Activity, change_types: SET, description: "当前活动名称" },
+    FieldSpec { path: "current_thought", scope: Scope::Agent, kind: ValueKind::Text, change_types: TEXT, description: "当前想法" },
+    FieldSpec { path: "clubs", scope: Scope::Agent, kind: ValueKind::Club, change_types: APPEND, description: "加入社团" },
+    FieldSpec { path: "career.clarity", scope: Scope::Agent, kind: UNIT, change_types: NUMERIC, description: "职业清晰度" },
+    FieldSpec { path: "closeness", scope: Scope::Relationship, kind: ValueKind::Number { min: -1.0, max: 1.0 }, change_types: NUMERIC, description: "亲密度" },
+    FieldSpec { path: "trust", scope: Scope::Relationship, kind: UNIT, change_types: NUMERIC, description: "信任度" },
+    FieldSpec { path: "tags", scope: Scope::Relationship, kind: ValueKind::Tag, change_types: APPEND, description: "关系标签（挚友/朋友等由亲密度推导，不能直接添加）" },
+];
+
+impl AgentField {
+    /// 按字段路径（如 `emotion.stress`）解析
+    pub fn from_path(path: &str) -> Option<Self> {
+        Some(match path {
+            "emotion.valence" => AgentField::Valence,
+            "emotion.arousal" => AgentField::Arousal,
+            "emotion.stress" => AgentField::Stress,
+            "ability.academic" => AgentField::Ability(Ability::Academic),
+            "ability.social" => AgentField::Ability(Ability::Social),
+            "ability.resilience" => AgentField::Ability(Ability::Resilience),
+            "ability.creativity" => AgentField::Ability(Ability::Creativity),
+            "location" => AgentField::Location,
+            "activity" => AgentField::Activity,
+            "current_thought" => AgentField::CurrentThought,
+            "clubs" => AgentField::Clubs,
+            "career.clarity" => AgentField::CareerClarity,
+            _ => return None,
+        })
+    }
+}
+
+impl RelationshipField {
+    fn from_path(path: &str) -> Option<Self> {
+        Some(match path {
+            "closeness" => RelationshipField::Closeness,
+            "trust" => RelationshipField::Trust,
+            "tags" => RelationshipField::Tags,
+            _ => return None,
+        })
+    }
+}
+
+/// 校验后的变更操作
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeOp {
+    /// 数值增量（已确认为有限数）
+    Delta(f32),
+    /// 数值设定（已夹到取值范围内）
+    SetNumber(f32),
+    SetText(String),
+    SetActivity(AgentActivity),
+    Append(String),
+}
+
+/// 类型化的状态变更
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedChange {
+    pub target: ChangeTarget,
+    pub op: ChangeOp,
+}
+
+/// 状态变更错误
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum ChangeError {
+    #[error("`{target}`: {reason}")]
+    Syntax { target: String, reason: String },
+
+    #[error("`{target}`: unknown field `{field}`, expected one of: {expected}")]
+    UnknownField {
+        target: String,
+        field: String,
+        expected: String,
+    },
+
+    #[error("`{target}` does not support {change_type:?}, use {allowed}")]
+    UnsupportedChangeType {
+        target: String,
+        change_type: ChangeType,
+        allowed: String,
+    },
+
+    #[error("`{target}` expects {expected}, got {value}")]
+    InvalidValue {
+        target: String,
+        expected: String,
+        value: serde_json::Value,
+    },
+
+    #[error("`{target}`: agent `{agent}` not found")]
+    UnknownAgent { target: String, agent: String },
+
+    #[error("`{target}`: per-tick limit {limit} already used up")]
+    LimitExceeded { target: String, limit: f32 },
+}
+
+impl From<ChangeError> for WorldError {
+    fn from(e: ChangeError) -> Self {
+        WorldError::InvalidStateChange(e.to_string())
+    }
+}
+
+impl ChangeTarget {
+    /// 解析目标文本
+    pub fn parse(target: &str) -> Result<Self, ChangeError> {
+        let syntax = |reason: &str| ChangeError::Syntax {
+            target: target.to_string(),
+            reason: reason.to_string(),
+        };
+        let expected = |scope: Scope| {
+            FIELDS
+                .iter()
+                .filter(|f| f.scope == scope)
+                .map(|f| f.path)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let unknown = |field: &str, scope: Scope| ChangeError::UnknownField {
+            target: target.to_string(),
+            field: field.to_string(),
+            expected: expected(scope),
+        };
+
+        if let Some(rest) = target.strip_prefix("agent:") {
+            let (agent, path) = rest
+                .split_once('.')
+                .ok_or_else(|| syntax("missing field after agent, e.g. agent:小明.emotion.valence"))?;
+            let agent = agent.trim();
+            if agent.is_empty() {
+                return Err(syntax("empty agent name"));
+            }
+            let field = AgentField::from_path(path).ok_or_else(|| unknown(path, Scope::Agent))?;
+            return Ok(ChangeTarget::Agent {
+                agent: agent.to_string(),
+                field,
+            });
+        }
+
+        if let Some(rest) = target.strip_prefix("relationship[") {
+            let (pair, path) = rest
+                .split_once(']')
+                .ok_or_else(|| syntax("missing `]` after relationship pair"))?;
+            let path = path
+                .strip_prefix('.')
+                .ok_or_else(|| syntax("missing field after relationship[A,B]"))?;
+            let names: Vec<&str> = pair.split(',').map(str::trim).collect();
+            let [agent_a, agent_b] = names[..] else {
+                return Err(syntax("relationship needs exactly two names"));
+            };
+            if agent_a.is_empty() || agent_b.is_empty() {
+                return Err(syntax("empty agent name in relationship"));
+            }
+            if agent_a == agent_b {
+                return Err(syntax("relationship needs two different agents"));
+            }
+            let field =
+                RelationshipField::from_path(path).ok_or_else(|| unknown(path, Scope::Relationship))?;
+            return Ok(ChangeTarget::Relationship {
+                agent_a: agent_a.to_string(),
+                agent_b: agent_b.to_string(),
+                field,
+            });
+        }
+
+        Err(syntax("target must start with `agent:` or `relationship[`"))
+    }
+
+    /// 目标字段的说明
+    pub fn spec(&self) -> &'static FieldSpec {
+        let (scope, path) = match self {
+            ChangeTarget::Agent { field, .. } => (Scope::Agent, field.path()),
+            ChangeTarget::Relationship { field, .. } => (Scope::Relationship, field.path()),
+        };
+        FIELDS
+            .iter()
+            .find(|f| f.scope == scope && f.path == path)
+            .expect("every field has a spec")
+    }
+}
+
+impl AgentField {
+    pub fn path(&self) -> &'static str {
+        match self {
+            AgentField::Valence => "emotion.valence",
+            AgentField::Arousal => "emotion.arousal",
+            AgentField::Stress => "emotion.stress",
+            AgentField::Ability(Ability::Academic) => "ability.academic",
+            AgentField::Ability(Ability::Social) => "ability.social",
+            AgentField::Ability(Ability::Resilience) => "ability.resilience",
+            AgentField::Ability(Ability::Creativity) => "ability.creativity",
+            AgentField::Location => "location",
+            AgentField::Activity => "activity",
+            AgentField::CurrentThought => "current_thought",
+            AgentField::Clubs => "clubs",
+            AgentField::CareerClarity => "career.clarity",
+        }
+    }
+}
+
+impl RelationshipField {
+    pub fn path(&self) -> &'static str {
+        match self {
+            RelationshipField::Closeness => "closeness",
+            RelationshipField::Trust => "trust",
+            RelationshipField::Tags => "tags",
+        }
+    }
+}
+
+impl TypedChange {
+    /// 解析并校验一条状态变更：目标语法、变更类型与取值类型
+    pub fn parse(change: &StateChange) -> Result<Self, ChangeError> {
+        let target = ChangeTarget::parse(&change.target)?;
+        let spec = target.spec();
+        if !spec.change_types.contains(&change.change_type) {
+            return Err(ChangeError::UnsupportedChangeType {
+                target: change.target.clone(),
+                change_type: change.change_type.clone(),
+                allowed: spec
+                    .change_types
+                    .iter()
+                    .map(|c| format!("{c:?}"))
+                    .collect::<Vec<_>>()
+                    .join("/"),
+            });
+        }
+
+        let invalid = |expected: &str| ChangeError::InvalidValue {
+            target: change.target.clone(),
+            expected: expected.to_string(),
+            value: change.value.clone(),
+        };
+        let text = || {
+            change
+                .value
+                .as_str()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .ok_or_else(|| invalid("a non-empty string"))
+        };
+
+        let op = match (spec.kind, &change.change_type) {
+            (ValueKind::Number { min, max }, change_type) => {
+                let value = change
+                    .value
+                    .as_f64()
+                    .map(|v| v as f32)
+                    .filter(|v| v.is_finite())
+                    .ok_or_else(|| invalid(&format!("a number in {min}..{max}")))?;
+                match change_type {
+                    ChangeType::Delta => ChangeOp::Delta(value),
+                    _ => ChangeOp::SetNumber(value.clamp(min, max)),
+                }
+            }
+            (ValueKind::Activity, _) => {
+                let activity = serde_json::from_value::<AgentActivity>(change.value.clone())
+                    .ok()
+                    .or_else(|| text().ok().map(|name| AgentActivity::Activity { name }))
+                    .ok_or_else(|| invalid("an activity name or AgentActivity object"))?;
+                ChangeOp::SetActivity(activity)
+            }
+            (_, ChangeType::Append) => ChangeOp::Append(text()?),
+            (_, _) => ChangeOp::SetText(text()?),
+        };
+
+        Ok(Self { target, op })
+    }
+}
+
+/// 本步已用掉的变化幅度
+#[derive(Debug, Default)]
+pub struct ChangeBudget {
+    tick: u64,
+    used: HashMap<String, f32>,
+}
+
+impl ChangeBudget {
+    /// 申请一次变化，返回上限内允许的部分（进入新的一步时清零）
+    pub fn take(&mut self, tick: u64, key: String, limit: f32, delta: f32) -> f32 {
+        if tick != self.tick {
+            self.tick = tick;
+            self.used.clear();
+        }
+        let used = self.used.entry(key).or_default();
+        let total = (*used + delta).clamp(-limit, limit);
+        let allowed = total - *used;
+        *used = total;
+        allowed
+    }
+}
+
+/// 变更的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangeOutcome {
+    Applied,
+    /// 超出每步上限或取值范围，按截断后的值应用
+    Clamped,
+    Rejected,
+}
+
+/// 审计日志中的一条变更
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeAudit {
+    pub timestamp: SimulationTime,
+    /// 变更来源事件
+    pub source_event: Option<EventId>,
+    pub target: String,
+    pub change_type: ChangeType,
+    pub requested: serde_json::Value,
+    /// 涉及的 Agent（解析失败时为空）
+    pub agents: Vec<AgentId>,
+    pub old_value: serde_json::Value,
+    pub new_value: serde_json::Value,
+    pub outcome: ChangeOutcome,
+    /// 被截断或拒绝的原因
+    pub reason: Option<String>,
+}
+
+/// 给 GM 的目标语法说明（每个字段一行）
+pub fn target_grammar() -> String {
+    FIELDS
+        .iter()
+        .map(|f| {
+            let target = match f.scope {
+                Scope::Agent => format!("agent:名字.{}", f.path),
+                Scope::Relationship => format!("relationship[名字A,名字B].{}", f.path),
+            };
+            let value = match f.kind {
+                ValueKind::Number { min, max } => format!("数值 {min}~{max}"),
+                ValueKind::Location => "位置 ID".to_string(),
+                ValueKind::Activity => "活动名称".to_string(),
+                ValueKind::Text => "文本".to_string(),
+                ValueKind::Club => "社团名称".to_string(),
+                ValueKind::Tag => "标签文本".to_string(),
+            };
+            let change_types: Vec<String> = f.change_types.iter().map(|c| format!("{c:?}")).collect();
+            format!("- {target}：{}，{value}，{}", f.description, change_types.join("/"))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 给 GM 的 `state_changes` 数组 JSON Schema
+pub fn state_changes_schema() -> serde_json::Value {
+    let agent_fields: Vec<&str> = FIELDS.iter().filter(|f| f.scope == Scope::Agent).map(|f| f.path).collect();
+    let relationship_fields: Vec<&str> = FIELDS
+        .iter()
+        .filter(|f| f.scope == Scope::Relationship)
+        .map(|f| f.path)
+        .collect();
+    let pattern = format!(
+        r"^(agent:[^.\[\]]+\.({})|relationship\[[^,\]]+,[^,\]]+\]\.({}))$",
+        agent_fields.join("|").replace('.', r"\."),
+        relationship_fields.join("|"),
+    );
+
+    serde_json::json!({
+        "type": "array",
+        "items": {
+            "type": "object",
+            "properties": {
+                "target": {
+                    "type": "string",
+                    "pattern": pattern,
+                    "description": target_grammar(),
+                },
+                "change_type": {"type": "string", "enum": ["Delta", "Set", "Append"]},
+                "value": {"type": ["number", "string", "object"]}
+            },
+            "required": ["target", "change_type", "value"]
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(target: &str, change_type: ChangeType, value: serde_json::Value) -> StateChange {
+        StateChange {
+            target: target.to_string(),
+            change_type,
+            value,
+        }
+    }
+
+    #[test]
+    fn test_parse_targets() {
+        assert_eq!(
+            ChangeTarget::parse("agent:小明.ability.academic").unwrap(),
+            ChangeTarget::Agent {
+                agent: "小明".to_string(),
+                field: AgentField::Ability(Ability::Academic),
+            }
+        );
+        assert_eq!(
+            ChangeTarget::parse("relationship[小明, 小红].tags").unwrap(),
+            ChangeTarget::Relationship {
+                agent_a: "小明".to_string(),
+                agent_b: "小红".to_string(),
+                field: RelationshipField::Tags,
+            }
+        );
+
+        let err = ChangeTarget::parse("agent:小明.emotion.joy").unwrap_err();
+        assert!(matches!(&err, ChangeError::UnknownField { field, .. } if field == "emotion.joy"));
+        assert!(err.to_string().contains("emotion.valence"));
+        assert!(matches!(ChangeTarget::parse("relationship[小明].trust"), Err(ChangeError::Syntax { .. })));
+        assert!(matches!(ChangeTarget::parse("school.mood"), Err(ChangeError::Syntax { .. })));
+    }
+
+    #[test]
+    fn test_validate_change_type_and_value() {
+        let parsed = TypedChange::parse(&change("agent:小明.emotion.stress", ChangeType::Set, 1.5.into())).unwrap();
+        assert_eq!(parsed.op, ChangeOp::SetNumber(1.0));
+
+        let err = TypedChange::parse(&change("agent:小明.emotion.stress", ChangeType::Append, 0.1.into())).unwrap_err();
+        assert!(matches!(err, ChangeError::UnsupportedChangeType { .. }));
+
+        let err = TypedChange::parse(&change("relationship[小明,小红].closeness", ChangeType::Delta, "多一点".into()))
+            .unwrap_err();
+        assert!(matches!(err, ChangeError::InvalidValue { .. }));
+
+        let parsed = TypedChange::parse(&change("agent:小明.activity", ChangeType::Set, "排练话剧".into())).unwrap();
+        assert_eq!(parsed.op, ChangeOp::SetActivity(AgentActivity::Activity { name: "排练话剧".to_string() }));
+        let parsed = TypedChange::parse(&change("agent:小明.activity", ChangeType::Set, "Resting".into())).unwrap();
+        assert_eq!(parsed.op, ChangeOp::SetActivity(AgentActivity::Resting));
+    }
+
+    #[test]
+    fn test_budget_limits_cumulative_delta_per_tick() {
+        let mut budget = ChangeBudget::default();
+        let key = || "a:emotion.stress".to_string();
+        assert_eq!(budget.take(1, key(), 0.3, 0.2), 0.2);
+        assert!((budget.take(1, key(), 0.3, 0.2) - 0.1).abs() < 1e-6);
+        assert_eq!(budget.take(1, key(), 0.3, 0.2), 0.0);
+        // 反方向仍可变化，新的一步重新计算
+        assert!((budget.take(1, key(), 0.3, -0.5) + 0.5).abs() < 1e-6);
+        assert_eq!(budget.take(2, key(), 0.3, 0.5), 0.3);
+    }
+
+    #[test]
+    fn test_schema_pattern_covers_all_fields() {
+        let schema = state_changes_schema();
+        let pattern = schema["items"]["properties"]["target"]["pattern"].as_str().unwrap();
+        for field in FIELDS {
+            assert!(pattern.contains(&field.path.replace('.', r"\.")));
+        }
+        assert_eq!(target_grammar().lines().count(), FIELDS.len());
+    }
+}
@@ -4,7 +4,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use ai_school_core::types::LocationId;
+use ai_school_core::types::{AgentId, LocationId};
 
 /// 学科定义
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,11 +13,19 @@ pub struct Subject {
     /// 基础难度 (0.0 ~ 1.0)
     pub base_difficulty: f32,
     /// 当前难度调整（可通过干预修改）
+    #[serde(default)]
     pub difficulty_modifier: f32,
     /// 对应教室
     pub classroom: LocationId,
 }
 
+impl Subject {
+    /// 有效难度 = 基础难度 + 难度调整
+    pub fn effective_difficulty(&self) -> f32 {
+        (self.base_difficulty + self.difficulty_modifier).clamp(0.0, 1.0)
+    }
+}
+
 /// 课程时间段
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClassSchedule {
@@ -27,60 +35,63 @@ pub struct ClassSchedule {
     pub period: u32,
     /// 学科
     pub subject: String,
+    /// 上课地点（缺省为学科对应教室）
+    #[serde(default)]
+    pub location: Option<LocationId>,
+}
+
+/// 班级：拥有独立的课程表与班级教室
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassGroup {
+    /// 班级名称，如“高一(1)班”
+    pub name: String,
+    /// 班级教室：晚自习及无专用教室的课程（如班会）在此进行
+    pub homeroom: LocationId,
+    pub schedule: Vec<ClassSchedule>,
+    #[serde(default)]
+    pub members: Vec<AgentId>,
+    /// 任课教师（各自教授其角色中的学科）
+    #[serde(default)]
+    pub teachers: Vec<AgentId>,
+    /// 班主任：晚自习在班级教室看班
+    #[serde(default)]
+    pub head_teacher: Option<AgentId>,
+}
+
+impl ClassGroup {
+    /// 某天某节的课程
+    pub fn class_at(&self, day_of_week: u32, period: u32) -> Option<&ClassSchedule> {
+        self.schedule
+            .iter()
+            .find(|c| c.day_of_week == day_of_week && c.period == period)
+    }
 }
 
-/// 创建默认课程表
-pub fn create_default_schedule() -> Vec<ClassSchedule> {
-    vec![
-        // 周一
-        ClassSchedule { day_of_week: 1, period: 1, subject: "数学".to_string() },
-        ClassSchedule { day_of_week: 1, period: 2, subject: "语文".to_string() },
-        ClassSchedule { day_of_week: 1, period: 3, subject: "英语".to_string() },
-        ClassSchedule { day_of_week: 1, period: 4, subject: "物理".to_string() },
-        ClassSchedule { day_of_week: 1, period: 5, subject: "体育".to_string() },
-        // 周二
-        ClassSchedule { day_of_week: 2, period: 1, subject: "英语".to_string() },
-        ClassSchedule { day_of_week: 2, period: 2, subject: "数学".to_string() },
-        ClassSchedule { day_of_week: 2, period: 3, subject: "化学".to_string() },
-        ClassSchedule { day_of_week: 2, period: 4, subject: "语文".to_string() },
-        ClassSchedule { day_of_week: 2, period: 5, subject: "美术".to_string() },
-        // 周三
-        ClassSchedule { day_of_week: 3, period: 1, subject: "物理".to_string() },
-        ClassSchedule { day_of_week: 3, period: 2, subject: "数学".to_string() },
-        ClassSchedule { day_of_week: 3, period: 3, subject: "语文".to_string() },
-        ClassSchedule { day_of_week: 3, period: 4, subject: "生物".to_string() },
-        ClassSchedule { day_of_week: 3, period: 5, subject: "信息技术".to_string() },
-        // 周四
-        ClassSchedule { day_of_week: 4, period: 1, subject: "语文".to_string() },
-        ClassSchedule { day_of_week: 4, period: 2, subject: "英语".to_string() },
-        ClassSchedule { day_of_week: 4, period: 3, subject: "数学".to_string() },
-        ClassSchedule { day_of_week: 4, period: 4, subject: "历史".to_string() },
-        ClassSchedule { day_of_week: 4, period: 5, subject: "音乐".to_string() },
-        // 周五
-        ClassSchedule { day_of_week: 5, period: 1, subject: "数学".to_string() },
-        ClassSchedule { day_of_week: 5, period: 2, subject: "化学".to_string() },
-        ClassSchedule { day_of_week: 5, period: 3, subject: "英语".to_string() },
-        ClassSchedule { day_of_week: 5, period: 4, subject: "地理".to_string() },
-        ClassSchedule { day_of_week: 5, period: 5, subject: "社团活动".to_string() },
-    ]
+/// 选修课（走班）
+///
+/// 选课学生在该时段离开本班，前往选修课地点；同一时段的多门选修课
+/// 构成一次分班，学生各选其一。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Elective {
+    pub name: String,
+    pub day_of_week: u32,
+    pub period: u32,
+    pub subject: String,
+    /// 上课地点（缺省为学科对应教室）
+    #[serde(default)]
+    pub location: Option<LocationId>,
+    pub max_members: usize,
+    #[serde(default)]
+    pub members: Vec<AgentId>,
 }
 
-/// 创建默认学科列表
-pub fn create_default_subjects() -> Vec<Subject> {
-    vec![
-        Subject { name: "数学".to_string(), base_difficulty: 0.7, difficulty_modifier: 0.0, classroom: LocationId("classroom_math".to_string()) },
-        Subject { name: "语文".to_string(), base_difficulty: 0.5, difficulty_modifier: 0.0, classroom: LocationId("classroom_chinese".to_string()) },
-        Subject { name: "英语".to_string(), base_difficulty: 0.6, difficulty_modifier: 0.0, classroom: LocationId("classroom_english".to_string()) },
-        Subject { name: "物理".to_string(), base_difficulty: 0.8, difficulty_modifier: 0.0, classroom: LocationId("classroom_science".to_string()) },
-        Subject { name: "化学".to_string(), base_difficulty: 0.7, difficulty_modifier: 0.0, classroom: LocationId("classroom_science".to_string()) },
-        Subject { name: "生物".to_string(), base_difficulty: 0.6, difficulty_modifier: 0.0, classroom: LocationId("classroom_science".to_string()) },
-        Subject { name: "历史".to_string(), base_difficulty: 0.4, difficulty_modifier: 0.0, classroom: LocationId("classroom_chinese".to_string()) },
-        Subject { name: "地理".to_string(), base_difficulty: 0.5, difficulty_modifier: 0.0, classroom: LocationId("classroom_chinese".to_string()) },
-        Subject { name: "信息技术".to_string(), base_difficulty: 0.5, difficulty_modifier: 0.0, classroom: LocationId("classroom_science".to_string()) },
-        Subject { name: "美术".to_string(), base_difficulty: 0.3, difficulty_modifier: 0.0, classroom: LocationId("club_room".to_string()) },
-        Subject { name: "音乐".to_string(), base_difficulty: 0.3, difficulty_modifier: 0.0, classroom: LocationId("auditorium".to_string()) },
-        Subject { name: "体育".to_string(), base_difficulty: 0.3, difficulty_modifier: 0.0, classroom: LocationId("playground".to_string()) },
-    ]
+/// 某个 Agent 实际要上的一节课
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassSession {
+    pub subject: String,
+    pub location: LocationId,
+    /// 所属班级或选修课名称
+    pub group: String,
 }
 
 /// 计算学业反馈：Agent 在某学科的表现
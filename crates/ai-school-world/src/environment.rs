@@ -0,0 +1,130 @@
+//! 环境参数
+//!
+//! 课程难度、社交密度、竞争压力三个可调参数（0.0 ~ 1.0，0.5 为默认水平），
+//! 分别作用于学科难度、课余的聚集与交谈、学业压力与排名竞争。
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use ai_school_core::types::{LocationId, SimulationTime};
+
+/// 默认水平
+pub const NEUTRAL_LEVEL: f32 = 0.5;
+
+/// 环境参数
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentParams {
+    /// 课程难度
+    pub course_difficulty: f32,
+    /// 社交密度
+    pub social_density: f32,
+    /// 竞争压力
+    pub competitive_pressure: f32,
+}
+
+impl Default for EnvironmentParams {
+    fn default() -> Self {
+        Self {
+            course_difficulty: NEUTRAL_LEVEL,
+            social_density: NEUTRAL_LEVEL,
+            competitive_pressure: NEUTRAL_LEVEL,
+        }
+    }
+}
+
+impl EnvironmentParams {
+    /// 各学科的难度调整：默认水平不调整，两端为 ±0.3
+    pub fn difficulty_modifier(&self) -> f32 {
+        (self.course_difficulty - NEUTRAL_LEVEL) * 0.6
+    }
+
+    /// 社交意愿倍率 (0.5 ~ 1.5)
+    pub fn social_factor(&self) -> f32 {
+        0.5 + self.social_density
+    }
+
+    /// 竞争压力高出默认水平的部分 (-0.5 ~ 0.5)
+    pub fn pressure_excess(&self) -> f32 {
+        self.competitive_pressure - NEUTRAL_LEVEL
+    }
+
+    /// 课余选择去处：社交密度高时倾向于去已有人去的地方，低时倾向于人最少的地方
+    ///
+    /// `planned` 为本轮已安排的其他 Agent 的去处；没有可选去处时为 None。
+    pub fn choose_gathering<R: Rng>(
+        &self,
+        options: &[LocationId],
+        planned: &[LocationId],
+        rng: &mut R,
+    ) -> Option<LocationId> {
+        let excess = (self.social_density - NEUTRAL_LEVEL) * 2.0;
+        let roll = rng.r#gen::<f32>();
+
+        if roll < excess {
+            let crowded: Vec<&LocationId> = planned.iter().filter(|l| options.contains(l)).collect();
+            if let Some(location) = crowded.choose(rng) {
+                return Some((*location).clone());
+            }
+        } else if roll < -excess {
+            let count = |l: &LocationId| planned.iter().filter(|p| *p == l).count();
+            if let Some(location) = options.iter().min_by_key(|l| count(l)) {
+                return Some(location.clone());
+            }
+        }
+        options.choose(rng).cloned()
+    }
+}
+
+/// 一次参数调整后的取值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvironmentRecord {
+    pub time: SimulationTime,
+    pub params: EnvironmentParams,
+}
+
+/// 某一 tick 生效的参数（该时刻及之前最后一次调整的取值）
+pub fn params_at(history: &[EnvironmentRecord], tick: u64) -> EnvironmentParams {
+    history
+        .iter()
+        .rev()
+        .find(|r| r.time.tick <= tick)
+        .map(|r| r.params)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(s: &str) -> LocationId {
+        LocationId(s.to_string())
+    }
+
+    #[test]
+    fn test_gathering_follows_social_density() {
+        let options = [id("library"), id("playground"), id("rest_area")];
+        let planned = [id("playground"), id("playground"), id("library")];
+        let mut rng = rand::thread_rng();
+
+        let dense = EnvironmentParams { social_density: 1.0, ..Default::default() };
+        let sparse = EnvironmentParams { social_density: 0.0, ..Default::default() };
+        for _ in 0..20 {
+            assert_ne!(dense.choose_gathering(&options, &planned, &mut rng), Some(id("rest_area")));
+            assert_eq!(sparse.choose_gathering(&options, &planned, &mut rng), Some(id("rest_area")));
+        }
+        assert_eq!(dense.choose_gathering(&[], &planned, &mut rng), None);
+    }
+
+    #[test]
+    fn test_params_at_uses_latest_change() {
+        let mut time = SimulationTime::new();
+        time.tick = 10;
+        let hard = EnvironmentParams { course_difficulty: 0.9, ..Default::default() };
+        let history = vec![EnvironmentRecord { time, params: hard }];
+
+        assert_eq!(params_at(&history, 5), EnvironmentParams::default());
+        assert_eq!(params_at(&history, 10), hard);
+        assert!((hard.difficulty_modifier() - 0.24).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,371 @@
+//! 考试与成绩
+//!
+//! 校历中的考试 → 按学科计算成绩 → 年级排名 → 成绩单与成绩分布统计。
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use ai_school_core::types::{AgentId, SimulationTime};
+
+/// 及格线
+pub const PASSING_SCORE: f32 = 60.0;
+
+/// 考试类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExamKind {
+    /// 月考
+    Monthly,
+    /// 期中考试
+    Midterm,
+    /// 期末考试
+    Final,
+}
+
+impl ExamKind {
+    pub fn label(&self) -> &str {
+        match self {
+            ExamKind::Monthly => "月考",
+            ExamKind::Midterm => "期中考试",
+            ExamKind::Final => "期末考试",
+        }
+    }
+
+    /// 考试的分量：越重要的考试，排名带来的情绪冲击越大
+    pub fn weight(&self) -> f32 {
+        match self {
+            ExamKind::Monthly => 0.6,
+            ExamKind::Midterm => 1.0,
+            ExamKind::Final => 1.2,
+        }
+    }
+}
+
+/// 校历中的考试安排（每学期第几周、星期几）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExamPeriod {
+    pub name: String,
+    pub kind: ExamKind,
+    pub week: u32,
+    /// 星期几 (1-5)
+    pub day_of_week: u32,
+}
+
+/// 单科成绩的影响因素
+#[derive(Debug, Clone, Copy)]
+pub struct ExamInput {
+    /// 学业能力 (0.0 ~ 1.0)
+    pub academic: f32,
+    /// 学科偏好 (0.0 ~ 1.0)
+    pub preference: f32,
+    /// 学科有效难度 (0.0 ~ 1.0)
+    pub difficulty: f32,
+    /// 考试时的压力 (0.0 ~ 1.0)
+    pub stress: f32,
+    /// 该学科近期的学习表现（没有记录时为 None）
+    pub recent_study: Option<f32>,
+}
+
+/// 成绩模型参数
+#[derive(Debug, Clone)]
+pub struct ExamModel {
+    /// 能力为 0、其余因素居中时的得分率
+    pub base: f32,
+    pub ability_weight: f32,
+    pub preference_weight: f32,
+    pub study_weight: f32,
+    pub difficulty_weight: f32,
+    /// 超过该压力后发挥受影响
+    pub stress_tolerance: f32,
+    pub stress_weight: f32,
+    /// 临场发挥的随机波动幅度
+    pub noise: f32,
+}
+
+impl Default for ExamModel {
+    fn default() -> Self {
+        Self {
+            base: 0.35,
+            ability_weight: 0.5,
+            preference_weight: 0.15,
+            study_weight: 0.3,
+            difficulty_weight: 0.3,
+            stress_tolerance: 0.5,
+            stress_weight: 0.4,
+            noise: 0.05,
+        }
+    }
+}
+
+impl ExamModel {
+    /// 计算单科成绩 (0 ~ 100)；`noise` 取值 -1.0 ~ 1.0
+    pub fn score(&self, input: &ExamInput, noise: f32) -> f32 {
+        let study = input.recent_study.unwrap_or(0.5);
+        let rate = self.base
+            + self.ability_weight * input.academic
+            + self.preference_weight * (input.preference - 0.5)
+            + self.study_weight * (study - 0.5)
+            - self.difficulty_weight * (input.difficulty - 0.5)
+            - self.stress_weight * (input.stress - self.stress_tolerance).max(0.0)
+            + self.noise * noise;
+        (rate.clamp(0.0, 1.0) * 100.0 * 10.0).round() / 10.0
+    }
+}
+
+/// 单科成绩
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExamScore {
+    pub subject: String,
+    pub score: f32,
+}
+
+/// 一名学生一次考试的成绩
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExamRecord {
+    pub agent_id: AgentId,
+    pub exam: String,
+    pub kind: ExamKind,
+    pub timestamp: SimulationTime,
+    pub scores: Vec<ExamScore>,
+    /// 各科平均分
+    pub average: f32,
+    /// 年级排名（从 1 开始）
+    pub rank: usize,
+    /// 参加考试的人数
+    pub cohort_size: usize,
+}
+
+impl ExamRecord {
+    /// 排名百分位：第一名为 1.0，最后一名为 0.0
+    pub fn percentile(&self) -> f32 {
+        if self.cohort_size <= 1 {
+            return 1.0;
+        }
+        1.0 - (self.rank - 1) as f32 / (self.cohort_size - 1) as f32
+    }
+
+    pub fn score_of(&self, subject: &str) -> Option<f32> {
+        self.scores.iter().find(|s| s.subject == subject).map(|s| s.score)
+    }
+}
+
+/// 成绩分布
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GradeDistribution {
+    pub count: usize,
+    pub mean: f32,
+    pub std_dev: f32,
+    pub min: f32,
+    pub max: f32,
+    /// 及格率
+    pub pass_rate: f32,
+    /// 分数段人数：<60、60-69、70-79、80-89、≥90
+    pub bands: [usize; 5],
+}
+
+impl GradeDistribution {
+    pub fn from_scores(scores: &[f32]) -> Option<Self> {
+        if scores.is_empty() {
+            return None;
+        }
+        let n = scores.len() as f32;
+        let mean = scores.iter().sum::<f32>() / n;
+        let variance = scores.iter().map(|s| (s - mean).powi(2)).sum::<f32>() / n;
+        let mut bands = [0; 5];
+        for score in scores {
+            let band = ((score - 50.0) / 10.0).floor().clamp(0.0, 4.0) as usize;
+            bands[band] += 1;
+        }
+
+        Some(Self {
+            count: scores.len(),
+            mean,
+            std_dev: variance.sqrt(),
+            min: scores.iter().cloned().fold(f32::MAX, f32::min),
+            max: scores.iter().cloned().fold(f32::MIN, f32::max),
+            pass_rate: scores.iter().filter(|s| **s >= PASSING_SCORE).count() as f32 / n,
+            bands,
+        })
+    }
+}
+
+/// 一次考试的成绩汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExamSummary {
+    pub exam: String,
+    pub kind: ExamKind,
+    pub timestamp: SimulationTime,
+    /// 平均分分布
+    pub overall: GradeDistribution,
+    /// 各科成绩分布
+    pub subjects: BTreeMap<String, GradeDistribution>,
+}
+
+/// 成绩册
+#[derive(Default)]
+pub struct Gradebook {
+    pub model: ExamModel,
+    records: Vec<ExamRecord>,
+}
+
+impl Gradebook {
+    /// 登记一次考试的成绩：计算平均分与排名
+    pub fn record_exam(
+        &mut self,
+        exam: &str,
+        kind: ExamKind,
+        timestamp: &SimulationTime,
+        results: Vec<(AgentId, Vec<ExamScore>)>,
+    ) -> Vec<ExamRecord> {
+        let mut records: Vec<ExamRecord> = results
+            .into_iter()
+            .filter(|(_, scores)| !scores.is_empty())
+            .map(|(agent_id, scores)| ExamRecord {
+                average: scores.iter().map(|s| s.score).sum::<f32>() / scores.len() as f32,
+                agent_id,
+                exam: exam.to_string(),
+                kind,
+                timestamp: timestamp.clone(),
+                scores,
+                rank: 0,
+                cohort_size: 0,
+            })
+            .collect();
+
+        records.sort_by(|a, b| b.average.total_cmp(&a.average));
+        let cohort_size = records.len();
+        for i in 0..cohort_size {
+            // 平均分相同的并列
+            records[i].rank = if i > 0 && records[i].average == records[i - 1].average {
+                records[i - 1].rank
+            } else {
+                i + 1
+            };
+            records[i].cohort_size = cohort_size;
+        }
+
+        self.records.extend(records.iter().cloned());
+        records
+    }
+
+    /// 某个 Agent 的成绩单（按时间顺序）
+    pub fn transcript(&self, agent_id: &AgentId) -> Vec<&ExamRecord> {
+        self.records
+            .iter()
+            .filter(|r| r.agent_id == *agent_id)
+            .collect()
+    }
+
+    /// 某个 Agent 最近一次考试成绩
+    pub fn latest(&self, agent_id: &AgentId) -> Option<&ExamRecord> {
+        self.records.iter().rev().find(|r| r.agent_id == *agent_id)
+    }
+
+    /// 某学期各次考试的成绩分布
+    pub fn semester_report(&self, semester: u32) -> Vec<ExamSummary> {
+        let mut sittings: Vec<Vec<&ExamRecord>> = Vec::new();
+        for record in self.records.iter().filter(|r| r.timestamp.semester == semester) {
+            match sittings.iter_mut().find(|s| {
+                s[0].exam == record.exam && s[0].timestamp.tick == record.timestamp.tick
+            }) {
+                Some(sitting) => sitting.push(record),
+                None => sittings.push(vec![record]),
+            }
+        }
+
+        sittings
+            .into_iter()
+            .filter_map(|sitting| {
+                let averages: Vec<f32> = sitting.iter().map(|r| r.average).collect();
+                let mut by_subject: BTreeMap<String, Vec<f32>> = BTreeMap::new();
+                for score in sitting.iter().flat_map(|r| &r.scores) {
+                    by_subject.entry(score.subject.clone()).or_default().push(score.score);
+                }
+                Some(ExamSummary {
+                    exam: sitting[0].exam.clone(),
+                    kind: sitting[0].kind,
+                    timestamp: sitting[0].timestamp.clone(),
+                    overall: GradeDistribution::from_scores(&averages)?,
+                    subjects: by_subject
+                        .into_iter()
+                        .filter_map(|(subject, scores)| {
+                            Some((subject, GradeDistribution::from_scores(&scores)?))
+                        })
+                        .collect(),
+                })
+            })
+            .collect()
+    }
+
+    /// 获取所有成绩记录
+    pub fn all_records(&self) -> &[ExamRecord] {
+        &self.records
+    }
+
+    /// 导出所有成绩记录为 JSON
+    pub fn export_json(&self) -> serde_json::Value {
+        serde_json::json!(self.records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(academic: f32, stress: f32) -> ExamInput {
+        ExamInput {
+            academic,
+            preference: 0.5,
+            difficulty: 0.5,
+            stress,
+            recent_study: None,
+        }
+    }
+
+    #[test]
+    fn test_score_factors() {
+        let model = ExamModel::default();
+        assert!(model.score(&input(0.9, 0.3), 0.0) > model.score(&input(0.4, 0.3), 0.0));
+        // 适度压力不影响发挥，过高压力拖累成绩
+        assert_eq!(model.score(&input(0.6, 0.2), 0.0), model.score(&input(0.6, 0.5), 0.0));
+        assert!(model.score(&input(0.6, 0.9), 0.0) < model.score(&input(0.6, 0.5), 0.0));
+
+        let studied = ExamInput {
+            recent_study: Some(0.9),
+            ..input(0.6, 0.3)
+        };
+        assert!(model.score(&studied, 0.0) > model.score(&input(0.6, 0.3), 0.0));
+    }
+
+    #[test]
+    fn test_ranking_and_report() {
+        let mut gradebook = Gradebook::default();
+        let time = SimulationTime::new();
+        let (a, b, c) = (AgentId::new(), AgentId::new(), AgentId::new());
+        let scores = |math: f32, chinese: f32| {
+            vec![
+                ExamScore { subject: "数学".to_string(), score: math },
+                ExamScore { subject: "语文".to_string(), score: chinese },
+            ]
+        };
+
+        let records = gradebook.record_exam(
+            "期中考试",
+            ExamKind::Midterm,
+            &time,
+            vec![
+                (a.clone(), scores(90.0, 80.0)),
+                (b.clone(), scores(50.0, 60.0)),
+                (c.clone(), scores(70.0, 70.0)),
+            ],
+        );
+        let rank = |id: &AgentId| records.iter().find(|r| r.agent_id == *id).unwrap().rank;
+        assert_eq!((rank(&a), rank(&c), rank(&b)), (1, 2, 3));
+        assert_eq!(gradebook.latest(&b).unwrap().percentile(), 0.0);
+
+        let report = gradebook.semester_report(time.semester);
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].subjects["数学"].bands, [1, 0, 1, 0, 1]);
+        assert!((report[0].subjects["数学"].pass_rate - 2.0 / 3.0).abs() < 1e-6);
+    }
+}
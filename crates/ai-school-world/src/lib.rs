@@ -1,6 +1,14 @@
+pub mod academics;
 pub mod campus;
+pub mod causality;
+pub mod changes;
 pub mod curriculum;
+pub mod environment;
+pub mod exams;
+pub mod movement;
+pub mod network;
 pub mod relationships;
+pub mod scenario;
 pub mod social;
 pub mod state;
 pub mod time;
@@ -0,0 +1,217 @@
+//! 校园移动
+//!
+//! Agent 沿校园邻接图移动，耗时由位置坐标距离决定。
+//! 同一时刻途经同一中间位置（如走廊）的 Agent 可能偶遇。
+
+use rand::Rng;
+
+use ai_school_core::types::{AgentActivity, AgentId, Location, LocationId};
+
+use crate::campus::{distance, shortest_path};
+
+/// 移动模型参数
+#[derive(Debug, Clone)]
+pub struct MovementModel {
+    /// 步行速度（地图坐标单位/分钟）
+    pub walking_speed: f32,
+    /// 偶遇时间窗口：两人经过同一位置的时间差不超过此分钟数
+    pub encounter_window_minutes: u32,
+    /// 满足时间窗口时发生偶遇的概率
+    pub encounter_chance: f32,
+}
+
+impl Default for MovementModel {
+    fn default() -> Self {
+        Self {
+            walking_speed: 60.0,
+            encounter_window_minutes: 2,
+            encounter_chance: 0.1,
+        }
+    }
+}
+
+/// 进行中的行程
+#[derive(Debug, Clone)]
+pub struct Journey {
+    /// 完整路径（含起点与终点）
+    pub path: Vec<LocationId>,
+    /// 到达路径上每个位置的时刻（自出发起的分钟数）
+    pub arrival_minutes: Vec<u32>,
+    /// 已行进的分钟数
+    pub elapsed_minutes: u32,
+    /// 到达终点后的活动
+    pub on_arrival: AgentActivity,
+}
+
+impl Journey {
+    /// 全程耗时（分钟）
+    pub fn total_minutes(&self) -> u32 {
+        self.arrival_minutes.last().copied().unwrap_or(0)
+    }
+
+    pub fn destination(&self) -> &LocationId {
+        self.path.last().expect("journey path is never empty")
+    }
+
+    pub fn is_arrived(&self) -> bool {
+        self.elapsed_minutes >= self.total_minutes()
+    }
+
+    /// 当前所在位置：已到达的最后一个路径节点
+    pub fn current_location(&self) -> &LocationId {
+        self.location_at(self.elapsed_minutes)
+    }
+
+    /// 出发若干分钟后所在的位置
+    pub fn location_at(&self, minutes: u32) -> &LocationId {
+        let reached = self
+            .arrival_minutes
+            .iter()
+            .rposition(|&t| t <= minutes)
+            .unwrap_or(0);
+        &self.path[reached]
+    }
+}
+
+/// 一次行程在本步内途经的中间位置及时刻（相对本步开始的分钟数）
+#[derive(Debug, Clone)]
+pub struct Pass {
+    pub agent_id: AgentId,
+    pub location: LocationId,
+    pub minute: u32,
+    /// 行程的起点与终点
+    pub route: (LocationId, LocationId),
+}
+
+/// 因目的地容量已满而改道
+#[derive(Debug, Clone, PartialEq)]
+pub struct Redirect {
+    pub agent_id: AgentId,
+    pub requested: LocationId,
+    pub assigned: LocationId,
+}
+
+/// 偶遇
+#[derive(Debug, Clone, PartialEq)]
+pub struct Encounter {
+    pub agents: (AgentId, AgentId),
+    pub location: LocationId,
+}
+
+impl MovementModel {
+    /// 规划行程：最短路径 + 按距离计算各节点到达时刻；不可达时返回 None
+    pub fn plan(
+        &self,
+        locations: &[Location],
+        from: &LocationId,
+        to: &LocationId,
+        on_arrival: AgentActivity,
+    ) -> Option<Journey> {
+        let path = shortest_path(locations, from, to)?;
+        let find = |id: &LocationId| locations.iter().find(|l| &l.id == id);
+
+        let mut arrival_minutes = vec![0];
+        let mut travelled = 0.0;
+        for pair in path.windows(2) {
+            travelled += distance(find(&pair[0])?, find(&pair[1])?);
+            arrival_minutes.push((travelled / self.walking_speed).ceil() as u32);
+        }
+
+        Some(Journey {
+            path,
+            arrival_minutes,
+            elapsed_minutes: 0,
+            on_arrival,
+        })
+    }
+
+    /// 从本步的途经记录中找出偶遇：不同 Agent 在时间窗口内经过同一位置，按概率发生
+    ///
+    /// 同一路线结伴而行的不算偶遇；每对 Agent 每步至多偶遇一次。
+    /// `social_factor` 按社交密度缩放偶遇概率。
+    pub fn encounters(&self, passes: &[Pass], social_factor: f32) -> Vec<Encounter> {
+        let mut rng = rand::thread_rng();
+        let mut encounters: Vec<Encounter> = Vec::new();
+
+        for (i, a) in passes.iter().enumerate() {
+            for b in &passes[i + 1..] {
+                if a.agent_id == b.agent_id
+                    || a.route == b.route
+                    || a.location != b.location
+                    || a.minute.abs_diff(b.minute) > self.encounter_window_minutes
+                {
+                    continue;
+                }
+                let met_already = encounters.iter().any(|e| {
+                    let (x, y) = &e.agents;
+                    (x == &a.agent_id && y == &b.agent_id) || (x == &b.agent_id && y == &a.agent_id)
+                });
+                if met_already || rng.r#gen::<f32>() >= self.encounter_chance * social_factor {
+                    continue;
+                }
+                encounters.push(Encounter {
+                    agents: (a.agent_id.clone(), b.agent_id.clone()),
+                    location: a.location.clone(),
+                });
+            }
+        }
+
+        encounters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::Scenario;
+
+    fn id(s: &str) -> LocationId {
+        LocationId(s.to_string())
+    }
+
+    #[test]
+    fn test_plan_travel_time_follows_distance() {
+        let locations = Scenario::bundled().locations;
+        let model = MovementModel::default();
+
+        let near = model
+            .plan(&locations, &id("library"), &id("study_room"), AgentActivity::Resting)
+            .unwrap();
+        let far = model
+            .plan(&locations, &id("dormitory"), &id("library"), AgentActivity::Resting)
+            .unwrap();
+
+        assert_eq!(far.path, vec![id("dormitory"), id("hallway"), id("library")]);
+        assert!(far.total_minutes() > near.total_minutes());
+        assert_eq!(far.current_location(), &id("dormitory"));
+    }
+
+    #[test]
+    fn test_encounters_need_same_place_and_time() {
+        let model = MovementModel {
+            encounter_chance: 1.0,
+            ..Default::default()
+        };
+        let (a, b, c) = (AgentId::new(), AgentId::new(), AgentId::new());
+        let (d, e) = (AgentId::new(), AgentId::new());
+        let pass = |agent: &AgentId, minute, from: &str| Pass {
+            agent_id: agent.clone(),
+            location: id("hallway"),
+            minute,
+            route: (id(from), id("library")),
+        };
+
+        let passes = [
+            pass(&a, 5, "dormitory"),
+            pass(&b, 6, "cafeteria"),
+            pass(&c, 30, "playground"),
+        ];
+        let encounters = model.encounters(&passes, 1.0);
+        assert_eq!(encounters.len(), 1);
+        assert_eq!(encounters[0].agents, (a, b));
+
+        // 同路线结伴而行
+        let together = model.encounters(&[pass(&d, 5, "dormitory"), pass(&e, 5, "dormitory")], 1.0);
+        assert!(together.is_empty());
+    }
+}
@@ -0,0 +1,354 @@
+//! 社交网络分析
+//!
+//! 以亲密度超过阈值的关系为边，在关系矩阵上计算度、加权度、介数中心性、
+//! 聚类系数、社区划分、孤立学生与信任互惠度，并按天留存以观察网络演化。
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use ai_school_core::types::{AgentId, SimulationTime};
+
+use crate::environment::EnvironmentParams;
+use crate::relationships::RelationshipManager;
+
+/// 网络分析参数
+#[derive(Debug, Clone)]
+pub struct NetworkParams {
+    /// 亲密度超过该值才算一条社交联系
+    pub tie_threshold: f32,
+    /// 信任度不低于该值视为信任
+    pub trust_threshold: f32,
+    /// 社区划分的最大迭代轮数
+    pub max_iterations: usize,
+}
+
+impl Default for NetworkParams {
+    fn default() -> Self {
+        Self {
+            tie_threshold: 0.1,
+            trust_threshold: 0.5,
+            max_iterations: 20,
+        }
+    }
+}
+
+/// 单个 Agent 的网络指标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentNetworkMetrics {
+    pub agent_id: AgentId,
+    /// 联系人数
+    pub degree: usize,
+    /// 联系的亲密度之和
+    pub weighted_degree: f32,
+    /// 归一化介数中心性 (0.0 ~ 1.0)
+    pub betweenness: f32,
+    /// 局部聚类系数 (0.0 ~ 1.0)
+    pub clustering: f32,
+    /// 所属社区编号
+    pub community: usize,
+}
+
+/// 某一时刻的网络指标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkMetrics {
+    pub time: SimulationTime,
+    pub node_count: usize,
+    pub tie_count: usize,
+    /// 网络密度
+    pub density: f32,
+    /// 平均聚类系数
+    pub average_clustering: f32,
+    /// 信任联系占比：社交联系中信任度达到阈值的比例
+    ///
+    /// 关系矩阵是无向的（一对 Agent 共享一个信任度），
+    /// 因此这里统计的是被信任的联系所占份额，而非双向互惠。
+    pub trusted_tie_ratio: f32,
+    /// 社区划分（每个社区的成员）
+    pub communities: Vec<Vec<AgentId>>,
+    /// 社区划分的模块度
+    pub modularity: f32,
+    /// 没有任何社交联系的 Agent
+    pub isolated: Vec<AgentId>,
+    pub agents: Vec<AgentNetworkMetrics>,
+}
+
+/// 网络指标时间序列中的一点，附带当时生效的环境参数
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkSample<'a> {
+    #[serde(flatten)]
+    pub metrics: &'a NetworkMetrics,
+    pub environment: EnvironmentParams,
+}
+
+impl NetworkMetrics {
+    pub fn agent(&self, agent_id: &AgentId) -> Option<&AgentNetworkMetrics> {
+        self.agents.iter().find(|a| a.agent_id == *agent_id)
+    }
+}
+
+/// 计算网络指标（只考虑 `nodes` 之间的关系）
+pub fn analyze(
+    nodes: &[AgentId],
+    relationships: &RelationshipManager,
+    params: &NetworkParams,
+    time: &SimulationTime,
+) -> NetworkMetrics {
+    let mut nodes = nodes.to_vec();
+    nodes.sort_by_key(|id| id.0);
+    let index: HashMap<&AgentId, usize> = nodes.iter().enumerate().map(|(i, id)| (id, i)).collect();
+    let n = nodes.len();
+
+    // 邻接表：(邻居, 亲密度)
+    let mut adjacency: Vec<Vec<(usize, f32)>> = vec![Vec::new(); n];
+    let mut ties = 0;
+    let mut trusted_ties = 0;
+    for rel in relationships.all_relationships() {
+        let (Some(&a), Some(&b)) = (index.get(&rel.agent_a), index.get(&rel.agent_b)) else {
+            continue;
+        };
+        if rel.closeness <= params.tie_threshold {
+            continue;
+        }
+        adjacency[a].push((b, rel.closeness));
+        adjacency[b].push((a, rel.closeness));
+        ties += 1;
+        if rel.trust >= params.trust_threshold {
+            trusted_ties += 1;
+        }
+    }
+
+    let betweenness = betweenness(&adjacency);
+    let clustering: Vec<f32> = (0..n).map(|v| local_clustering(&adjacency, v)).collect();
+    let labels = detect_communities(&adjacency, params.max_iterations);
+
+    let mut communities: Vec<Vec<AgentId>> = Vec::new();
+    let mut community_of = vec![0; n];
+    let mut seen: HashMap<usize, usize> = HashMap::new();
+    for v in 0..n {
+        let next = seen.len();
+        let c = *seen.entry(labels[v]).or_insert(next);
+        if c == communities.len() {
+            communities.push(Vec::new());
+        }
+        communities[c].push(nodes[v].clone());
+        community_of[v] = c;
+    }
+
+    let agents: Vec<AgentNetworkMetrics> = (0..n)
+        .map(|v| AgentNetworkMetrics {
+            agent_id: nodes[v].clone(),
+            degree: adjacency[v].len(),
+            weighted_degree: adjacency[v].iter().map(|(_, w)| w).sum(),
+            betweenness: betweenness[v],
+            clustering: clustering[v],
+            community: community_of[v],
+        })
+        .collect();
+
+    NetworkMetrics {
+        time: time.clone(),
+        node_count: n,
+        tie_count: ties,
+        density: if n > 1 {
+            2.0 * ties as f32 / (n * (n - 1)) as f32
+        } else {
+            0.0
+        },
+        average_clustering: if n > 0 {
+            clustering.iter().sum::<f32>() / n as f32
+        } else {
+            0.0
+        },
+        trusted_tie_ratio: if ties > 0 {
+            trusted_ties as f32 / ties as f32
+        } else {
+            0.0
+        },
+        modularity: modularity(&adjacency, &community_of),
+        communities,
+        isolated: agents
+            .iter()
+            .filter(|a| a.degree == 0)
+            .map(|a| a.agent_id.clone())
+            .collect(),
+        agents,
+    }
+}
+
+/// Brandes 算法（无权图），按 (n-1)(n-2)/2 归一化
+fn betweenness(adjacency: &[Vec<(usize, f32)>]) -> Vec<f32> {
+    let n = adjacency.len();
+    let mut centrality = vec![0.0f32; n];
+
+    for s in 0..n {
+        let mut stack = Vec::new();
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut paths = vec![0.0f32; n];
+        let mut distance = vec![-1i64; n];
+        paths[s] = 1.0;
+        distance[s] = 0;
+
+        let mut queue = VecDeque::from([s]);
+        while let Some(v) = queue.pop_front() {
+            stack.push(v);
+            for &(w, _) in &adjacency[v] {
+                if distance[w] < 0 {
+                    distance[w] = distance[v] + 1;
+                    queue.push_back(w);
+                }
+                if distance[w] == distance[v] + 1 {
+                    paths[w] += paths[v];
+                    predecessors[w].push(v);
+                }
+            }
+        }
+
+        let mut dependency = vec![0.0f32; n];
+        while let Some(w) = stack.pop() {
+            for &v in &predecessors[w] {
+                dependency[v] += paths[v] / paths[w] * (1.0 + dependency[w]);
+            }
+            if w != s {
+                centrality[w] += dependency[w];
+            }
+        }
+    }
+
+    // 无向图每条最短路径被统计两次
+    let norm = if n > 2 { ((n - 1) * (n - 2)) as f32 } else { 1.0 };
+    centrality.iter().map(|c| c / norm).collect()
+}
+
+/// 局部聚类系数：邻居之间实际相连的比例
+fn local_clustering(adjacency: &[Vec<(usize, f32)>], v: usize) -> f32 {
+    let neighbors: HashSet<usize> = adjacency[v].iter().map(|(w, _)| *w).collect();
+    let k = neighbors.len();
+    if k < 2 {
+        return 0.0;
+    }
+    let links = neighbors
+        .iter()
+        .map(|&u| adjacency[u].iter().filter(|(w, _)| neighbors.contains(w)).count())
+        .sum::<usize>()
+        / 2;
+    2.0 * links as f32 / (k * (k - 1)) as f32
+}
+
+/// 社区划分：Louvain 的局部移动阶段
+///
+/// 每个节点依次移入能使模块度增益最大的相邻社区，直到没有节点移动。
+fn detect_communities(adjacency: &[Vec<(usize, f32)>], max_iterations: usize) -> Vec<usize> {
+    let n = adjacency.len();
+    let strength: Vec<f32> = adjacency
+        .iter()
+        .map(|edges| edges.iter().map(|(_, w)| w).sum())
+        .collect();
+    let total: f32 = strength.iter().sum();
+    let mut community: Vec<usize> = (0..n).collect();
+    if total == 0.0 {
+        return community;
+    }
+    let mut community_strength = strength.clone();
+
+    for _ in 0..max_iterations {
+        let mut moved = false;
+        for v in 0..n {
+            let current = community[v];
+            community_strength[current] -= strength[v];
+
+            let mut links: HashMap<usize, f32> = HashMap::new();
+            for &(w, weight) in &adjacency[v] {
+                *links.entry(community[w]).or_default() += weight;
+            }
+            let gain = |c: usize, k_in: f32| k_in - community_strength[c] * strength[v] / total;
+            let mut best = (current, gain(current, links.get(&current).copied().unwrap_or(0.0)));
+            let mut candidates: Vec<(usize, f32)> = links.into_iter().collect();
+            candidates.sort_by_key(|(c, _)| *c);
+            for (c, k_in) in candidates {
+                let g = gain(c, k_in);
+                if g > best.1 + 1e-6 {
+                    best = (c, g);
+                }
+            }
+
+            community[v] = best.0;
+            community_strength[best.0] += strength[v];
+            moved |= best.0 != current;
+        }
+        if !moved {
+            break;
+        }
+    }
+    community
+}
+
+/// 加权模块度
+fn modularity(adjacency: &[Vec<(usize, f32)>], community: &[usize]) -> f32 {
+    let strength: Vec<f32> = adjacency
+        .iter()
+        .map(|edges| edges.iter().map(|(_, w)| w).sum())
+        .collect();
+    let total: f32 = strength.iter().sum();
+    if total == 0.0 {
+        return 0.0;
+    }
+
+    let mut q = 0.0;
+    for (v, edges) in adjacency.iter().enumerate() {
+        for &(w, weight) in edges {
+            if community[v] == community[w] {
+                q += weight;
+            }
+        }
+    }
+    let mut community_strength: HashMap<usize, f32> = HashMap::new();
+    for (v, s) in strength.iter().enumerate() {
+        *community_strength.entry(community[v]).or_default() += s;
+    }
+    (q - community_strength.values().map(|s| s * s).sum::<f32>() / total) / total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 两个三角形由一座“桥” 2-3 相连，另有一个孤立节点 6
+    fn barbell() -> (Vec<AgentId>, RelationshipManager) {
+        let ids: Vec<AgentId> = (0..7).map(|_| AgentId::new()).collect();
+        let mut manager = RelationshipManager::new();
+        let time = SimulationTime::new();
+        for (a, b) in [(0, 1), (0, 2), (1, 2), (2, 3), (3, 4), (3, 5), (4, 5)] {
+            manager.update_closeness(&ids[a], &ids[b], 0.6, &time);
+        }
+        manager.update_closeness(&ids[0], &ids[6], 0.05, &time);
+        (ids, manager)
+    }
+
+    #[test]
+    fn test_bridge_has_highest_betweenness() {
+        let (ids, manager) = barbell();
+        let metrics = analyze(&ids, &manager, &NetworkParams::default(), &SimulationTime::new());
+
+        assert_eq!(metrics.tie_count, 7);
+        assert_eq!(metrics.isolated, vec![ids[6].clone()]);
+        let bridge = metrics.agent(&ids[2]).unwrap();
+        let leaf = metrics.agent(&ids[0]).unwrap();
+        assert!(bridge.betweenness > leaf.betweenness);
+        assert_eq!(leaf.betweenness, 0.0);
+        assert_eq!(leaf.clustering, 1.0);
+        assert!((bridge.clustering - 1.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_communities_split_at_bridge() {
+        let (ids, manager) = barbell();
+        let metrics = analyze(&ids, &manager, &NetworkParams::default(), &SimulationTime::new());
+
+        let community = |i: usize| metrics.agent(&ids[i]).unwrap().community;
+        assert_eq!(community(0), community(1));
+        assert_eq!(community(4), community(5));
+        assert_ne!(community(0), community(5));
+        assert!(metrics.modularity > 0.3);
+    }
+}
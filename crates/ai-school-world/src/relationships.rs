@@ -1,21 +1,164 @@
 //! 关系矩阵
 //!
-//! Agent 间亲密度、群组归属、关系变更。
+//! Agent 间亲密度、群组归属、关系变更；无互动时关系向中性回归，
+//! 由亲密度与信任度派生关系标签（带滞回），并在朋友关系图上识别小圈子。
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
 
 use ai_school_core::types::{AgentId, Relationship, SimulationTime};
 
+/// 师生关系标签
+pub const TEACHER_STUDENT_TAG: &str = "师生";
+
+/// 由亲密度与信任度派生的同伴关系标签（同一对 Agent 至多一个）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RelationshipLabel {
+    /// 挚友
+    BestFriend,
+    /// 朋友
+    Friend,
+    /// 对手：关系差但仍彼此看重
+    Rival,
+    /// 疏远：关系差且互不信任
+    Estranged,
+}
+
+impl RelationshipLabel {
+    /// 判定优先级：先强后弱
+    const ALL: [RelationshipLabel; 4] = [
+        RelationshipLabel::BestFriend,
+        RelationshipLabel::Friend,
+        RelationshipLabel::Estranged,
+        RelationshipLabel::Rival,
+    ];
+
+    /// 写入 `Relationship::tags` 的标签文本
+    pub fn tag(&self) -> &'static str {
+        match self {
+            RelationshipLabel::BestFriend => "挚友",
+            RelationshipLabel::Friend => "朋友",
+            RelationshipLabel::Rival => "对手",
+            RelationshipLabel::Estranged => "疏远",
+        }
+    }
+
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.tag() == tag)
+    }
+
+    pub fn is_friendly(&self) -> bool {
+        matches!(self, RelationshipLabel::BestFriend | RelationshipLabel::Friend)
+    }
+}
+
+/// 关系标签变化（由引擎取走并生成事件）
+#[derive(Debug, Clone)]
+pub struct LabelChange {
+    pub agent_a: AgentId,
+    pub agent_b: AgentId,
+    pub from: Option<RelationshipLabel>,
+    pub to: Option<RelationshipLabel>,
+}
+
+/// 关系动力学参数
+#[derive(Debug, Clone)]
+pub struct RelationshipModel {
+    /// 超过该时长（小时）没有互动，关系开始回归中性
+    pub grace_hours: u32,
+    /// 每天亲密度向 0 回归的比例
+    pub closeness_decay: f32,
+    /// 每天信任度向中性值回归的比例
+    pub trust_decay: f32,
+    /// 信任度中性值
+    pub neutral_trust: f32,
+    /// 滞回宽度：已有标签在阈值放宽这么多之前不会失去
+    pub hysteresis: f32,
+    pub friend_closeness: f32,
+    pub best_friend_closeness: f32,
+    pub best_friend_trust: f32,
+    pub rival_closeness: f32,
+    pub estranged_closeness: f32,
+    pub estranged_trust: f32,
+    /// 小圈子的最少人数
+    pub min_clique_size: usize,
+}
+
+impl Default for RelationshipModel {
+    fn default() -> Self {
+        Self {
+            grace_hours: 48,
+            closeness_decay: 0.03,
+            trust_decay: 0.02,
+            neutral_trust: 0.5,
+            hysteresis: 0.1,
+            friend_closeness: 0.4,
+            best_friend_closeness: 0.7,
+            best_friend_trust: 0.7,
+            rival_closeness: -0.3,
+            estranged_closeness: -0.5,
+            estranged_trust: 0.3,
+            min_clique_size: 3,
+        }
+    }
+}
+
+impl RelationshipModel {
+    /// 是否满足标签条件；`slack` 为已有标签的滞回放宽量
+    fn qualifies(&self, label: RelationshipLabel, rel: &Relationship, slack: f32) -> bool {
+        match label {
+            RelationshipLabel::BestFriend => {
+                rel.closeness >= self.best_friend_closeness - slack
+                    && rel.trust >= self.best_friend_trust - slack
+            }
+            RelationshipLabel::Friend => rel.closeness >= self.friend_closeness - slack,
+            RelationshipLabel::Estranged => {
+                rel.closeness <= self.estranged_closeness + slack
+                    && rel.trust <= self.estranged_trust + slack
+            }
+            RelationshipLabel::Rival => rel.closeness <= self.rival_closeness + slack,
+        }
+    }
+
+    /// 按当前亲密度/信任度派生标签（师生关系不参与）
+    pub fn derive_label(&self, rel: &Relationship) -> Option<RelationshipLabel> {
+        if rel.tags.iter().any(|t| t == TEACHER_STUDENT_TAG) {
+            return None;
+        }
+        let current = label_of(rel);
+        RelationshipLabel::ALL.into_iter().find(|label| {
+            let slack = if current == Some(*label) { self.hysteresis } else { 0.0 };
+            self.qualifies(*label, rel, slack)
+        })
+    }
+}
+
+/// 关系当前的同伴标签
+pub fn label_of(rel: &Relationship) -> Option<RelationshipLabel> {
+    rel.tags.iter().find_map(|t| RelationshipLabel::from_tag(t))
+}
+
 /// 关系管理器
 pub struct RelationshipManager {
     /// (agent_a, agent_b) → Relationship，保证 agent_a < agent_b
     relationships: HashMap<(AgentId, AgentId), Relationship>,
+    /// 关系动力学参数
+    pub model: RelationshipModel,
+    /// 待处理的标签变化
+    label_changes: Vec<LabelChange>,
+    /// 小圈子缓存，标签变化时失效
+    cliques: OnceLock<Vec<Vec<AgentId>>>,
 }
 
 impl RelationshipManager {
     pub fn new() -> Self {
         Self {
             relationships: HashMap::new(),
+            model: RelationshipModel::default(),
+            label_changes: Vec::new(),
+            cliques: OnceLock::new(),
         }
     }
 
@@ -58,6 +201,7 @@ impl RelationshipManager {
         let rel = self.get_or_create(a, b);
         rel.closeness = (rel.closeness + delta).clamp(-1.0, 1.0);
         rel.last_interaction = Some(time.clone());
+        self.relabel(a, b);
     }
 
     /// 更新信任度
@@ -65,6 +209,110 @@ impl RelationshipManager {
         let rel = self.get_or_create(a, b);
         rel.trust = (rel.trust + delta).clamp(0.0, 1.0);
         rel.last_interaction = Some(time.clone());
+        self.relabel(a, b);
+    }
+
+    /// 重新派生一对关系的标签，变化时记录下来
+    fn relabel(&mut self, a: &AgentId, b: &AgentId) {
+        let Some(rel) = self.relationships.get_mut(&Self::key(a, b)) else {
+            return;
+        };
+        let from = label_of(rel);
+        let to = self.model.derive_label(rel);
+        if from == to {
+            return;
+        }
+        rel.tags.retain(|t| RelationshipLabel::from_tag(t).is_none());
+        if let Some(label) = to {
+            rel.tags.push(label.tag().to_string());
+        }
+        self.cliques = OnceLock::new();
+        self.label_changes.push(LabelChange {
+            agent_a: rel.agent_a.clone(),
+            agent_b: rel.agent_b.clone(),
+            from,
+            to,
+        });
+    }
+
+    /// 关系衰减：久未互动的关系按天向中性回归（每天调用一次）
+    pub fn decay(&mut self, now: &SimulationTime, hours_per_tick: u32) {
+        let model = self.model.clone();
+        let mut decayed = Vec::new();
+        for (key, rel) in self.relationships.iter_mut() {
+            let idle_hours = rel
+                .last_interaction
+                .as_ref()
+                .map(|t| now.tick.saturating_sub(t.tick) * hours_per_tick as u64)
+                .unwrap_or(u64::MAX);
+            if idle_hours < model.grace_hours as u64 {
+                continue;
+            }
+            rel.closeness *= 1.0 - model.closeness_decay;
+            rel.trust += (model.neutral_trust - rel.trust) * model.trust_decay;
+            decayed.push(key.clone());
+        }
+        for (a, b) in decayed {
+            self.relabel(&a, &b);
+        }
+    }
+
+    /// 取走待处理的标签变化
+    pub fn take_label_changes(&mut self) -> Vec<LabelChange> {
+        std::mem::take(&mut self.label_changes)
+    }
+
+    /// 某个 Agent 带某种标签的关系对象
+    pub fn labeled(&self, agent_id: &AgentId, label: RelationshipLabel) -> Vec<AgentId> {
+        self.get_agent_relationships(agent_id)
+            .into_iter()
+            .filter(|r| label_of(r) == Some(label))
+            .map(|r| if r.agent_a == *agent_id { r.agent_b.clone() } else { r.agent_a.clone() })
+            .collect()
+    }
+
+    /// 识别小圈子：朋友关系图上的极大团（Bron–Kerbosch），按人数从多到少
+    ///
+    /// 结果缓存到下一次标签变化。
+    pub fn cliques(&self) -> &[Vec<AgentId>] {
+        self.cliques.get_or_init(|| self.find_cliques())
+    }
+
+    fn find_cliques(&self) -> Vec<Vec<AgentId>> {
+        let mut graph: HashMap<AgentId, HashSet<AgentId>> = HashMap::new();
+        for rel in self.relationships.values() {
+            if label_of(rel).is_some_and(|l| l.is_friendly()) {
+                graph.entry(rel.agent_a.clone()).or_default().insert(rel.agent_b.clone());
+                graph.entry(rel.agent_b.clone()).or_default().insert(rel.agent_a.clone());
+            }
+        }
+
+        let mut cliques = Vec::new();
+        let candidates: HashSet<AgentId> = graph.keys().cloned().collect();
+        bron_kerbosch(&graph, Vec::new(), candidates, HashSet::new(), &mut cliques);
+
+        cliques.retain(|c| c.len() >= self.model.min_clique_size);
+        for clique in &mut cliques {
+            clique.sort_by_key(|id| id.0);
+        }
+        cliques.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a[0].0.cmp(&b[0].0)));
+        cliques
+    }
+
+    /// 某个 Agent 所在的小圈子
+    pub fn cliques_of(&self, agent_id: &AgentId) -> Vec<&Vec<AgentId>> {
+        self.cliques()
+            .iter()
+            .filter(|c| c.contains(agent_id))
+            .collect()
+    }
+
+    /// 添加关系标签（已有则忽略）
+    pub fn add_tag(&mut self, a: &AgentId, b: &AgentId, tag: &str) {
+        let rel = self.get_or_create(a, b);
+        if !rel.tags.iter().any(|t| t == tag) {
+            rel.tags.push(tag.to_string());
+        }
     }
 
     /// 获取某个 Agent 的所有关系
@@ -95,14 +343,157 @@ impl RelationshipManager {
             })
             .collect();
 
-        related.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        related.sort_by(|a, b| b.1.total_cmp(&a.1));
         related.truncate(limit);
         related
     }
 }
 
+/// 带主元的 Bron–Kerbosch 极大团枚举
+fn bron_kerbosch(
+    graph: &HashMap<AgentId, HashSet<AgentId>>,
+    clique: Vec<AgentId>,
+    mut candidates: HashSet<AgentId>,
+    mut excluded: HashSet<AgentId>,
+    out: &mut Vec<Vec<AgentId>>,
+) {
+    if candidates.is_empty() {
+        if excluded.is_empty() {
+            out.push(clique);
+        }
+        return;
+    }
+    let empty = HashSet::new();
+    let neighbors = |v: &AgentId| graph.get(v).unwrap_or(&empty);
+    let pivot = candidates
+        .union(&excluded)
+        .max_by_key(|v| neighbors(v).intersection(&candidates).count())
+        .cloned()
+        .expect("candidates is not empty");
+
+    let branches: Vec<AgentId> = candidates
+        .difference(neighbors(&pivot))
+        .cloned()
+        .collect();
+    for v in branches {
+        let mut next = clique.clone();
+        next.push(v.clone());
+        bron_kerbosch(
+            graph,
+            next,
+            candidates.intersection(neighbors(&v)).cloned().collect(),
+            excluded.intersection(neighbors(&v)).cloned().collect(),
+            out,
+        );
+        candidates.remove(&v);
+        excluded.insert(v);
+    }
+}
+
+/// 关系描述文本（用于感知与 Prompt）
+pub fn relationship_description(rel: &Relationship) -> String {
+    let teacher_student = rel.tags.iter().any(|t| t == TEACHER_STUDENT_TAG);
+    let derived = label_of(rel);
+    let label = if teacher_student {
+        if rel.closeness > 0.3 {
+            "关系融洽的师生"
+        } else if rel.closeness > -0.3 {
+            "师生"
+        } else {
+            "师生关系紧张"
+        }
+    } else if let Some(label) = derived {
+        label.tag()
+    } else if rel.closeness > 0.3 {
+        "关系不错"
+    } else if rel.closeness > -0.3 {
+        "普通同学"
+    } else if rel.closeness > -0.6 {
+        "关系不太好"
+    } else {
+        "关系紧张"
+    };
+
+    let tags: Vec<&str> = rel
+        .tags
+        .iter()
+        .map(String::as_str)
+        .filter(|t| *t != TEACHER_STUDENT_TAG && RelationshipLabel::from_tag(t).is_none())
+        .collect();
+    if tags.is_empty() {
+        label.to_string()
+    } else {
+        format!("{label}，{}", tags.join("、"))
+    }
+}
+
 impl Default for RelationshipManager {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels_have_hysteresis() {
+        let mut manager = RelationshipManager::new();
+        let (a, b) = (AgentId::new(), AgentId::new());
+        let time = SimulationTime::new();
+
+        manager.update_closeness(&a, &b, 0.45, &time);
+        assert_eq!(label_of(manager.get(&a, &b).unwrap()), Some(RelationshipLabel::Friend));
+
+        // 略低于入门阈值仍是朋友，跌破滞回区间才失去标签
+        manager.update_closeness(&a, &b, -0.1, &time);
+        assert_eq!(label_of(manager.get(&a, &b).unwrap()), Some(RelationshipLabel::Friend));
+        manager.update_closeness(&a, &b, -0.1, &time);
+        assert_eq!(label_of(manager.get(&a, &b).unwrap()), None);
+
+        let changes = manager.take_label_changes();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[1].from, Some(RelationshipLabel::Friend));
+    }
+
+    #[test]
+    fn test_idle_relationships_decay() {
+        let mut manager = RelationshipManager::new();
+        let (a, b) = (AgentId::new(), AgentId::new());
+        let mut time = SimulationTime::new();
+        manager.update_closeness(&a, &b, 0.8, &time);
+        manager.update_trust(&a, &b, 0.3, &time);
+
+        time.tick += 24;
+        manager.decay(&time, 1);
+        assert_eq!(manager.get(&a, &b).unwrap().closeness, 0.8);
+
+        time.tick += 48;
+        manager.decay(&time, 1);
+        let rel = manager.get(&a, &b).unwrap();
+        assert!(rel.closeness < 0.8 && rel.trust < 0.8);
+    }
+
+    #[test]
+    fn test_cliques_are_maximal_friend_groups() {
+        let mut manager = RelationshipManager::new();
+        let ids: Vec<AgentId> = (0..5).map(|_| AgentId::new()).collect();
+        let time = SimulationTime::new();
+        // 0-1-2 互为朋友，2-3 是朋友，4 与所有人关系一般
+        for (x, y) in [(0, 1), (0, 2), (1, 2), (2, 3)] {
+            manager.update_closeness(&ids[x], &ids[y], 0.5, &time);
+        }
+        manager.update_closeness(&ids[3], &ids[4], 0.1, &time);
+
+        let cliques = manager.cliques();
+        assert_eq!(cliques.len(), 1);
+        assert_eq!(cliques[0].len(), 3);
+        assert!(!cliques[0].contains(&ids[3]));
+        assert_eq!(manager.cliques_of(&ids[1]).len(), 1);
+
+        // 朋友关系破裂后缓存失效
+        manager.update_closeness(&ids[0], &ids[1], -1.0, &time);
+        assert!(manager.cliques_of(&ids[1]).is_empty());
+    }
+}
@@ -0,0 +1,485 @@
+//! 场景文件
+//!
+//! 以 JSON 描述一所学校：校园布局（邻接、容量）、学科、各班级课程表、
+//! 选修课、社团、考试安排、初始学生名单与仿真配置。加载后经过校验再交给 `WorldState`。
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use ai_school_core::config::SimulationConfig;
+use ai_school_core::error::WorldError;
+use ai_school_core::types::{
+    AgentRole, BigFiveParams, CareerCategory, Location, LocationId, PersonalityParams,
+};
+
+use crate::curriculum::{ClassGroup, Elective, Subject};
+use crate::time::SchoolCalendar;
+use crate::social::Club;
+
+/// 内置默认场景
+const DEFAULT_SCENARIO: &str = include_str!("../scenarios/default.json");
+
+/// 场景定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// 仿真配置（缺省字段取默认值）
+    #[serde(default)]
+    pub config: SimulationConfig,
+    pub locations: Vec<Location>,
+    pub subjects: Vec<Subject>,
+    /// 班级（每个班级有独立课程表）
+    pub class_groups: Vec<ClassGroup>,
+    #[serde(default)]
+    pub electives: Vec<Elective>,
+    #[serde(default)]
+    pub clubs: Vec<Club>,
+    /// 校历：作息铃声、节假日与寒暑假
+    #[serde(default)]
+    pub calendar: SchoolCalendar,
+    /// 初始名单：学生与教职工（没有学生时由调用方随机生成学生）
+    #[serde(default)]
+    pub roster: Vec<RosterEntry>,
+    /// 教职工办公室（缺省时教职工留在原地）
+    #[serde(default)]
+    pub staff_room: Option<LocationId>,
+    /// 作息中各时段的去处
+    #[serde(default)]
+    pub routines: Routines,
+}
+
+/// 作息去处：课间、用餐、课外活动、晚自习、就寝、休息日，以及新 Agent 的初始位置
+///
+/// 缺省值对应内置校园的位置 ID；自定义校园须给出自己的位置。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Routines {
+    /// 课间休息
+    pub break_locations: Vec<LocationId>,
+    /// 用餐（学生与教职工）
+    pub meal_location: LocationId,
+    /// 课外活动（非社团活动日）
+    pub free_time_locations: Vec<LocationId>,
+    /// 不属于任何班级的学生上晚自习的地方
+    pub study_locations: Vec<LocationId>,
+    /// 就寝
+    pub dormitory: LocationId,
+    /// 休息日
+    pub day_off_locations: Vec<LocationId>,
+    /// 未指定位置的新 Agent 的初始位置
+    pub spawn_location: LocationId,
+}
+
+impl Default for Routines {
+    fn default() -> Self {
+        let ids = |ids: &[&str]| ids.iter().map(|id| LocationId(id.to_string())).collect();
+        Self {
+            break_locations: ids(&["rest_area", "hallway", "playground"]),
+            meal_location: LocationId("cafeteria".to_string()),
+            free_time_locations: ids(&["library", "playground", "club_room", "rest_area", "study_room"]),
+            study_locations: ids(&["study_room", "library"]),
+            dormitory: LocationId("dormitory".to_string()),
+            day_off_locations: ids(&["library", "playground", "dormitory", "rest_area", "club_room"]),
+            spawn_location: LocationId("dormitory".to_string()),
+        }
+    }
+}
+
+impl Routines {
+    /// 各作息引用的位置（名称, 位置列表）
+    fn references(&self) -> Vec<(&str, Vec<&LocationId>)> {
+        vec![
+            ("break_locations", self.break_locations.iter().collect()),
+            ("meal_location", vec![&self.meal_location]),
+            ("free_time_locations", self.free_time_locations.iter().collect()),
+            ("study_locations", self.study_locations.iter().collect()),
+            ("dormitory", vec![&self.dormitory]),
+            ("day_off_locations", self.day_off_locations.iter().collect()),
+            ("spawn_location", vec![&self.spawn_location]),
+        ]
+    }
+}
+
+/// 名单中的学生或教职工
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RosterEntry {
+    pub name: String,
+    /// 角色（缺省为学生）
+    #[serde(default)]
+    pub role: AgentRole,
+    /// 教师任课的班级
+    #[serde(default)]
+    pub teaches: Vec<String>,
+    /// 担任班主任的班级
+    #[serde(default)]
+    pub head_teacher_of: Option<String>,
+    #[serde(default)]
+    pub age: Option<u8>,
+    /// 背景（教职工的人物设定也写在这里）
+    #[serde(default)]
+    pub background: Option<String>,
+    /// MBTI 四轴分数 [e_i, s_n, t_f, j_p]
+    #[serde(default)]
+    pub mbti: Option<[f32; 4]>,
+    /// 大五人格参数（优先于 `mbti`）
+    #[serde(default)]
+    pub big_five: Option<BigFiveParams>,
+    #[serde(default)]
+    pub career: Option<RosterCareer>,
+    /// 初始位置（缺省：学生为作息中的初始位置，教职工为办公室）
+    #[serde(default)]
+    pub location: Option<LocationId>,
+    /// 所在班级名称（缺省时分到人数最少的班级）
+    #[serde(default)]
+    pub class_group: Option<String>,
+    /// 选修课名称（未选的时段按学科偏好自动分配）
+    #[serde(default)]
+    pub electives: Vec<String>,
+    /// 加入的社团名称
+    #[serde(default)]
+    pub clubs: Vec<String>,
+}
+
+/// 名单中的职业志向
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RosterCareer {
+    pub ideal_career: String,
+    pub category: CareerCategory,
+}
+
+impl RosterEntry {
+    /// 名单指定的人格参数（未指定时为 None，由调用方生成）
+    pub fn personality(&self) -> Option<PersonalityParams> {
+        if let Some(big_five) = &self.big_five {
+            return Some(PersonalityParams::from_big_five(big_five.clone()));
+        }
+        self.mbti
+            .map(|[e_i, s_n, t_f, j_p]| PersonalityParams::new(e_i, s_n, t_f, j_p))
+    }
+}
+
+impl Scenario {
+    /// 内置默认场景
+    pub fn bundled() -> Self {
+        Self::from_json(DEFAULT_SCENARIO).expect("bundled scenario must be valid")
+    }
+
+    /// 从 JSON 文本解析并校验
+    pub fn from_json(json: &str) -> Result<Self, WorldError> {
+        let scenario: Scenario = serde_json::from_str(json)
+            .map_err(|e| WorldError::InvalidScenario(format!("parse error: {e}")))?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// 从文件加载并校验
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, WorldError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            WorldError::InvalidScenario(format!("cannot read {}: {e}", path.display()))
+        })?;
+        Self::from_json(&json)
+    }
+
+    /// 校验引用完整性，一次报告所有问题
+    pub fn validate(&self) -> Result<(), WorldError> {
+        let mut problems = Vec::new();
+
+        let mut location_ids = HashSet::new();
+        for location in &self.locations {
+            if !location_ids.insert(&location.id) {
+                problems.push(format!("duplicate location id: {}", location.id));
+            }
+        }
+        let known = |id: &LocationId| location_ids.contains(id);
+
+        for location in &self.locations {
+            for adjacent in &location.adjacent {
+                if !known(adjacent) {
+                    problems.push(format!(
+                        "location {} is adjacent to unknown location {adjacent}",
+                        location.id
+                    ));
+                }
+            }
+        }
+
+        let mut subject_names = HashSet::new();
+        for subject in &self.subjects {
+            if !subject_names.insert(subject.name.as_str()) {
+                problems.push(format!("duplicate subject: {}", subject.name));
+            }
+            if !known(&subject.classroom) {
+                problems.push(format!(
+                    "subject {} uses unknown classroom {}",
+                    subject.name, subject.classroom
+                ));
+            }
+        }
+
+        self.calendar.validate(&mut problems);
+        let periods = self.calendar.periods();
+
+        let check_class = |problems: &mut Vec<String>,
+                           owner: &str,
+                           day: u32,
+                           period: u32,
+                           subject: &str,
+                           location: Option<&LocationId>| {
+            if !(1..=7).contains(&day) {
+                problems.push(format!("invalid day_of_week {day} for {subject} in {owner}"));
+            }
+            if !periods.contains(&period) {
+                problems.push(format!("{subject} in {owner} is in period {period} with no bell"));
+            }
+            if let Some(location) = location.filter(|l| !known(l)) {
+                problems.push(format!("class {subject} in {owner} uses unknown classroom {location}"));
+            }
+        };
+
+        if self.class_groups.is_empty() {
+            problems.push("scenario has no class groups".to_string());
+        }
+        let mut group_names = HashSet::new();
+        for group in &self.class_groups {
+            if !group_names.insert(group.name.as_str()) {
+                problems.push(format!("duplicate class group: {}", group.name));
+            }
+            if !known(&group.homeroom) {
+                problems.push(format!("class group {} uses unknown homeroom {}", group.name, group.homeroom));
+            }
+            let mut slots = HashSet::new();
+            for class in &group.schedule {
+                check_class(&mut problems, &group.name, class.day_of_week, class.period, &class.subject, class.location.as_ref());
+                if !slots.insert((class.day_of_week, class.period)) {
+                    problems.push(format!(
+                        "duplicate timetable slot in {}: day {} period {}",
+                        group.name, class.day_of_week, class.period
+                    ));
+                }
+            }
+        }
+
+        let mut electives = HashMap::new();
+        for elective in &self.electives {
+            let slot = (elective.day_of_week, elective.period);
+            if electives.insert(elective.name.as_str(), slot).is_some() {
+                problems.push(format!("duplicate elective: {}", elective.name));
+            }
+            check_class(&mut problems, &elective.name, elective.day_of_week, elective.period, &elective.subject, elective.location.as_ref());
+            if elective.location.is_none() && !subject_names.contains(elective.subject.as_str()) {
+                problems.push(format!(
+                    "elective {} has no subject definition and no location",
+                    elective.name
+                ));
+            }
+        }
+
+        let mut club_names = HashSet::new();
+        for club in &self.clubs {
+            if !club_names.insert(club.name.as_str()) {
+                problems.push(format!("duplicate club: {}", club.name));
+            }
+            if !known(&club.location) {
+                problems.push(format!("club {} meets at unknown location {}", club.name, club.location));
+            }
+            for day in club.meeting_days.iter().filter(|d| !(1..=7).contains(*d)) {
+                problems.push(format!("club {} meets on invalid day {day}", club.name));
+            }
+        }
+
+        let mut student_names = HashSet::new();
+        for entry in &self.roster {
+            if !student_names.insert(entry.name.as_str()) {
+                problems.push(format!("duplicate student name: {}", entry.name));
+            }
+            if let Some(location) = entry.location.as_ref().filter(|l| !known(l)) {
+                problems.push(format!("student {} starts at unknown location {location}", entry.name));
+            }
+            if let Some(group) = entry.class_group.as_ref().filter(|g| !group_names.contains(g.as_str())) {
+                problems.push(format!("student {} is in unknown class group {group}", entry.name));
+            }
+            let mut chosen_slots = HashSet::new();
+            for elective in &entry.electives {
+                match electives.get(elective.as_str()) {
+                    None => problems.push(format!("student {} takes unknown elective {elective}", entry.name)),
+                    Some(slot) if !chosen_slots.insert(*slot) => problems.push(format!(
+                        "student {} takes two electives at day {} period {}",
+                        entry.name, slot.0, slot.1
+                    )),
+                    Some(_) => {}
+                }
+            }
+            for club in &entry.clubs {
+                if !club_names.contains(club.as_str()) {
+                    problems.push(format!("student {} joins unknown club {club}", entry.name));
+                }
+            }
+            if entry.role.is_staff() {
+                if entry.class_group.is_some() || !entry.electives.is_empty() || !entry.clubs.is_empty() {
+                    problems.push(format!(
+                        "staff {} cannot have a class group, electives or clubs",
+                        entry.name
+                    ));
+                }
+            } else if !entry.teaches.is_empty() || entry.head_teacher_of.is_some() {
+                problems.push(format!("student {} cannot teach or lead a class", entry.name));
+            }
+            if let AgentRole::Teacher { subjects, .. } = &entry.role {
+                for subject in subjects.iter().filter(|s| !subject_names.contains(s.as_str())) {
+                    problems.push(format!("teacher {} teaches unknown subject {subject}", entry.name));
+                }
+            } else if !entry.teaches.is_empty() {
+                problems.push(format!("{} teaches classes but is not a teacher", entry.name));
+            }
+            for group in entry.teaches.iter().chain(&entry.head_teacher_of) {
+                if !group_names.contains(group.as_str()) {
+                    problems.push(format!("{} is assigned to unknown class group {group}", entry.name));
+                }
+            }
+        }
+
+        let mut head_teachers = HashSet::new();
+        for group in self.roster.iter().filter_map(|e| e.head_teacher_of.as_ref()) {
+            if !head_teachers.insert(group.as_str()) {
+                problems.push(format!("class group {group} has more than one head teacher"));
+            }
+        }
+        if let Some(room) = self.staff_room.as_ref().filter(|r| !known(r)) {
+            problems.push(format!("unknown staff room {room}"));
+        }
+        for (routine, locations) in self.routines.references() {
+            if locations.is_empty() {
+                problems.push(format!("routine {routine} has no locations"));
+            }
+            for location in locations.into_iter().filter(|l| !known(l)) {
+                problems.push(format!("routine {routine} uses unknown location {location}"));
+            }
+        }
+
+        for elective in &self.electives {
+            let members = self.roster.iter().filter(|e| e.electives.contains(&elective.name)).count();
+            if members > elective.max_members {
+                problems.push(format!(
+                    "elective {} has {members} members, max {}",
+                    elective.name, elective.max_members
+                ));
+            }
+        }
+
+        for club in &self.clubs {
+            let members = self.roster.iter().filter(|e| e.clubs.contains(&club.name)).count();
+            if members > club.max_members {
+                problems.push(format!(
+                    "club {} has {members} members, max {}",
+                    club.name, club.max_members
+                ));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(WorldError::InvalidScenario(problems.join("; ")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_scenario_is_valid() {
+        let scenario = Scenario::bundled();
+        assert!(!scenario.locations.is_empty());
+        assert!(scenario.subjects.iter().any(|s| s.name == "数学"));
+        assert_eq!(scenario.class_groups.len(), 2);
+        assert!(scenario.class_groups.iter().all(|g| g.schedule.len() == 25));
+        assert!(scenario.roster.iter().all(|e| e.role.is_staff()));
+    }
+
+    #[test]
+    fn test_bundled_class_groups_use_different_rooms() {
+        let scenario = Scenario::bundled();
+        let room = |subject: &str| {
+            scenario
+                .subjects
+                .iter()
+                .find(|s| s.name == subject)
+                .map(|s| s.classroom.clone())
+        };
+        let (first, second) = (&scenario.class_groups[0], &scenario.class_groups[1]);
+
+        let shared: Vec<_> = first
+            .schedule
+            .iter()
+            .filter(|a| a.location.is_none())
+            .filter(|a| {
+                second
+                    .class_at(a.day_of_week, a.period)
+                    .is_some_and(|b| room(&a.subject) == room(&b.subject))
+            })
+            .collect();
+        // 只有选修课时段两班共用教室
+        assert!(shared.iter().all(|c| scenario
+            .electives
+            .iter()
+            .any(|e| e.day_of_week == c.day_of_week && e.period == c.period)));
+    }
+
+    #[test]
+    fn test_validation_reports_broken_references() {
+        let mut scenario = Scenario::bundled();
+        scenario.locations[0].adjacent.push(LocationId("nowhere".to_string()));
+        scenario.subjects[0].classroom = LocationId("missing_room".to_string());
+        let duplicate = scenario.locations[1].clone();
+        scenario.locations.push(duplicate);
+        scenario.roster.push(RosterEntry {
+            name: "小明".to_string(),
+            role: AgentRole::Student,
+            teaches: Vec::new(),
+            head_teacher_of: None,
+            age: None,
+            background: None,
+            mbti: Some([0.5, -0.2, 0.1, 0.3]),
+            big_five: None,
+            career: None,
+            location: None,
+            class_group: Some("高三(9)班".to_string()),
+            electives: vec!["美术选修".to_string(), "音乐选修".to_string()],
+            clubs: vec!["围棋社".to_string()],
+        });
+        scenario.class_groups[1].homeroom = LocationId("attic".to_string());
+        let teacher = &mut scenario.roster[0];
+        teacher.role = AgentRole::Teacher {
+            subjects: vec!["天文".to_string()],
+            style: Default::default(),
+        };
+        teacher.head_teacher_of = Some("高一(2)班".to_string());
+        scenario.calendar.exam_weeks[0].exam_day = 6;
+        scenario.calendar.exam_weeks[1].week = 5;
+        scenario.calendar.exam_weeks[1].exam_day = 3;
+        scenario.routines.meal_location = LocationId("canteen".to_string());
+        scenario.routines.break_locations.clear();
+
+        let err = scenario.validate().unwrap_err().to_string();
+        assert!(err.contains("unknown location nowhere"));
+        assert!(err.contains("unknown classroom missing_room"));
+        assert!(err.contains("duplicate location id"));
+        assert!(err.contains("unknown club 围棋社"));
+        assert!(err.contains("unknown class group 高三(9)班"));
+        assert!(err.contains("two electives at day 3 period 5"));
+        assert!(err.contains("unknown homeroom attic"));
+        assert!(err.contains("teaches unknown subject 天文"));
+        assert!(err.contains("more than one head teacher"));
+        assert!(err.contains("invalid date week 6 day 6"));
+        assert!(err.contains("exam 期中考试 falls on holiday 国庆节"));
+        assert!(err.contains("routine meal_location uses unknown location canteen"));
+        assert!(err.contains("routine break_locations has no locations"));
+    }
+}
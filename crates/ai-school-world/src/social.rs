@@ -4,7 +4,9 @@
 
 use serde::{Deserialize, Serialize};
 
-use ai_school_core::types::{AgentId, LocationId};
+use ai_school_core::types::{
+    AbilityMetrics, AgentId, CareerAspiration, CareerCategory, LocationId, PersonalityParams,
+};
 
 /// 社团定义
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,56 +14,122 @@ pub struct Club {
     pub name: String,
     pub description: String,
     pub location: LocationId,
+    #[serde(default)]
     pub members: Vec<AgentId>,
     pub max_members: usize,
     /// 活动频率（每周次数）
     pub activity_frequency: u32,
+    /// 活动日（星期几）；缺省按活动频率在工作日均匀分布
+    #[serde(default)]
+    pub meeting_days: Vec<u32>,
+    /// 契合的职业类别
+    #[serde(default)]
+    pub career_categories: Vec<CareerCategory>,
+    /// 社交强度：-1.0（安静、适合内向者）~ 1.0（热闹、适合外向者）
+    #[serde(default)]
+    pub sociability: f32,
+    /// 活动锻炼的能力
+    #[serde(default)]
+    pub trains: Vec<Ability>,
 }
 
-/// 创建默认社团列表
-pub fn create_default_clubs() -> Vec<Club> {
-    vec![
-        Club {
-            name: "编程社".to_string(),
-            description: "学习编程和计算机科学".to_string(),
-            location: LocationId("classroom_science".to_string()),
-            members: Vec::new(),
-            max_members: 15,
-            activity_frequency: 2,
-        },
-        Club {
-            name: "文学社".to_string(),
-            description: "阅读和创作文学作品".to_string(),
-            location: LocationId("library".to_string()),
-            members: Vec::new(),
-            max_members: 20,
-            activity_frequency: 2,
-        },
-        Club {
-            name: "篮球队".to_string(),
-            description: "篮球训练和比赛".to_string(),
-            location: LocationId("playground".to_string()),
-            members: Vec::new(),
-            max_members: 12,
-            activity_frequency: 3,
-        },
-        Club {
-            name: "辩论社".to_string(),
-            description: "锻炼逻辑思维和表达能力".to_string(),
-            location: LocationId("club_room".to_string()),
-            members: Vec::new(),
-            max_members: 16,
-            activity_frequency: 2,
-        },
-        Club {
-            name: "美术社".to_string(),
-            description: "绘画和艺术创作".to_string(),
-            location: LocationId("club_room".to_string()),
-            members: Vec::new(),
-            max_members: 15,
-            activity_frequency: 2,
-        },
-    ]
+/// 可通过活动锻炼的能力
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Ability {
+    Academic,
+    Social,
+    Resilience,
+    Creativity,
+}
+
+impl Ability {
+    /// 提升能力值（上限 1.0）
+    pub fn train(self, abilities: &mut AbilityMetrics, gain: f32) {
+        let value = self.value_mut(abilities);
+        *value = (*value + gain).clamp(0.0, 1.0);
+    }
+
+    pub fn value(self, abilities: &AbilityMetrics) -> f32 {
+        match self {
+            Ability::Academic => abilities.academic,
+            Ability::Social => abilities.social,
+            Ability::Resilience => abilities.resilience,
+            Ability::Creativity => abilities.creativity,
+        }
+    }
+
+    pub fn value_mut(self, abilities: &mut AbilityMetrics) -> &mut f32 {
+        match self {
+            Ability::Academic => &mut abilities.academic,
+            Ability::Social => &mut abilities.social,
+            Ability::Resilience => &mut abilities.resilience,
+            Ability::Creativity => &mut abilities.creativity,
+        }
+    }
+}
+
+impl Club {
+    /// 本周的活动日
+    pub fn meeting_days(&self) -> Vec<u32> {
+        if !self.meeting_days.is_empty() {
+            return self.meeting_days.clone();
+        }
+        let frequency = self.activity_frequency.min(5);
+        (0..frequency).map(|i| 1 + i * 5 / frequency).collect()
+    }
+
+    pub fn meets_on(&self, day_of_week: u32) -> bool {
+        self.meeting_days().contains(&day_of_week)
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.members.len() >= self.max_members
+    }
+
+    /// 学生对社团的兴趣 (0.3 ~ 1.0)：职业契合 + 社交强度与外向程度的匹配
+    pub fn affinity(&self, personality: &PersonalityParams, career: &CareerAspiration) -> f32 {
+        let career_fit = if self.career_categories.contains(&career.category) {
+            0.4
+        } else {
+            0.0
+        };
+        // e_i: -1.0 外倾 ~ 1.0 内倾
+        let extraversion = -personality.e_i;
+        let social_fit = 1.0 - (self.sociability - extraversion).abs() / 2.0;
+        0.3 + career_fit + 0.3 * social_fit
+    }
+}
+
+/// 社团参与参数
+#[derive(Debug, Clone)]
+pub struct ClubModel {
+    /// 兴趣达到此值才会加入
+    pub join_threshold: f32,
+    /// 每人最多加入的社团数
+    pub max_clubs_per_agent: usize,
+    /// 每次活动对所锻炼能力的提升
+    pub ability_gain: f32,
+    /// 每次活动中成员两两之间的亲近度提升
+    pub closeness_gain: f32,
+}
+
+impl Default for ClubModel {
+    fn default() -> Self {
+        Self {
+            join_threshold: 0.55,
+            max_clubs_per_agent: 2,
+            ability_gain: 0.01,
+            closeness_gain: 0.03,
+        }
+    }
+}
+
+/// 一次社团活动
+#[derive(Debug, Clone)]
+pub struct ClubMeeting {
+    pub club: String,
+    pub location: LocationId,
+    pub attendees: Vec<AgentId>,
 }
 
 /// 社交事件模板
@@ -100,3 +168,56 @@ pub fn social_event_templates() -> Vec<SocialEventTemplate> {
         },
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::Scenario;
+    use ai_school_core::types::CareerAspiration;
+
+    fn club(name: &str) -> Club {
+        Scenario::bundled()
+            .clubs
+            .into_iter()
+            .find(|c| c.name == name)
+            .unwrap()
+    }
+
+    fn career(category: CareerCategory) -> CareerAspiration {
+        CareerAspiration {
+            ideal_career: "探索中".to_string(),
+            category,
+            subject_preferences: Vec::new(),
+            clarity: 0.5,
+            history: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_affinity_follows_personality_and_career() {
+        let basketball = club("篮球队");
+        let coding = club("编程社");
+        let extravert = PersonalityParams::new(-0.8, 0.0, 0.0, 0.0);
+        let introvert = PersonalityParams::new(0.8, 0.0, 0.0, 0.0);
+        let undecided = career(CareerCategory::Other("未定".to_string()));
+
+        assert!(
+            basketball.affinity(&extravert, &undecided) > basketball.affinity(&introvert, &undecided)
+        );
+        assert!(
+            coding.affinity(&introvert, &career(CareerCategory::Technology))
+                > coding.affinity(&introvert, &undecided)
+        );
+    }
+
+    #[test]
+    fn test_meeting_days_spread_by_frequency() {
+        let mut club = club("篮球队");
+        assert_eq!(club.meeting_days(), vec![1, 3, 5]);
+
+        club.meeting_days.clear();
+        club.activity_frequency = 2;
+        assert_eq!(club.meeting_days(), vec![1, 3]);
+        assert!(club.meets_on(3) && !club.meets_on(2));
+    }
+}
@@ -2,23 +2,40 @@
 //!
 //! 聚合所有子系统状态，状态变更的验证与应用。
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use rand::seq::SliceRandom;
+use rand::Rng;
 use tracing::{debug, warn};
 
 use ai_school_core::error::WorldError;
 use ai_school_core::types::{
-    AgentActivity, AgentId, AgentState, ChangeType, Location, LocationId,
-    SimulationEvent, StateChange, WorldSnapshot,
+    AgentActivity, AgentId, AgentMovement, AgentRole, AgentState, EventId, EventScope, EventTrigger, EventType,
+    Location, LocationId,
+    ObservedAgent, Perception, SimulationEvent, StateChange, WorldSnapshot,
 };
 
 use crate::time::TimeEvent;
 
-use crate::campus::create_default_campus;
-use crate::curriculum::{create_default_schedule, create_default_subjects, ClassSchedule, Subject};
-use crate::relationships::RelationshipManager;
-use crate::social::{create_default_clubs, Club};
+use crate::academics::{AcademicTracker, StudySession, SubjectPerformance};
+use crate::changes::{
+    AgentField, ChangeAudit, ChangeBudget, ChangeError, ChangeLimits, ChangeOp, ChangeOutcome, ChangeTarget,
+    RelationshipField, TypedChange, ValueKind,
+};
+use crate::curriculum::{
+    calculate_academic_feedback, AcademicFeedback, ClassGroup, ClassSession, Elective, Subject,
+};
+use crate::campus::{path_length, shortest_path};
+use crate::environment::{params_at, EnvironmentParams, EnvironmentRecord};
+use crate::causality::{causal_trace, CausalTrace, TraceQuery};
+use crate::exams::{ExamInput, ExamKind, ExamPeriod, ExamRecord, ExamScore, Gradebook};
+use crate::movement::{Encounter, Journey, MovementModel, Pass, Redirect};
+use crate::network::{analyze, NetworkMetrics, NetworkParams, NetworkSample};
+use crate::relationships::{
+    relationship_description, RelationshipLabel, RelationshipManager, TEACHER_STUDENT_TAG,
+};
+use crate::scenario::{Routines, Scenario};
+use crate::social::{Club, ClubMeeting, ClubModel};
 use crate::time::SimulationClock;
 
 /// 世界状态管理器 — ADR-0003 结构化世界状态管理器
@@ -29,37 +46,153 @@ pub struct WorldState {
     pub agents: HashMap<AgentId, AgentState>,
     /// 关系矩阵
     pub relationships: RelationshipManager,
-    /// 课程表
-    pub schedule: Vec<ClassSchedule>,
+    /// 社交网络分析参数
+    pub network_params: NetworkParams,
+    /// 每天的社交网络指标
+    pub network_history: Vec<NetworkMetrics>,
+    /// 班级（各自的课程表与成员）
+    pub class_groups: Vec<ClassGroup>,
+    /// 选修课
+    pub electives: Vec<Elective>,
     /// 学科列表
     pub subjects: Vec<Subject>,
+    /// 各学科在场景中设定的难度调整，课程难度参数在此基础上叠加
+    subject_modifiers: HashMap<String, f32>,
+    /// 环境参数
+    pub environment: EnvironmentParams,
+    /// 环境参数的调整记录
+    pub environment_history: Vec<EnvironmentRecord>,
     /// 社团列表
     pub clubs: Vec<Club>,
+    /// 社团参与参数
+    pub club_model: ClubModel,
+    /// 各社团最近一次活动的日期（学期、周、星期），避免同一天重复举行
+    held_club_meetings: HashMap<String, (u32, u32, u32)>,
+    /// 学业表现追踪
+    pub academics: AcademicTracker,
+    /// 校历中的考试
+    pub exam_calendar: Vec<ExamPeriod>,
+    /// 成绩册
+    pub gradebook: Gradebook,
+    /// 已举行考试的日期（学期、周、星期），避免同一天重复考试
+    held_exams: HashSet<(u32, u32, u32)>,
+    /// 教职工办公室
+    pub staff_room: Option<LocationId>,
+    /// 作息去处
+    pub routines: Routines,
+    /// 移动模型
+    pub movement: MovementModel,
+    /// 进行中的行程
+    pub journeys: HashMap<AgentId, Journey>,
+    /// 本步发生的移动
+    pub movements: Vec<AgentMovement>,
+    /// 待处理的改道记录（由引擎取走并生成事件）
+    pub redirects: Vec<Redirect>,
     /// 仿真时钟
     pub clock: SimulationClock,
     /// 事件日志
     pub event_log: Vec<SimulationEvent>,
+    /// 状态变更的每步上限
+    pub change_limits: ChangeLimits,
+    /// 本步已用掉的变化幅度
+    change_budget: ChangeBudget,
+    /// 状态变更审计日志（按时间顺序，最多保留 `audit_capacity` 条）
+    pub change_audit: VecDeque<ChangeAudit>,
+    /// 审计日志容量
+    pub audit_capacity: usize,
+}
+
+/// 一条变更应用前后的值
+#[derive(Default)]
+struct AppliedChange {
+    old_value: serde_json::Value,
+    new_value: serde_json::Value,
+    /// 被截断的原因
+    reason: Option<String>,
+}
+
+impl AppliedChange {
+    fn new(old_value: serde_json::Value, new_value: serde_json::Value) -> Self {
+        Self { old_value, new_value, reason: None }
+    }
 }
 
 impl WorldState {
-    /// 创建默认世界状态
+    /// 创建默认世界状态（内置默认场景）
     pub fn new(time_step_hours: u32) -> Self {
-        Self {
-            locations: create_default_campus(),
+        Self::from_scenario(&Scenario::bundled(), time_step_hours)
+            .expect("bundled scenario must be valid")
+    }
+
+    /// 从场景创建世界状态（名单中的学生由调用方构建后加入）
+    pub fn from_scenario(scenario: &Scenario, time_step_hours: u32) -> Result<Self, WorldError> {
+        scenario.validate()?;
+
+        Ok(Self {
+            locations: scenario.locations.clone(),
             agents: HashMap::new(),
             relationships: RelationshipManager::new(),
-            schedule: create_default_schedule(),
-            subjects: create_default_subjects(),
-            clubs: create_default_clubs(),
-            clock: SimulationClock::new(time_step_hours),
+            network_params: NetworkParams::default(),
+            network_history: Vec::new(),
+            class_groups: scenario
+                .class_groups
+                .iter()
+                .map(|g| ClassGroup { members: Vec::new(), ..g.clone() })
+                .collect(),
+            electives: scenario
+                .electives
+                .iter()
+                .map(|e| Elective { members: Vec::new(), ..e.clone() })
+                .collect(),
+            subjects: scenario.subjects.clone(),
+            subject_modifiers: scenario
+                .subjects
+                .iter()
+                .map(|s| (s.name.clone(), s.difficulty_modifier))
+                .collect(),
+            environment: EnvironmentParams::default(),
+            environment_history: Vec::new(),
+            clubs: scenario
+                .clubs
+                .iter()
+                .map(|c| Club { members: Vec::new(), ..c.clone() })
+                .collect(),
+            club_model: ClubModel::default(),
+            held_club_meetings: HashMap::new(),
+            academics: AcademicTracker::default(),
+            exam_calendar: scenario.calendar.exam_periods(),
+            gradebook: Gradebook::default(),
+            held_exams: HashSet::new(),
+            staff_room: scenario.staff_room.clone(),
+            routines: scenario.routines.clone(),
+            movement: MovementModel::default(),
+            journeys: HashMap::new(),
+            movements: Vec::new(),
+            redirects: Vec::new(),
+            clock: SimulationClock::with_calendar(scenario.calendar.clone(), time_step_hours),
             event_log: Vec::new(),
-        }
+            change_limits: scenario.config.change_limits.clone(),
+            change_budget: ChangeBudget::default(),
+            change_audit: VecDeque::new(),
+            audit_capacity: scenario.config.audit_capacity,
+        })
     }
 
-    /// 添加 Agent 到世界
-    pub fn add_agent(&mut self, agent: AgentState) {
+    /// 添加 Agent 到世界（学生分到人数最少的班级）
+    pub fn add_agent(&mut self, mut agent: AgentState) {
+        if self.get_location(&agent.location).is_err() {
+            agent.location = self.routines.spawn_location.clone();
+        }
         debug!(agent_id = %agent.id, name = %agent.config.name, "Agent added to world");
-        self.agents.insert(agent.id.clone(), agent);
+        let agent_id = agent.id.clone();
+        let is_staff = agent.config.role.is_staff();
+        self.agents.insert(agent_id.clone(), agent);
+
+        if !is_staff && self.class_group_of(&agent_id).is_none() {
+            if let Some(group) = self.class_groups.iter_mut().min_by_key(|g| g.members.len()) {
+                group.members.push(agent_id.clone());
+            }
+        }
     }
 
     /// 获取 Agent 状态
@@ -69,6 +202,18 @@ impl WorldState {
             .ok_or_else(|| WorldError::AgentNotFound(id.clone()))
     }
 
+    /// 事件影响范围内的 Agent（不存在的 Agent 略过）
+    pub fn agents_in_scope(&self, scope: &EventScope) -> Vec<AgentId> {
+        let mut agents: Vec<AgentId> = match scope {
+            EventScope::Global => self.agents.keys().cloned().collect(),
+            EventScope::Agents(ids) => ids.iter().filter(|id| self.agents.contains_key(id)).cloned().collect(),
+            EventScope::Location(location) => self.agents_at_location(location).iter().map(|a| a.id.clone()).collect(),
+        };
+        agents.sort_by_key(|id| id.0);
+        agents.dedup();
+        agents
+    }
+
     /// 获取可变 Agent 状态
     pub fn get_agent_mut(&mut self, id: &AgentId) -> Result<&mut AgentState, WorldError> {
         self.agents
@@ -76,6 +221,391 @@ impl WorldState {
             .ok_or_else(|| WorldError::AgentNotFound(id.clone()))
     }
 
+    /// 加入社团
+    pub fn join_club(&mut self, agent_id: &AgentId, club_name: &str) -> Result<(), WorldError> {
+        let joined = self.clubs_of(agent_id).len();
+        let club = self
+            .clubs
+            .iter_mut()
+            .find(|c| c.name == club_name)
+            .ok_or_else(|| WorldError::StateError(format!("Unknown club: {club_name}")))?;
+        if club.members.contains(agent_id) {
+            return Ok(());
+        }
+        if club.is_full() {
+            return Err(WorldError::CapacityExceeded(club.location.clone()));
+        }
+        if joined >= self.club_model.max_clubs_per_agent {
+            return Err(WorldError::StateError(format!(
+                "Agent {agent_id} already belongs to {joined} clubs"
+            )));
+        }
+        club.members.push(agent_id.clone());
+        Ok(())
+    }
+
+    /// Agent 加入的社团
+    pub fn clubs_of(&self, agent_id: &AgentId) -> Vec<&Club> {
+        self.clubs
+            .iter()
+            .filter(|c| c.members.contains(agent_id))
+            .collect()
+    }
+
+    /// Agent 最感兴趣、尚有名额且兴趣达到门槛的社团
+    pub fn best_club_for(&self, agent_id: &AgentId) -> Option<&Club> {
+        let agent = self.agents.get(agent_id).filter(|a| !a.config.role.is_staff())?;
+        if self.clubs_of(agent_id).len() >= self.club_model.max_clubs_per_agent {
+            return None;
+        }
+        self.clubs
+            .iter()
+            .filter(|c| !c.is_full() && !c.members.contains(agent_id))
+            .map(|c| {
+                let affinity =
+                    c.affinity(&agent.config.personality, &agent.config.career_aspiration);
+                (c, affinity)
+            })
+            .filter(|(_, affinity)| *affinity >= self.club_model.join_threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(c, _)| c)
+    }
+
+    /// 社团招新：兴趣最高的组合优先，直到名额或个人社团数用尽
+    ///
+    /// 返回新加入的 (Agent, 社团名称)。
+    pub fn recruit_club_members(&mut self) -> Vec<(AgentId, String)> {
+        let mut candidates: Vec<(AgentId, String, f32)> = Vec::new();
+        for agent in self.agents.values().filter(|a| !a.config.role.is_staff()) {
+            for club in &self.clubs {
+                let affinity =
+                    club.affinity(&agent.config.personality, &agent.config.career_aspiration);
+                if affinity >= self.club_model.join_threshold && !club.members.contains(&agent.id) {
+                    candidates.push((agent.id.clone(), club.name.clone(), affinity));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+        let mut joined = Vec::new();
+        for (agent_id, club_name, _) in candidates {
+            if self.join_club(&agent_id, &club_name).is_ok() {
+                joined.push((agent_id, club_name));
+            }
+        }
+        joined
+    }
+
+    /// 今天有活动的社团中 Agent 所属的第一个
+    fn club_meeting_today(&self, agent_id: &AgentId) -> Option<&Club> {
+        let day = self.clock.current_time().day_of_week;
+        self.clubs
+            .iter()
+            .find(|c| c.meets_on(day) && c.members.contains(agent_id))
+    }
+
+    /// 举行今天的社团活动：到场成员锻炼能力、彼此更亲近
+    ///
+    /// 到场指正在参加或正前往该社团活动的成员；每个社团每天至多举行一次。
+    pub fn hold_club_meetings(&mut self) -> Vec<ClubMeeting> {
+        let time = self.clock.current_time().clone();
+        let today = (time.semester, time.week, time.day_of_week);
+        let mut meetings = Vec::new();
+
+        for club in &self.clubs {
+            if !club.meets_on(today.2) || self.held_club_meetings.get(&club.name) == Some(&today) {
+                continue;
+            }
+            let activity = AgentActivity::Activity {
+                name: club.name.clone(),
+            };
+            let attendees: Vec<AgentId> = club
+                .members
+                .iter()
+                .filter(|id| {
+                    let heading = self.journeys.get(*id).map(|j| &j.on_arrival);
+                    let doing = self.agents.get(*id).map(|a| &a.activity);
+                    heading == Some(&activity) || doing == Some(&activity)
+                })
+                .cloned()
+                .collect();
+            if attendees.is_empty() {
+                continue;
+            }
+            meetings.push((
+                ClubMeeting {
+                    club: club.name.clone(),
+                    location: club.location.clone(),
+                    attendees,
+                },
+                club.trains.clone(),
+            ));
+        }
+
+        let model = self.club_model.clone();
+        let mut held = Vec::new();
+        for (meeting, trains) in meetings {
+            for id in &meeting.attendees {
+                if let Some(agent) = self.agents.get_mut(id) {
+                    for ability in &trains {
+                        ability.train(&mut agent.abilities, model.ability_gain);
+                    }
+                }
+            }
+            for (i, a) in meeting.attendees.iter().enumerate() {
+                for b in &meeting.attendees[i + 1..] {
+                    self.relationships.update_closeness(a, b, model.closeness_gain, &time);
+                }
+            }
+            self.held_club_meetings.insert(meeting.club.clone(), today);
+            held.push(meeting);
+        }
+        held
+    }
+
+    /// 今天安排的考试（只在上课日举行）
+    pub fn exam_today(&self) -> Option<&ExamPeriod> {
+        let time = self.clock.current_time();
+        if !time.is_school_day() {
+            return None;
+        }
+        self.exam_calendar
+            .iter()
+            .find(|e| e.week == time.week && e.day_of_week == time.day_of_week)
+    }
+
+    /// 举行今天安排的考试（没有考试或今天已考过时为 None）
+    pub fn hold_scheduled_exam(&mut self) -> Option<Vec<ExamRecord>> {
+        let exam = self.exam_today()?.clone();
+        let time = self.clock.current_time();
+        if !self.held_exams.insert((time.semester, time.week, time.day_of_week)) {
+            return None;
+        }
+        Some(self.sit_exam(&exam.name, exam.kind))
+    }
+
+    /// 全体学生参加考试：考本班课程表上的各科，登记成绩与年级排名
+    pub fn sit_exam(&mut self, name: &str, kind: ExamKind) -> Vec<ExamRecord> {
+        let mut rng = rand::thread_rng();
+        let mut results = Vec::new();
+
+        for agent in self.agents.values().filter(|a| !a.config.role.is_staff()) {
+            let Some(group) = self.class_group_of(&agent.id) else {
+                continue;
+            };
+            let mut subjects: Vec<&str> = Vec::new();
+            for class in &group.schedule {
+                if !subjects.contains(&class.subject.as_str()) {
+                    subjects.push(&class.subject);
+                }
+            }
+
+            let scores = subjects
+                .into_iter()
+                .filter_map(|subject| {
+                    let input = ExamInput {
+                        academic: agent.abilities.academic,
+                        preference: agent
+                            .config
+                            .career_aspiration
+                            .subject_preferences
+                            .iter()
+                            .find(|p| p.subject == subject)
+                            .map(|p| p.preference)
+                            .unwrap_or(0.5),
+                        difficulty: self.get_subject(subject)?.effective_difficulty(),
+                        stress: agent.emotion.stress,
+                        recent_study: self.academics.recent_subject_performance(&agent.id, subject, 10),
+                    };
+                    Some(ExamScore {
+                        subject: subject.to_string(),
+                        score: self.gradebook.model.score(&input, rng.gen_range(-1.0..=1.0)),
+                    })
+                })
+                .collect();
+            results.push((agent.id.clone(), scores));
+        }
+
+        let time = self.clock.current_time().clone();
+        self.gradebook.record_exam(name, kind, &time, results)
+    }
+
+    /// 当前学生社交网络的指标
+    pub fn network_metrics(&self) -> NetworkMetrics {
+        let students: Vec<AgentId> = self
+            .agents
+            .values()
+            .filter(|a| !a.config.role.is_staff())
+            .map(|a| a.id.clone())
+            .collect();
+        analyze(&students, &self.relationships, &self.network_params, self.clock.current_time())
+    }
+
+    /// 记录当前网络指标，供观察网络随时间的演化
+    pub fn record_network_metrics(&mut self) {
+        let metrics = self.network_metrics();
+        self.network_history.push(metrics);
+    }
+
+    /// 调整环境参数：课程难度变化时立即作用于所有学科（叠加在学科自身的调整上），并记录调整
+    pub fn set_environment(&mut self, params: EnvironmentParams) {
+        if params.course_difficulty != self.environment.course_difficulty {
+            let modifier = params.difficulty_modifier();
+            for subject in &mut self.subjects {
+                let base = self.subject_modifiers.get(&subject.name).copied().unwrap_or(0.0);
+                subject.difficulty_modifier = base + modifier;
+            }
+        }
+        self.environment = params;
+        self.environment_history.push(EnvironmentRecord {
+            time: self.clock.current_time().clone(),
+            params,
+        });
+    }
+
+    /// 某一 tick 生效的环境参数
+    pub fn environment_at(&self, tick: u64) -> EnvironmentParams {
+        params_at(&self.environment_history, tick)
+    }
+
+    /// 网络指标的时间序列，每一点附带当时生效的环境参数（供导出）
+    pub fn network_timeline(&self) -> Vec<NetworkSample<'_>> {
+        self.network_history
+            .iter()
+            .map(|metrics| NetworkSample {
+                metrics,
+                environment: self.environment_at(metrics.time.tick),
+            })
+            .collect()
+    }
+
+    /// 是否为教职工
+    pub fn is_staff(&self, agent_id: &AgentId) -> bool {
+        self.agents
+            .get(agent_id)
+            .is_some_and(|a| a.config.role.is_staff())
+    }
+
+    /// 安排教师到班级任课
+    pub fn assign_teacher(&mut self, agent_id: &AgentId, group_name: &str) -> Result<(), WorldError> {
+        if !matches!(self.get_agent(agent_id)?.config.role, AgentRole::Teacher { .. }) {
+            return Err(WorldError::StateError(format!("Agent {agent_id} is not a teacher")));
+        }
+        let group = self.class_group_mut(group_name)?;
+        if !group.teachers.contains(agent_id) {
+            group.teachers.push(agent_id.clone());
+        }
+        Ok(())
+    }
+
+    /// 任命班主任
+    pub fn set_head_teacher(&mut self, agent_id: &AgentId, group_name: &str) -> Result<(), WorldError> {
+        if !self.is_staff(agent_id) {
+            return Err(WorldError::StateError(format!("Agent {agent_id} is not staff")));
+        }
+        self.class_group_mut(group_name)?.head_teacher = Some(agent_id.clone());
+        Ok(())
+    }
+
+    fn class_group_mut(&mut self, group_name: &str) -> Result<&mut ClassGroup, WorldError> {
+        self.class_groups
+            .iter_mut()
+            .find(|g| g.name == group_name)
+            .ok_or_else(|| WorldError::StateError(format!("Unknown class group: {group_name}")))
+    }
+
+    /// Agent 所在班级
+    pub fn class_group_of(&self, agent_id: &AgentId) -> Option<&ClassGroup> {
+        self.class_groups.iter().find(|g| g.members.contains(agent_id))
+    }
+
+    /// 转入班级（离开原班级）
+    pub fn join_class_group(&mut self, agent_id: &AgentId, group_name: &str) -> Result<(), WorldError> {
+        if !self.class_groups.iter().any(|g| g.name == group_name) {
+            return Err(WorldError::StateError(format!("Unknown class group: {group_name}")));
+        }
+        for group in &mut self.class_groups {
+            group.members.retain(|id| id != agent_id);
+            if group.name == group_name {
+                group.members.push(agent_id.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// 选修课程（替换同一时段已选的选修课）
+    pub fn enroll_elective(&mut self, agent_id: &AgentId, elective_name: &str) -> Result<(), WorldError> {
+        let elective = self
+            .electives
+            .iter()
+            .find(|e| e.name == elective_name)
+            .ok_or_else(|| WorldError::StateError(format!("Unknown elective: {elective_name}")))?;
+        if elective.members.contains(agent_id) {
+            return Ok(());
+        }
+        if elective.members.len() >= elective.max_members {
+            return Err(WorldError::StateError(format!("Elective is full: {elective_name}")));
+        }
+
+        let slot = (elective.day_of_week, elective.period);
+        for elective in &mut self.electives {
+            if (elective.day_of_week, elective.period) == slot {
+                elective.members.retain(|id| id != agent_id);
+            }
+            if elective.name == elective_name {
+                elective.members.push(agent_id.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// 为尚未选课的时段分配选修课：有空位的选修课中选学科偏好最高的
+    pub fn assign_electives(&mut self, agent_id: &AgentId) {
+        let Some(agent) = self.agents.get(agent_id).filter(|a| !a.config.role.is_staff()) else {
+            return;
+        };
+        let preference = |subject: &str| {
+            agent
+                .config
+                .career_aspiration
+                .subject_preferences
+                .iter()
+                .find(|p| p.subject == subject)
+                .map(|p| p.preference)
+                .unwrap_or(0.5)
+        };
+
+        let mut choices: Vec<(u32, u32, String, f32)> = Vec::new();
+        for elective in &self.electives {
+            if elective.members.len() >= elective.max_members {
+                continue;
+            }
+            let slot_taken = self.electives.iter().any(|e| {
+                e.day_of_week == elective.day_of_week
+                    && e.period == elective.period
+                    && e.members.contains(agent_id)
+            });
+            if slot_taken {
+                continue;
+            }
+            let score = preference(&elective.subject);
+            match choices
+                .iter_mut()
+                .find(|c| c.0 == elective.day_of_week && c.1 == elective.period)
+            {
+                Some(choice) if choice.3 >= score => {}
+                Some(choice) => *choice = (choice.0, choice.1, elective.name.clone(), score),
+                None => choices.push((elective.day_of_week, elective.period, elective.name.clone(), score)),
+            }
+        }
+
+        for (_, _, name, _) in choices {
+            if let Err(e) = self.enroll_elective(agent_id, &name) {
+                warn!(agent = %agent_id, elective = %name, error = %e, "Elective assignment failed");
+            }
+        }
+    }
+
     /// 获取指定位置的所有 Agent
     pub fn agents_at_location(&self, location_id: &LocationId) -> Vec<&AgentState> {
         self.agents
@@ -92,253 +622,1064 @@ impl WorldState {
             .ok_or_else(|| WorldError::LocationNotFound(id.clone()))
     }
 
-    /// 获取当前时间段的课程
-    pub fn current_class(&self) -> Option<&ClassSchedule> {
-        let time = self.clock.current_time();
-        let period = match time.hour {
-            8 => Some(1),
-            9 => Some(2),
-            11 => Some(3),
-            14 => Some(4),
-            15 => Some(5),
-            _ => None,
+    /// 位置的占用人数：在此停留的 Agent 加上正前往此处的 Agent
+    pub fn occupancy(&self, location: &LocationId) -> usize {
+        self.occupancy_excluding(location, None)
+    }
+
+    fn occupancy_excluding(&self, location: &LocationId, exclude: Option<&AgentId>) -> usize {
+        self.agents
+            .values()
+            .filter(|a| Some(&a.id) != exclude)
+            .filter(|a| match self.journeys.get(&a.id) {
+                Some(journey) => journey.destination() == location,
+                None => a.location == *location,
+            })
+            .count()
+    }
+
+    /// 位置是否还能容纳该 Agent
+    pub fn has_room_for(&self, location: &LocationId, agent_id: &AgentId) -> bool {
+        self.get_location(location)
+            .map(|l| self.occupancy_excluding(location, Some(agent_id)) < l.capacity)
+            .unwrap_or(false)
+    }
+
+    /// 拥挤度：当前在场人数 / 容量
+    pub fn crowding(&self, location: &LocationId) -> f32 {
+        self.get_location(location)
+            .map(|l| self.agents_at_location(location).len() as f32 / l.capacity.max(1) as f32)
+            .unwrap_or(0.0)
+    }
+
+    /// 尚未满员的位置
+    pub fn available_locations(&self) -> Vec<&Location> {
+        self.locations
+            .iter()
+            .filter(|l| self.occupancy(&l.id) < l.capacity)
+            .collect()
+    }
+
+    /// 目的地已满时的替代位置：离原目的地最近且有空位的位置
+    fn overflow_location(&self, requested: &LocationId, agent_id: &AgentId) -> Option<LocationId> {
+        self.locations
+            .iter()
+            .filter(|l| l.id != *requested && self.has_room_for(&l.id, agent_id))
+            .filter_map(|l| {
+                let path = shortest_path(&self.locations, requested, &l.id)?;
+                Some((l.id.clone(), path_length(&self.locations, &path)?))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _)| id)
+    }
+
+    /// 取走待处理的改道记录
+    pub fn take_redirects(&mut self) -> Vec<Redirect> {
+        std::mem::take(&mut self.redirects)
+    }
+
+    /// Agent 的显示名称（未知 Agent 返回“未知”）
+    pub fn agent_name(&self, id: &AgentId) -> &str {
+        self.agents.get(id).map_or("未知", |a| a.config.name.as_str())
+    }
+
+    /// 位置的显示名称（未知位置返回原始 ID）
+    pub fn location_name(&self, id: &LocationId) -> String {
+        self.get_location(id)
+            .map(|l| l.name.clone())
+            .unwrap_or_else(|_| id.0.clone())
+    }
+
+    /// 一组 Agent 共同所在的位置（不在同一处时为 None）
+    pub fn common_location(&self, agent_ids: &[AgentId]) -> Option<LocationId> {
+        let mut locations = agent_ids
+            .iter()
+            .filter_map(|id| self.agents.get(id).map(|a| &a.location));
+        let first = locations.next()?;
+        locations.all(|l| l == first).then(|| first.clone())
+    }
+
+    /// 他人可观察到的活动描述
+    pub fn describe_activity(&self, activity: &AgentActivity) -> String {
+        match activity {
+            AgentActivity::Studying { subject } => format!("在学习{subject}"),
+            AgentActivity::Teaching { subject } => format!("在讲{subject}课"),
+            AgentActivity::Socializing { with } => {
+                let names: Vec<&str> = with
+                    .iter()
+                    .filter_map(|id| self.agents.get(id).map(|a| a.config.name.as_str()))
+                    .collect();
+                if names.is_empty() {
+                    "在和同学聊天".to_string()
+                } else {
+                    format!("在和{}聊天", names.join("、"))
+                }
+            }
+            AgentActivity::Resting => "在休息".to_string(),
+            AgentActivity::Reflecting => "在独自思考".to_string(),
+            AgentActivity::Activity { name } => format!("在参加{name}"),
+            AgentActivity::Moving { to } => format!("正在前往{}", self.location_name(to)),
+            AgentActivity::Troubled { .. } => "看起来有心事".to_string(),
+        }
+    }
+
+    /// 构建 Agent 的感知输入
+    ///
+    /// 附近的人（活动、情绪、关系）、位置名称、此处发生过的事件与全局近期事件。
+    pub fn perceive(&self, agent_id: &AgentId) -> Result<Perception, WorldError> {
+        let agent = self.get_agent(agent_id)?;
+
+        let mut others = self.agents_at_location(&agent.location);
+        others.retain(|a| a.id != *agent_id);
+        others.sort_by(|a, b| a.config.name.cmp(&b.config.name));
+        let own_group = self.class_group_of(agent_id);
+
+        let nearby_agents: Vec<ObservedAgent> = others
+            .iter()
+            .map(|other| {
+                let mut mood = other.emotion.mood_label().to_string();
+                if other.emotion.stress > 0.7 {
+                    mood.push_str("，显得很紧张");
+                }
+                ObservedAgent {
+                    id: other.id.clone(),
+                    name: other.config.name.clone(),
+                    activity: self.describe_activity(&other.activity),
+                    mood,
+                    relationship: match (
+                        self.relationships.get(agent_id, &other.id).map(relationship_description),
+                        own_group.is_some_and(|g| g.members.contains(&other.id)),
+                    ) {
+                        (Some(description), true) => Some(format!("同班同学，{description}")),
+                        (None, true) => Some("同班同学".to_string()),
+                        (description, false) => description,
+                    },
+                }
+            })
+            .collect();
+
+        // 按活动归并：“小红、小明在学习数学”
+        let mut observable_activities: Vec<(String, Vec<&str>)> = Vec::new();
+        for observed in &nearby_agents {
+            match observable_activities
+                .iter_mut()
+                .find(|(activity, _)| *activity == observed.activity)
+            {
+                Some((_, names)) => names.push(&observed.name),
+                None => observable_activities
+                    .push((observed.activity.clone(), vec![observed.name.as_str()])),
+            }
+        }
+        let observable_activities = observable_activities
+            .into_iter()
+            .map(|(activity, names)| format!("{}{activity}", names.join("、")))
+            .collect();
+
+        let local: Vec<&SimulationEvent> = self
+            .event_log
+            .iter()
+            .rev()
+            .filter(|e| e.location.as_ref() == Some(&agent.location))
+            .take(3)
+            .collect();
+        // 上一步以来针对自己的干预排在最前，不会被之后的事件挤掉；上课、拥挤等日常事件不算新鲜事
+        let now = self.clock.current_time().tick;
+        let interventions = self
+            .event_log
+            .iter()
+            .rev()
+            .take_while(|e| e.timestamp.tick + 1 >= now)
+            .filter(|e| e.trigger == EventTrigger::UserIntervention && e.involved_agents.contains(agent_id));
+        let mut recent: Vec<&SimulationEvent> = Vec::new();
+        let latest = self.event_log.iter().rev().filter(|e| e.event_type != EventType::Routine).take(3);
+        for event in interventions.chain(latest) {
+            if !recent.iter().any(|e| e.id == event.id) {
+                recent.push(event);
+            }
+        }
+
+        let mut event_ids: Vec<EventId> = Vec::new();
+        for event in local.iter().chain(&recent) {
+            if !event_ids.contains(&event.id) {
+                event_ids.push(event.id.clone());
+            }
+        }
+        let local_events = local.iter().map(|e| e.narrative.clone()).collect();
+        let recent_events = recent.iter().map(|e| e.narrative.clone()).collect();
+
+        Ok(Perception {
+            location_name: self.location_name(&agent.location),
+            crowding: self.crowding(&agent.location),
+            nearby_agents,
+            observable_activities,
+            environment_description: self.describe_situation(agent_id),
+            recent_events,
+            local_events,
+            event_ids,
+        })
+    }
+
+    /// 当前是第几节课（非上课时间为 None）
+    pub fn current_period(&self) -> Option<u32> {
+        self.clock.current_period()
+    }
+
+    /// Agent 今天第几节要上的课：所选选修课优先，其次本班课程表
+    pub fn class_session(&self, agent_id: &AgentId, period: u32) -> Option<ClassSession> {
+        let day = self.clock.current_time().day_of_week;
+        let group = self.class_group_of(agent_id);
+        let classroom = |subject: &str, location: &Option<LocationId>| {
+            self.classroom(subject, location, group)
         };
 
-        period.and_then(|p| {
-            self.schedule
-                .iter()
-                .find(|c| c.day_of_week == time.day_of_week && c.period == p)
+        if let Some(elective) = self.electives.iter().find(|e| {
+            e.day_of_week == day && e.period == period && e.members.contains(agent_id)
+        }) {
+            return Some(ClassSession {
+                subject: elective.subject.clone(),
+                location: classroom(&elective.subject, &elective.location)?,
+                group: elective.name.clone(),
+            });
+        }
+
+        let group = group?;
+        let class = group.class_at(day, period)?;
+        Some(ClassSession {
+            subject: class.subject.clone(),
+            location: classroom(&class.subject, &class.location)?,
+            group: group.name.clone(),
         })
     }
 
+    /// 上课地点：课程表指定地点 → 学科教室 → 班级教室
+    fn classroom(
+        &self,
+        subject: &str,
+        location: &Option<LocationId>,
+        group: Option<&ClassGroup>,
+    ) -> Option<LocationId> {
+        location
+            .clone()
+            .or_else(|| self.get_subject(subject).map(|s| s.classroom.clone()))
+            .or_else(|| group.map(|g| g.homeroom.clone()))
+    }
+
+    /// Agent 当前正在上的课
+    pub fn current_class(&self, agent_id: &AgentId) -> Option<ClassSession> {
+        self.class_session(agent_id, self.current_period()?)
+    }
+
+    /// 按名称查找学科
+    pub fn get_subject(&self, name: &str) -> Option<&Subject> {
+        self.subjects.iter().find(|s| s.name == name)
+    }
+
+    /// 选择 Agent 自主学习的学科
+    ///
+    /// 优先当前正在学习的学科，其次当前课程，最后是最偏好的学科。
+    pub fn study_subject_for(&self, agent_id: &AgentId) -> Option<String> {
+        let agent = self.agents.get(agent_id)?;
+
+        if let AgentActivity::Studying { subject } = &agent.activity {
+            if self.get_subject(subject).is_some() {
+                return Some(subject.clone());
+            }
+        }
+
+        if let Some(class) = self.current_class(agent_id) {
+            if self.get_subject(&class.subject).is_some() {
+                return Some(class.subject.clone());
+            }
+        }
+
+        agent
+            .config
+            .career_aspiration
+            .subject_preferences
+            .iter()
+            .filter(|p| self.get_subject(&p.subject).is_some())
+            .max_by(|a, b| a.preference.total_cmp(&b.preference))
+            .map(|p| p.subject.clone())
+    }
+
+    /// 学习一次：按学科有效难度与学科偏好计算反馈，更新学业能力并记录表现
+    pub fn study(
+        &mut self,
+        agent_id: &AgentId,
+        subject_name: &str,
+        session: StudySession,
+    ) -> Result<AcademicFeedback, WorldError> {
+        let difficulty = self
+            .get_subject(subject_name)
+            .map(|s| s.effective_difficulty())
+            .ok_or_else(|| {
+                WorldError::StateError(format!("Unknown subject: {subject_name}"))
+            })?;
+        let timestamp = self.clock.current_time().clone();
+
+        let agent = self
+            .agents
+            .get_mut(agent_id)
+            .ok_or_else(|| WorldError::AgentNotFound(agent_id.clone()))?;
+
+        let preference = agent
+            .config
+            .career_aspiration
+            .subject_preferences
+            .iter()
+            .find(|p| p.subject == subject_name)
+            .map(|p| p.preference)
+            .unwrap_or(0.5);
+
+        let feedback =
+            calculate_academic_feedback(difficulty, agent.abilities.academic, preference);
+
+        let delta =
+            self.academics
+                .ability_delta(session, feedback.performance, agent.abilities.academic);
+        agent.abilities.academic = (agent.abilities.academic + delta).clamp(0.0, 1.0);
+
+        self.academics.record(SubjectPerformance {
+            agent_id: agent_id.clone(),
+            subject: subject_name.to_string(),
+            timestamp,
+            session,
+            difficulty,
+            performance: feedback.performance,
+            satisfaction: feedback.satisfaction,
+            academic_after: agent.abilities.academic,
+        });
+
+        Ok(feedback)
+    }
+
     /// 应用状态变更 — 单一入口
-    /// 验证合法性 → 执行状态更新 → 记录事件日志
+    /// 验证合法性 → 按每步上限截断 → 执行状态更新 → 记入审计日志
     pub fn apply_state_changes(
         &mut self,
         changes: &[StateChange],
+        source_event: Option<&EventId>,
     ) -> Result<Vec<String>, WorldError> {
         let mut warnings = Vec::new();
 
         for change in changes {
-            match self.apply_single_change(change) {
-                Ok(()) => {
-                    debug!(target = %change.target, "State change applied");
+            let (agents, result) = match TypedChange::parse(change) {
+                Ok(typed) => (self.change_agents(&typed.target), self.apply_single_change(change, typed)),
+                Err(e) => (Vec::new(), Err(e.into())),
+            };
+            let (old_value, new_value, outcome, reason) = match result {
+                Ok(applied) => {
+                    debug!(target = %change.target, clamped = applied.reason.is_some(), "State change applied");
+                    let outcome = match applied.reason {
+                        Some(_) => ChangeOutcome::Clamped,
+                        None => ChangeOutcome::Applied,
+                    };
+                    (applied.old_value, applied.new_value, outcome, applied.reason)
                 }
                 Err(e) => {
                     warn!(target = %change.target, error = %e, "State change failed");
                     warnings.push(format!("Failed to apply change to {}: {e}", change.target));
+                    (serde_json::Value::Null, serde_json::Value::Null, ChangeOutcome::Rejected, Some(e.to_string()))
                 }
+            };
+            if self.change_audit.len() >= self.audit_capacity {
+                self.change_audit.pop_front();
             }
+            self.change_audit.push_back(ChangeAudit {
+                timestamp: self.clock.current_time().clone(),
+                source_event: source_event.cloned(),
+                target: change.target.clone(),
+                change_type: change.change_type.clone(),
+                requested: change.value.clone(),
+                agents,
+                old_value,
+                new_value,
+                outcome,
+                reason,
+            });
         }
 
         Ok(warnings)
     }
 
-    fn apply_single_change(&mut self, change: &StateChange) -> Result<(), WorldError> {
-        let parts: Vec<&str> = change.target.split('.').collect();
-        if parts.len() < 2 {
-            return Err(WorldError::InvalidStateChange(format!(
-                "Invalid target format: {}",
-                change.target
-            )));
+    /// 某个 Agent 相关的变更审计记录（按时间顺序），跳过前 `offset` 条，最多取 `limit` 条
+    pub fn audit_for(&self, agent_id: &AgentId, offset: usize, limit: usize) -> Vec<&ChangeAudit> {
+        self.change_audit
+            .iter()
+            .filter(|a| a.agents.contains(agent_id))
+            .skip(offset)
+            .take(limit)
+            .collect()
+    }
+
+    /// 按 ID 查找事件
+    pub fn event(&self, event_id: &EventId) -> Option<&SimulationEvent> {
+        self.event_log.iter().rev().find(|e| e.id == *event_id)
+    }
+
+    /// 回溯某个 Agent 的状态变化：从相关变更与事件出发，沿因果边找出背后的事件与干预
+    pub fn explain(&self, agent_id: &AgentId, query: &TraceQuery) -> CausalTrace {
+        let since = query.since_tick.unwrap_or(0);
+        let changes: Vec<ChangeAudit> = self
+            .audit_for(agent_id, 0, usize::MAX)
+            .into_iter()
+            .filter(|a| a.timestamp.tick >= since && a.outcome != ChangeOutcome::Rejected)
+            .filter(|a| match &query.field {
+                Some(field) => ChangeTarget::parse(&a.target).is_ok_and(|t| t.spec().path == field),
+                None => true,
+            })
+            .cloned()
+            .collect();
+
+        let mut roots: Vec<EventId> = Vec::new();
+        let sources = changes.iter().filter_map(|a| a.source_event.clone());
+        let involved = self
+            .event_log
+            .iter()
+            .filter(|e| query.field.is_none() && e.timestamp.tick >= since && e.involved_agents.contains(agent_id))
+            .map(|e| e.id.clone());
+        for id in sources.chain(involved) {
+            if !roots.contains(&id) {
+                roots.push(id);
+            }
         }
+        // 只保留最近的起点
+        roots.sort_by_key(|id| self.event(id).map(|e| e.timestamp.tick));
+        let roots = roots.split_off(roots.len().saturating_sub(query.limit));
+        let changes = changes
+            .into_iter()
+            .filter(|a| a.source_event.as_ref().is_some_and(|id| roots.contains(id)))
+            .collect();
 
-        match parts[0] {
-            target if target.starts_with("agent:") => {
-                let agent_key = &target[6..];
-                // Support both name and UUID lookup for robustness
-                let agent = if let Some(a) = self.agents.values_mut().find(|a| a.config.name == agent_key) {
-                    a
-                } else if let Ok(uuid) = uuid::Uuid::parse_str(agent_key) {
-                    self.agents.get_mut(&AgentId(uuid)).ok_or_else(|| {
-                        WorldError::InvalidStateChange(format!("Agent not found: {agent_key}"))
-                    })?
-                } else {
-                    return Err(WorldError::InvalidStateChange(format!("Agent not found: {agent_key}")));
-                };
+        causal_trace(&self.event_log, roots, changes, query.max_depth)
+    }
 
-                match parts[1] {
-                    "emotion" if parts.len() > 2 => match parts[2] {
-                        "valence" => {
-                            Self::apply_numeric_change(&mut agent.emotion.valence, change, -1.0, 1.0);
-                        }
-                        "arousal" => {
-                            Self::apply_numeric_change(&mut agent.emotion.arousal, change, 0.0, 1.0);
-                        }
-                        "stress" => {
-                            Self::apply_numeric_change(&mut agent.emotion.stress, change, 0.0, 1.0);
-                        }
-                        _ => return Err(WorldError::InvalidStateChange(format!("Unknown emotion field: {}", parts[2]))),
-                    },
-                    "location" => {
-                        if let Some(loc_str) = change.value.as_str() {
-                            agent.location = LocationId(loc_str.to_string());
-                        }
+    /// 检查世界状态不变量，返回违反项
+    ///
+    /// 位置必须存在，情绪、能力与关系取值必须有限且在范围内，社团人数不超过上限。
+    pub fn check_invariants(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut check = |owner: &str, name: &str, value: f32, min: f32, max: f32| {
+            if !value.is_finite() || value < min || value > max {
+                problems.push(format!("{owner}: {name} = {value} outside [{min}, {max}]"));
+            }
+        };
+
+        for agent in self.agents.values() {
+            let owner = &agent.config.name;
+            check(owner, "emotion.valence", agent.emotion.valence, -1.0, 1.0);
+            check(owner, "emotion.arousal", agent.emotion.arousal, 0.0, 1.0);
+            check(owner, "emotion.stress", agent.emotion.stress, 0.0, 1.0);
+            check(owner, "ability.academic", agent.abilities.academic, 0.0, 1.0);
+            check(owner, "ability.social", agent.abilities.social, 0.0, 1.0);
+            check(owner, "ability.resilience", agent.abilities.resilience, 0.0, 1.0);
+            check(owner, "ability.creativity", agent.abilities.creativity, 0.0, 1.0);
+            check(owner, "career.clarity", agent.config.career_aspiration.clarity, 0.0, 1.0);
+        }
+        for rel in self.relationships.all_relationships() {
+            let owner = format!("{} - {}", self.agent_name(&rel.agent_a), self.agent_name(&rel.agent_b));
+            check(&owner, "closeness", rel.closeness, -1.0, 1.0);
+            check(&owner, "trust", rel.trust, 0.0, 1.0);
+        }
+
+        for agent in self.agents.values() {
+            if self.get_location(&agent.location).is_err() {
+                problems.push(format!("{}: unknown location {}", agent.config.name, agent.location.0));
+            }
+        }
+        for club in &self.clubs {
+            if club.members.len() > club.max_members {
+                problems.push(format!(
+                    "club {} has {} members, max {}",
+                    club.name,
+                    club.members.len(),
+                    club.max_members
+                ));
+            }
+        }
+        problems
+    }
+
+    /// 变更目标涉及的 Agent（找不到的略过）
+    fn change_agents(&self, target: &ChangeTarget) -> Vec<AgentId> {
+        let keys = match target {
+            ChangeTarget::Agent { agent, .. } => vec![agent],
+            ChangeTarget::Relationship { agent_a, agent_b, .. } => vec![agent_a, agent_b],
+        };
+        keys.into_iter().filter_map(|key| self.resolve_agent(key)).collect()
+    }
+
+    fn apply_single_change(
+        &mut self,
+        change: &StateChange,
+        TypedChange { target, op }: TypedChange,
+    ) -> Result<AppliedChange, WorldError> {
+        let resolve = |key: &str| {
+            self.resolve_agent(key).ok_or_else(|| ChangeError::UnknownAgent {
+                target: change.target.clone(),
+                agent: key.to_string(),
+            })
+        };
+        let path = target.spec().path;
+        let (min, max) = match target.spec().kind {
+            ValueKind::Number { min, max } => (min, max),
+            _ => (0.0, 0.0),
+        };
+        let tick = self.clock.current_time().tick;
+
+        match target {
+            ChangeTarget::Agent { agent, field } => {
+                let agent_id = resolve(&agent)?;
+                match (field, op) {
+                    (AgentField::Location, ChangeOp::SetText(location)) => {
+                        let old_value = serde_json::json!(self.agents[&agent_id].location.0);
+                        // 沿校园图前往，到达后保持原有活动
+                        let on_arrival = match self.journeys.get(&agent_id) {
+                            Some(journey) => journey.on_arrival.clone(),
+                            None => self.agents[&agent_id].activity.clone(),
+                        };
+                        // 记录实际分配的目的地（满员时会改道，行程可能尚未走完）
+                        let assigned = self.move_agent(&agent_id, &LocationId(location.clone()), on_arrival)?;
+                        let reason = (assigned.0 != location)
+                            .then(|| format!("{location} is full, redirected to {assigned}"));
+                        Ok(AppliedChange {
+                            old_value,
+                            new_value: serde_json::json!(assigned.0),
+                            reason,
+                        })
                     }
-                    _ => {
-                        return Err(WorldError::InvalidStateChange(format!(
-                            "Unknown agent field: {}",
-                            parts[1]
-                        )));
+                    (AgentField::Clubs, ChangeOp::Append(club)) => {
+                        let clubs = |world: &Self| {
+                            serde_json::json!(world.clubs_of(&agent_id).iter().map(|c| &c.name).collect::<Vec<_>>())
+                        };
+                        let old_value = clubs(self);
+                        self.join_club(&agent_id, &club)?;
+                        Ok(AppliedChange::new(old_value, clubs(self)))
                     }
-                }
-            }
-            target if target.starts_with("relationship[") => {
-                // relationship[小明,小红].closeness
-                if let (Some(start), Some(end)) = (target.find('['), target.find(']')) {
-                    let agents_str = &target[start + 1..end];
-                    let agent_names: Vec<&str> = agents_str.split(',').collect();
-                    if agent_names.len() == 2 {
-                        let time = self.clock.current_time().clone();
-                        if let Some(delta) = change.value.as_f64() {
-                            // Find agent IDs by name
-                            let id_a = self.agents.values().find(|a| a.config.name == agent_names[0]).map(|a| a.id.clone());
-                            let id_b = self.agents.values().find(|a| a.config.name == agent_names[1]).map(|a| a.id.clone());
-                            if let (Some(id_a), Some(id_b)) = (id_a, id_b) {
-                                match parts.get(1) {
-                                    Some(&"closeness") => {
-                                        self.relationships.update_closeness(&id_a, &id_b, delta as f32, &time);
-                                    }
-                                    Some(&"trust") => {
-                                        self.relationships.update_trust(&id_a, &id_b, delta as f32, &time);
+                    (field, op) => {
+                        let agent = self.agents.get_mut(&agent_id).expect("resolved agent exists");
+                        match (field, op) {
+                            (AgentField::Activity, ChangeOp::SetActivity(activity)) => {
+                                let old_value = serde_json::json!(format!("{:?}", agent.activity));
+                                agent.activity = activity;
+                                Ok(AppliedChange::new(old_value, serde_json::json!(format!("{:?}", agent.activity))))
+                            }
+                            (AgentField::CurrentThought, op) => {
+                                let old_value = serde_json::json!(agent.current_thought);
+                                match op {
+                                    ChangeOp::SetText(text) => agent.current_thought = Some(text),
+                                    ChangeOp::Append(text) => {
+                                        agent.current_thought = Some(match agent.current_thought.take() {
+                                            Some(thought) => format!("{thought} {text}"),
+                                            None => text,
+                                        });
                                     }
                                     _ => {}
                                 }
+                                Ok(AppliedChange::new(old_value, serde_json::json!(agent.current_thought)))
+                            }
+                            (field, op) => {
+                                let value = match field {
+                                    AgentField::Valence => &mut agent.emotion.valence,
+                                    AgentField::Arousal => &mut agent.emotion.arousal,
+                                    AgentField::Stress => &mut agent.emotion.stress,
+                                    AgentField::Ability(ability) => ability.value_mut(&mut agent.abilities),
+                                    AgentField::CareerClarity => &mut agent.config.career_aspiration.clarity,
+                                    _ => return Ok(AppliedChange::default()),
+                                };
+                                let key = format!("{}:{path}", agent_id.0);
+                                let (delta, reason) = Self::bounded_delta(
+                                    &mut self.change_budget,
+                                    &self.change_limits,
+                                    (tick, key, path),
+                                    *value,
+                                    &op,
+                                    (min, max),
+                                )
+                                .map_err(|limit| ChangeError::LimitExceeded {
+                                    target: change.target.clone(),
+                                    limit,
+                                })?;
+                                let old_value = *value;
+                                *value += delta;
+                                Ok(AppliedChange {
+                                    old_value: serde_json::json!(old_value),
+                                    new_value: serde_json::json!(*value),
+                                    reason,
+                                })
                             }
                         }
                     }
                 }
             }
-            _ => {
-                return Err(WorldError::InvalidStateChange(format!(
-                    "Unknown target prefix: {}",
-                    parts[0]
-                )));
+            ChangeTarget::Relationship { agent_a, agent_b, field } => {
+                let (id_a, id_b) = (resolve(&agent_a)?, resolve(&agent_b)?);
+                let time = self.clock.current_time().clone();
+                let current = self.relationships.get(&id_a, &id_b).cloned();
+                match (field, op) {
+                    (RelationshipField::Tags, ChangeOp::Append(tag)) => {
+                        if RelationshipLabel::from_tag(&tag).is_some() || tag == TEACHER_STUDENT_TAG {
+                            return Err(ChangeError::InvalidValue {
+                                target: change.target.clone(),
+                                expected: "a tag other than the derived labels and 师生".to_string(),
+                                value: change.value.clone(),
+                            }
+                            .into());
+                        }
+                        let old_value = serde_json::json!(current.map(|r| r.tags).unwrap_or_default());
+                        self.relationships.add_tag(&id_a, &id_b, &tag);
+                        let new_value = serde_json::json!(self.relationships.get(&id_a, &id_b).map(|r| &r.tags));
+                        Ok(AppliedChange::new(old_value, new_value))
+                    }
+                    (RelationshipField::Tags, _) => Ok(AppliedChange::default()),
+                    (field, op) => {
+                        let old_value = match field {
+                            RelationshipField::Closeness => current.map_or(0.0, |r| r.closeness),
+                            _ => current.map_or(0.5, |r| r.trust),
+                        };
+                        let mut pair = [id_a.0, id_b.0];
+                        pair.sort();
+                        let key = format!("{}-{}:{path}", pair[0], pair[1]);
+                        let (delta, reason) = Self::bounded_delta(
+                            &mut self.change_budget,
+                            &self.change_limits,
+                            (tick, key, path),
+                            old_value,
+                            &op,
+                            (min, max),
+                        )
+                        .map_err(|limit| ChangeError::LimitExceeded {
+                            target: change.target.clone(),
+                            limit,
+                        })?;
+                        if field == RelationshipField::Closeness {
+                            self.relationships.update_closeness(&id_a, &id_b, delta, &time);
+                        } else {
+                            self.relationships.update_trust(&id_a, &id_b, delta, &time);
+                        }
+                        Ok(AppliedChange {
+                            old_value: serde_json::json!(old_value),
+                            new_value: serde_json::json!(old_value + delta),
+                            reason,
+                        })
+                    }
+                }
             }
         }
-
-        Ok(())
     }
 
-    fn apply_numeric_change(field: &mut f32, change: &StateChange, min: f32, max: f32) {
-        if let Some(value) = change.value.as_f64() {
-            match change.change_type {
-                ChangeType::Delta => *field = (*field + value as f32).clamp(min, max),
-                ChangeType::Set => *field = (value as f32).clamp(min, max),
-                ChangeType::Append => {} // Not applicable for numeric
+    /// 数值变更折算成实际增量：先受每步上限约束，再夹到取值范围内
+    ///
+    /// 返回增量与被截断的原因；本步上限已用完时返回该上限。
+    fn bounded_delta(
+        budget: &mut ChangeBudget,
+        limits: &ChangeLimits,
+        (tick, key, path): (u64, String, &str),
+        current: f32,
+        op: &ChangeOp,
+        (min, max): (f32, f32),
+    ) -> Result<(f32, Option<String>), f32> {
+        let requested = Self::numeric_delta(current, op);
+        let mut delta = requested;
+        let mut reason = None;
+        if let Some(limit) = limits.limit(path) {
+            delta = budget.take(tick, key, limit, requested);
+            if delta == 0.0 && requested != 0.0 {
+                return Err(limit);
+            }
+            if (delta - requested).abs() > f32::EPSILON {
+                reason = Some(format!("per-tick limit {limit} on {path}: requested {requested:+.3}, applied {delta:+.3}"));
             }
         }
+        let bounded = (current + delta).clamp(min, max) - current;
+        if reason.is_none() && (bounded - delta).abs() > f32::EPSILON {
+            reason = Some(format!("{path} clamped to [{min}, {max}]"));
+        }
+        Ok((bounded, reason))
+    }
+
+    /// 按名字或 UUID 查找 Agent
+    pub fn resolve_agent(&self, key: &str) -> Option<AgentId> {
+        if let Some(agent) = self.agents.values().find(|a| a.config.name == key) {
+            return Some(agent.id.clone());
+        }
+        let id = AgentId(uuid::Uuid::parse_str(key).ok()?);
+        self.agents.contains_key(&id).then_some(id)
+    }
+
+    /// Agent 数值字段的当前值（非数值字段为 None）
+    pub fn agent_value(&self, agent_id: &AgentId, field: AgentField) -> Option<f32> {
+        let agent = self.agents.get(agent_id)?;
+        Some(match field {
+            AgentField::Valence => agent.emotion.valence,
+            AgentField::Arousal => agent.emotion.arousal,
+            AgentField::Stress => agent.emotion.stress,
+            AgentField::Ability(ability) => ability.value(&agent.abilities),
+            AgentField::CareerClarity => agent.config.career_aspiration.clarity,
+            _ => return None,
+        })
+    }
+
+    /// 数值变更折算成相对当前值的增量
+    fn numeric_delta(current: f32, op: &ChangeOp) -> f32 {
+        match op {
+            ChangeOp::Delta(delta) => *delta,
+            ChangeOp::SetNumber(value) => value - current,
+            _ => 0.0,
+        }
     }
 
-    /// 处理时间事件：根据时间段自动移动 Agent 到对应位置
-    pub fn process_time_events(&mut self, events: &[TimeEvent]) {
+    /// 处理时间事件：Agent 沿校园图前往对应位置，并推进所有行程
+    ///
+    /// 返回本步途中发生的偶遇。
+    pub fn process_time_events(&mut self, events: &[TimeEvent]) -> Vec<Encounter> {
         let mut rng = rand::thread_rng();
+        let (staff_ids, agent_ids): (Vec<AgentId>, Vec<AgentId>) =
+            self.agents.keys().cloned().partition(|id| self.is_staff(id));
+        let mut plans: Vec<(AgentId, LocationId, AgentActivity)> = Vec::new();
 
         for event in events {
+            for id in &staff_ids {
+                if let Some(plan) = self.staff_plan(id, event) {
+                    plans.push(plan);
+                }
+            }
             match event {
+                TimeEvent::ClassStart { .. } if self.exam_today().is_some() => {
+                    let exam = self.exam_today().map(|e| e.name.clone()).unwrap_or_default();
+                    for id in &agent_ids {
+                        let Some(group) = self.class_group_of(id) else {
+                            continue;
+                        };
+                        plans.push((
+                            id.clone(),
+                            group.homeroom.clone(),
+                            AgentActivity::Activity { name: exam.clone() },
+                        ));
+                    }
+                    debug!(exam = %exam, "Exam day: students sit the exam in their homerooms");
+                }
                 TimeEvent::ClassStart { period } => {
-                    let time = self.clock.current_time();
-                    let subject = self
-                        .schedule
-                        .iter()
-                        .find(|c| c.day_of_week == time.day_of_week && c.period == *period)
-                        .map(|c| c.subject.clone());
-
-                    if let Some(subject_name) = subject {
-                        let classroom = self
-                            .subjects
-                            .iter()
-                            .find(|s| s.name == subject_name)
-                            .map(|s| s.classroom.clone())
-                            .unwrap_or(LocationId("classroom_math".to_string()));
-
-                        let subject_for_activity = subject_name.clone();
-                        debug!(subject = %subject_name, location = %classroom.0, "Moving agents to class");
-                        for agent in self.agents.values_mut() {
-                            agent.location = classroom.clone();
-                            agent.activity = AgentActivity::Studying {
-                                subject: subject_for_activity.clone(),
-                            };
-                        }
+                    self.record_lessons(*period);
+                    for id in &agent_ids {
+                        let Some(class) = self.class_session(id, *period) else {
+                            continue;
+                        };
+                        debug!(agent = %id, subject = %class.subject, location = %class.location, group = %class.group, "Moving agent to class");
+                        plans.push((
+                            id.clone(),
+                            class.location,
+                            AgentActivity::Studying {
+                                subject: class.subject,
+                            },
+                        ));
                     }
                 }
                 TimeEvent::Break => {
-                    let break_locations = [
-                        LocationId("rest_area".to_string()),
-                        LocationId("hallway".to_string()),
-                        LocationId("playground".to_string()),
-                    ];
-                    for agent in self.agents.values_mut() {
-                        let loc = break_locations.choose(&mut rng).unwrap().clone();
-                        agent.location = loc;
-                        agent.activity = AgentActivity::Resting;
+                    let mut planned = Vec::new();
+                    for id in &agent_ids {
+                        let Some(loc) =
+                            self.environment.choose_gathering(&self.routines.break_locations, &planned, &mut rng)
+                        else {
+                            continue;
+                        };
+                        planned.push(loc.clone());
+                        plans.push((id.clone(), loc, AgentActivity::Resting));
                     }
                     debug!("Break: agents moved to rest areas");
                 }
                 TimeEvent::LunchBreak | TimeEvent::Dinner => {
-                    for agent in self.agents.values_mut() {
-                        agent.location = LocationId("cafeteria".to_string());
-                        agent.activity = AgentActivity::Resting;
+                    for id in &agent_ids {
+                        plans.push((
+                            id.clone(),
+                            self.routines.meal_location.clone(),
+                            AgentActivity::Resting,
+                        ));
                     }
                     debug!("Meal time: agents moved to cafeteria");
                 }
                 TimeEvent::FreeTime => {
-                    let free_locations = [
-                        LocationId("library".to_string()),
-                        LocationId("playground".to_string()),
-                        LocationId("club_room".to_string()),
-                        LocationId("rest_area".to_string()),
-                        LocationId("study_room".to_string()),
-                    ];
-                    for agent in self.agents.values_mut() {
-                        let loc = free_locations.choose(&mut rng).unwrap().clone();
-                        agent.location = loc;
-                        agent.activity = AgentActivity::Activity {
-                            name: "课外活动".to_string(),
+                    let mut planned = Vec::new();
+                    for id in &agent_ids {
+                        // 社团活动日的成员前往社团活动地点
+                        if let Some(club) = self.club_meeting_today(id) {
+                            plans.push((
+                                id.clone(),
+                                club.location.clone(),
+                                AgentActivity::Activity {
+                                    name: club.name.clone(),
+                                },
+                            ));
+                            continue;
+                        }
+                        let Some(loc) =
+                            self.environment.choose_gathering(&self.routines.free_time_locations, &planned, &mut rng)
+                        else {
+                            continue;
                         };
+                        planned.push(loc.clone());
+                        plans.push((
+                            id.clone(),
+                            loc,
+                            AgentActivity::Activity {
+                                name: "课外活动".to_string(),
+                            },
+                        ));
                     }
-                    debug!("Free time: agents dispersed to various locations");
+                    debug!("Free time: club members to club rooms, others dispersed");
                 }
                 TimeEvent::EveningStudy => {
-                    let study_locations = [
-                        LocationId("study_room".to_string()),
-                        LocationId("library".to_string()),
-                        LocationId("classroom_math".to_string()),
-                    ];
-                    for agent in self.agents.values_mut() {
-                        let loc = study_locations.choose(&mut rng).unwrap().clone();
-                        agent.location = loc;
-                        agent.activity = AgentActivity::Studying {
-                            subject: "自习".to_string(),
+                    // 晚自习在班级教室；不属于任何班级的去作息中的自习地点
+                    for id in &agent_ids {
+                        let loc = match self.class_group_of(id) {
+                            Some(group) => group.homeroom.clone(),
+                            None => match self.routines.study_locations.choose(&mut rng) {
+                                Some(location) => location.clone(),
+                                None => continue,
+                            },
                         };
+                        plans.push((
+                            id.clone(),
+                            loc,
+                            AgentActivity::Studying {
+                                subject: "自习".to_string(),
+                            },
+                        ));
                     }
                     debug!("Evening study: agents moved to study areas");
                 }
                 TimeEvent::Bedtime | TimeEvent::NewDay => {
-                    for agent in self.agents.values_mut() {
-                        agent.location = LocationId("dormitory".to_string());
-                        agent.activity = AgentActivity::Resting;
+                    for id in &agent_ids {
+                        plans.push((
+                            id.clone(),
+                            self.routines.dormitory.clone(),
+                            AgentActivity::Resting,
+                        ));
                     }
                     debug!("Bedtime: agents moved to dormitory");
                 }
-                TimeEvent::Weekend => {
-                    let weekend_locations = [
-                        LocationId("library".to_string()),
-                        LocationId("playground".to_string()),
-                        LocationId("dormitory".to_string()),
-                        LocationId("rest_area".to_string()),
-                        LocationId("club_room".to_string()),
-                    ];
-                    for agent in self.agents.values_mut() {
-                        let loc = weekend_locations.choose(&mut rng).unwrap().clone();
-                        agent.location = loc;
-                        agent.activity = AgentActivity::Resting;
+                TimeEvent::DayOff => {
+                    let mut planned = Vec::new();
+                    for id in &agent_ids {
+                        let Some(loc) =
+                            self.environment.choose_gathering(&self.routines.day_off_locations, &planned, &mut rng)
+                        else {
+                            continue;
+                        };
+                        planned.push(loc.clone());
+                        plans.push((id.clone(), loc, AgentActivity::Resting));
                     }
-                    debug!("Weekend: agents dispersed freely");
+                    debug!("Day off: agents dispersed freely");
                 }
                 _ => {}
             }
         }
+
+        for (agent_id, destination, activity) in plans {
+            if let Err(e) = self.move_agent(&agent_id, &destination, activity) {
+                warn!(agent = %agent_id, error = %e, "Movement failed");
+            }
+        }
+
+        self.advance_movements()
+    }
+
+    /// 教职工的日程：教师去任课班级上课、班主任晚自习看班、用餐去食堂，其余时间在办公室
+    fn staff_plan(&self, agent_id: &AgentId, event: &TimeEvent) -> Option<(AgentId, LocationId, AgentActivity)> {
+        let agent = self.agents.get(agent_id)?;
+        let office = self.staff_room.clone().unwrap_or_else(|| agent.location.clone());
+        let working = AgentActivity::Activity {
+            name: "办公".to_string(),
+        };
+
+        let head_of = self.class_groups.iter().find(|g| g.head_teacher.as_ref() == Some(agent_id));
+
+        let (location, activity) = match event {
+            TimeEvent::ClassStart { .. } if self.exam_today().is_some() => match head_of {
+                Some(group) => (
+                    group.homeroom.clone(),
+                    AgentActivity::Activity {
+                        name: "监考".to_string(),
+                    },
+                ),
+                None => (office, working),
+            },
+            TimeEvent::ClassStart { period } => match self.lesson_for(agent_id, *period) {
+                Some(class) => (
+                    class.location,
+                    AgentActivity::Teaching {
+                        subject: class.subject,
+                    },
+                ),
+                None => (office, working),
+            },
+            TimeEvent::EveningStudy => match head_of {
+                Some(group) => (
+                    group.homeroom.clone(),
+                    AgentActivity::Activity {
+                        name: "晚自习看班".to_string(),
+                    },
+                ),
+                None => (office, working),
+            },
+            TimeEvent::LunchBreak | TimeEvent::Dinner => {
+                (self.routines.meal_location.clone(), AgentActivity::Resting)
+            }
+            TimeEvent::Break | TimeEvent::FreeTime | TimeEvent::DayOff => (office, working),
+            TimeEvent::Bedtime | TimeEvent::NewDay => (office, AgentActivity::Resting),
+            _ => return None,
+        };
+        Some((agent_id.clone(), location, activity))
+    }
+
+    /// 教师第几节要上的课：任课班级此时排的是其所教学科
+    pub fn lesson_for(&self, teacher_id: &AgentId, period: u32) -> Option<ClassSession> {
+        let role = &self.agents.get(teacher_id)?.config.role;
+        let day = self.clock.current_time().day_of_week;
+        self.class_groups
+            .iter()
+            .filter(|g| g.teachers.contains(teacher_id))
+            .find_map(|group| {
+                let class = group.class_at(day, period).filter(|c| role.teaches(&c.subject))?;
+                Some(ClassSession {
+                    subject: class.subject.clone(),
+                    location: self.classroom(&class.subject, &class.location, Some(group))?,
+                    group: group.name.clone(),
+                })
+            })
+    }
+
+    /// 上课时建立师生关系：任课教师与本节课上的本班学生
+    fn record_lessons(&mut self, period: u32) {
+        let mut pairs = Vec::new();
+        for teacher_id in self.agents.keys().filter(|id| self.is_staff(id)) {
+            let Some(lesson) = self.lesson_for(teacher_id, period) else {
+                continue;
+            };
+            let Some(group) = self.class_groups.iter().find(|g| g.name == lesson.group) else {
+                continue;
+            };
+            for student in &group.members {
+                if self.class_session(student, period).as_ref() == Some(&lesson) {
+                    pairs.push((teacher_id.clone(), student.clone()));
+                }
+            }
+        }
+
+        let time = self.clock.current_time().clone();
+        for (teacher, student) in pairs {
+            self.relationships.add_tag(&teacher, &student, TEACHER_STUDENT_TAG);
+            self.relationships.get_or_create(&teacher, &student).last_interaction = Some(time.clone());
+        }
+    }
+
+    /// 让 Agent 出发前往目的地，到达后切换到指定活动
+    ///
+    /// 目的地已满时改道至最近的有空位的位置（记入 `redirects`）；
+    /// 已在目的地时直接切换活动；行程由 `advance_movements` 推进。返回实际分配的目的地。
+    pub fn move_agent(
+        &mut self,
+        agent_id: &AgentId,
+        destination: &LocationId,
+        on_arrival: AgentActivity,
+    ) -> Result<LocationId, WorldError> {
+        self.get_location(destination)?;
+        let destination = if self.has_room_for(destination, agent_id) {
+            destination.clone()
+        } else {
+            let assigned = self
+                .overflow_location(destination, agent_id)
+                .ok_or_else(|| WorldError::CapacityExceeded(destination.clone()))?;
+            debug!(agent = %agent_id, requested = %destination, assigned = %assigned, "Location full, redirecting");
+            self.redirects.push(Redirect {
+                agent_id: agent_id.clone(),
+                requested: destination.clone(),
+                assigned: assigned.clone(),
+            });
+            assigned
+        };
+        let agent = self
+            .agents
+            .get_mut(agent_id)
+            .ok_or_else(|| WorldError::AgentNotFound(agent_id.clone()))?;
+
+        if agent.location == destination {
+            self.journeys.remove(agent_id);
+            agent.activity = on_arrival;
+            return Ok(destination);
+        }
+
+        let journey = self
+            .movement
+            .plan(&self.locations, &agent.location, &destination, on_arrival)
+            .ok_or_else(|| {
+                WorldError::StateError(format!(
+                    "No path from {} to {destination}",
+                    agent.location
+                ))
+            })?;
+        agent.activity = AgentActivity::Moving {
+            to: destination.clone(),
+        };
+        self.journeys.insert(agent_id.clone(), journey);
+        Ok(destination)
+    }
+
+    /// 推进所有行程一个仿真步，记录本步移动路径，返回途中偶遇
+    pub fn advance_movements(&mut self) -> Vec<Encounter> {
+        let step_minutes = self.clock.step_hours() * 60;
+        let mut passes = Vec::new();
+        let mut arrived = Vec::new();
+        self.movements.clear();
+
+        for (agent_id, journey) in self.journeys.iter_mut() {
+            let Some(agent) = self.agents.get_mut(agent_id) else {
+                arrived.push(agent_id.clone());
+                continue;
+            };
+
+            let before = journey.elapsed_minutes;
+            journey.elapsed_minutes = (before + step_minutes).min(journey.total_minutes());
+
+            let mut path = vec![journey.location_at(before).clone()];
+            let route = (journey.path[0].clone(), journey.destination().clone());
+            let last = journey.path.len() - 1;
+            for (i, (location, &minute)) in
+                journey.path.iter().zip(&journey.arrival_minutes).enumerate()
+            {
+                if minute <= before || minute > journey.elapsed_minutes {
+                    continue;
+                }
+                path.push(location.clone());
+                if i != last {
+                    passes.push(Pass {
+                        agent_id: agent_id.clone(),
+                        location: location.clone(),
+                        minute: minute - before,
+                        route: route.clone(),
+                    });
+                }
+            }
+
+            agent.location = journey.current_location().clone();
+            if journey.is_arrived() {
+                agent.activity = journey.on_arrival.clone();
+                arrived.push(agent_id.clone());
+            }
+
+            self.movements.push(AgentMovement {
+                agent_id: agent_id.clone(),
+                path,
+                travel_minutes: journey.elapsed_minutes - before,
+                arrived: journey.is_arrived(),
+            });
+        }
+
+        for agent_id in arrived {
+            self.journeys.remove(&agent_id);
+        }
+
+        self.movement.encounters(&passes, self.environment.social_factor())
     }
 
     /// 生成世界状态快照
@@ -353,6 +1694,7 @@ impl WorldState {
                 .cloned()
                 .collect(),
             active_events: Vec::new(),
+            movements: self.movements.clone(),
         }
     }
 
@@ -388,8 +1730,45 @@ impl WorldState {
             desc.push_str("周围没有其他人。");
         }
 
-        if let Some(class) = self.current_class() {
-            desc.push_str(&format!("当前课程: {}。", class.subject));
+        match self.exam_today() {
+            Some(exam) => desc.push_str(&format!("今天是{}。", exam.name)),
+            None => {
+                if let Some(class) = self.current_class(agent_id) {
+                    desc.push_str(&format!("当前课程: {}（{}）。", class.subject, class.group));
+                }
+            }
+        }
+        let names = |ids: &[AgentId]| -> Vec<String> {
+            ids.iter()
+                .filter(|id| *id != agent_id)
+                .filter_map(|id| self.agents.get(id))
+                .map(|a| a.config.name.clone())
+                .collect()
+        };
+        let best_friends = names(&self.relationships.labeled(agent_id, RelationshipLabel::BestFriend));
+        if !best_friends.is_empty() {
+            desc.push_str(&format!("你的挚友: {}。", best_friends.join("、")));
+        }
+        for clique in self.relationships.cliques_of(agent_id) {
+            desc.push_str(&format!("你和{}是一个小圈子。", names(clique).join("、")));
+        }
+        let rivals = names(&self.relationships.labeled(agent_id, RelationshipLabel::Rival));
+        if !rivals.is_empty() {
+            desc.push_str(&format!("你暗中较劲的对手: {}。", rivals.join("、")));
+        }
+        if let Some(record) = self.gradebook.latest(agent_id) {
+            desc.push_str(&format!(
+                "你在{}中平均{:.1}分，年级排名{}/{}。",
+                record.exam, record.average, record.rank, record.cohort_size
+            ));
+        }
+
+        let clubs: Vec<&str> = self.clubs_of(agent_id).iter().map(|c| c.name.as_str()).collect();
+        if !clubs.is_empty() {
+            desc.push_str(&format!("你参加了: {}。", clubs.join("、")));
+        }
+        if let Some(club) = self.club_meeting_today(agent_id) {
+            desc.push_str(&format!("今天放学后{}在{}活动。", club.name, self.location_name(&club.location)));
         }
 
         desc
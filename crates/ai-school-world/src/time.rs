@@ -1,113 +1,419 @@
 //! M2.3 环境与时间系统
 //!
-//! 仿真时钟推进、学期/周/天/时段层次时间。
+//! 校历（作息铃声、节假日、寒暑假）→ 仿真时钟按小时推进 → 学期/周/天/时段层次时间，
+//! 并把每个时刻换算为真实日期。
 
-use ai_school_core::types::SimulationTime;
+use std::collections::HashSet;
 
-/// 仿真时钟
-pub struct SimulationClock {
-    current: SimulationTime,
-    /// 每步推进的小时数
-    step_hours: u32,
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use ai_school_core::types::{DayType, SimulationTime};
+
+use crate::exams::{ExamKind, ExamPeriod};
+
+/// 作息铃声：每个上课日在固定整点触发
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bell {
+    pub hour: u32,
+    pub event: TimeEvent,
 }
 
-impl SimulationClock {
-    pub fn new(step_hours: u32) -> Self {
+/// 节假日（首尾两天都放假）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Holiday {
+    pub name: String,
+    pub start: NaiveDate,
+    /// 缺省时只放一天
+    #[serde(default)]
+    pub end: Option<NaiveDate>,
+}
+
+impl Holiday {
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        date >= self.start && date <= self.end.unwrap_or(self.start)
+    }
+}
+
+/// 考试周：每学期的这一周在 `exam_day` 举行考试，其余上课日用于复习
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExamWeek {
+    pub name: String,
+    pub kind: ExamKind,
+    /// 学期内第几周
+    pub week: u32,
+    /// 考试在星期几 (1-5)
+    pub exam_day: u32,
+}
+
+/// 学期周数必须为正，否则无法换算日期
+fn positive_weeks<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    let weeks = u32::deserialize(deserializer)?;
+    if weeks == 0 {
+        return Err(serde::de::Error::custom("weeks_per_semester must be positive"));
+    }
+    Ok(weeks)
+}
+
+/// 校历
+///
+/// 一学年 = 第一学期 + 寒假 + 第二学期 + 暑假，之后循环。
+/// 假期的周继续计入前一学期（第 21 周起即为假期）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SchoolCalendar {
+    /// 第一学期第一周的周一
+    pub start_date: NaiveDate,
+    /// 仿真开始的整点
+    pub start_hour: u32,
+    #[serde(deserialize_with = "positive_weeks")]
+    pub weeks_per_semester: u32,
+    /// 第一学期结束后的寒假周数
+    pub winter_break_weeks: u32,
+    /// 第二学期结束后的暑假周数
+    pub summer_break_weeks: u32,
+    pub bell_schedule: Vec<Bell>,
+    pub holidays: Vec<Holiday>,
+    pub exam_weeks: Vec<ExamWeek>,
+}
+
+impl Default for SchoolCalendar {
+    fn default() -> Self {
+        let bell = |hour, event| Bell { hour, event };
         Self {
-            current: SimulationTime::new(),
-            step_hours,
+            start_date: NaiveDate::from_ymd_opt(2025, 9, 1).unwrap(),
+            start_hour: 7,
+            weeks_per_semester: 20,
+            winter_break_weeks: 5,
+            summer_break_weeks: 7,
+            bell_schedule: vec![
+                bell(8, TimeEvent::ClassStart { period: 1 }),
+                bell(9, TimeEvent::ClassStart { period: 2 }),
+                bell(10, TimeEvent::Break),
+                bell(11, TimeEvent::ClassStart { period: 3 }),
+                bell(12, TimeEvent::LunchBreak),
+                bell(14, TimeEvent::ClassStart { period: 4 }),
+                bell(15, TimeEvent::ClassStart { period: 5 }),
+                bell(16, TimeEvent::FreeTime),
+                bell(18, TimeEvent::Dinner),
+                bell(19, TimeEvent::EveningStudy),
+                bell(22, TimeEvent::Bedtime),
+            ],
+            holidays: Vec::new(),
+            exam_weeks: Vec::new(),
         }
     }
+}
 
-    pub fn current_time(&self) -> &SimulationTime {
-        &self.current
+impl SchoolCalendar {
+    /// 一学年的周数
+    fn weeks_per_year(&self) -> u32 {
+        2 * self.weeks_per_semester + self.winter_break_weeks + self.summer_break_weeks
     }
 
-    /// 推进一步，返回触发的时间事件
-    pub fn advance(&mut self) -> Vec<TimeEvent> {
-        let mut events = Vec::new();
-        let old_hour = self.current.hour;
-        let _old_day = self.current.day_of_week;
+    /// 某个日期所在的 (学期, 学期内第几周)
+    fn locate(&self, date: NaiveDate) -> (u32, u32) {
+        let week_index = ((date - self.start_date).num_days().max(0) / 7) as u32;
+        let year = week_index / self.weeks_per_year();
+        let offset = week_index % self.weeks_per_year();
+        let first = self.weeks_per_semester + self.winter_break_weeks;
+        if offset < first {
+            (2 * year + 1, offset + 1)
+        } else {
+            (2 * year + 2, offset - first + 1)
+        }
+    }
 
-        self.current.tick += 1;
-        self.current.hour += self.step_hours;
+    /// 某学期第几周星期几对应的日期
+    pub fn date_of(&self, semester: u32, week: u32, day_of_week: u32) -> NaiveDate {
+        let year = (semester.max(1) - 1) / 2;
+        let offset = if semester % 2 == 1 {
+            0
+        } else {
+            self.weeks_per_semester + self.winter_break_weeks
+        };
+        let weeks = year * self.weeks_per_year() + offset + week.max(1) - 1;
+        self.start_date + Duration::days(weeks as i64 * 7 + day_of_week.max(1) as i64 - 1)
+    }
 
-        // 处理日期进位
-        if self.current.hour >= 24 {
-            self.current.hour -= 24;
-            self.current.day_of_week += 1;
-            events.push(TimeEvent::NewDay);
+    /// 某天放的节假日
+    pub fn holiday_on(&self, date: NaiveDate) -> Option<&Holiday> {
+        self.holidays.iter().find(|h| h.contains(date))
+    }
 
-            if self.current.day_of_week > 7 {
-                self.current.day_of_week = 1;
-                self.current.week += 1;
-                events.push(TimeEvent::NewWeek);
+    /// 某学期第几周是否为考试周
+    pub fn exam_week(&self, week: u32) -> Option<&ExamWeek> {
+        self.exam_weeks.iter().find(|e| e.week == week)
+    }
 
-                if self.current.week > 20 {
-                    self.current.week = 1;
-                    self.current.semester += 1;
+    /// 由考试周得出的考试安排
+    pub fn exam_periods(&self) -> Vec<ExamPeriod> {
+        self.exam_weeks
+            .iter()
+            .map(|e| ExamPeriod {
+                name: e.name.clone(),
+                kind: e.kind,
+                week: e.week,
+                day_of_week: e.exam_day,
+            })
+            .collect()
+    }
+
+    pub fn day_type(&self, date: NaiveDate) -> DayType {
+        let (_, week) = self.locate(date);
+        let day_of_week = date.weekday().number_from_monday();
+        if week > self.weeks_per_semester {
+            DayType::Vacation
+        } else if self.holiday_on(date).is_some() {
+            DayType::Holiday
+        } else if day_of_week > 5 {
+            DayType::Weekend
+        } else if self.exam_week(week).is_some_and(|e| e.exam_day == day_of_week) {
+            DayType::ExamDay
+        } else {
+            DayType::SchoolDay
+        }
+    }
+
+    /// 从开学第一天零点起经过 `hours` 小时的仿真时间
+    pub fn time_at(&self, hours: u64, tick: u64) -> SimulationTime {
+        let datetime = self.start_date.and_hms_opt(0, 0, 0).unwrap() + Duration::hours(hours as i64);
+        let date = datetime.date();
+        let (semester, week) = self.locate(date);
+        SimulationTime {
+            semester,
+            week,
+            day_of_week: date.weekday().number_from_monday(),
+            hour: (hours % 24) as u32,
+            tick,
+            day_type: self.day_type(date),
+            datetime: Some(datetime),
+            elapsed_hours: hours,
+        }
+    }
+
+    /// 某个整点触发的时间事件
+    pub fn events_at(&self, time: &SimulationTime) -> Vec<TimeEvent> {
+        let mut events = Vec::new();
+        if time.hour == 0 {
+            events.push(TimeEvent::NewDay);
+            if time.day_of_week == 1 {
+                events.push(TimeEvent::NewWeek);
+                if time.week == 1 {
                     events.push(TimeEvent::NewSemester);
                 }
             }
+            if !time.is_school_day() {
+                events.push(TimeEvent::DayOff);
+            }
+        }
+        if time.is_school_day() {
+            events.extend(
+                self.bell_schedule
+                    .iter()
+                    .filter(|b| b.hour == time.hour)
+                    .map(|b| b.event.clone()),
+            );
+        }
+        events
+    }
+
+    /// 某个整点所在的最近一次铃声
+    fn bell_before(&self, hour: u32) -> Option<&Bell> {
+        self.bell_schedule.iter().rev().find(|b| b.hour <= hour)
+    }
+
+    /// 某个整点正在上第几节课
+    pub fn period_at(&self, hour: u32) -> Option<u32> {
+        match self.bell_before(hour)?.event {
+            TimeEvent::ClassStart { period } => Some(period),
+            _ => None,
+        }
+    }
+
+    /// 作息表中的所有节次
+    pub fn periods(&self) -> Vec<u32> {
+        self.bell_schedule
+            .iter()
+            .filter_map(|b| match b.event {
+                TimeEvent::ClassStart { period } => Some(period),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 时间段描述
+    pub fn period_description(&self, time: &SimulationTime) -> String {
+        match time.day_type {
+            DayType::SchoolDay | DayType::ExamDay => {}
+            DayType::Weekend => return "周末自由时间".to_string(),
+            DayType::Holiday => {
+                let name = time
+                    .datetime
+                    .and_then(|d| self.holiday_on(d.date()))
+                    .map_or("节假日", |h| h.name.as_str());
+                return format!("{name}放假");
+            }
+            DayType::Vacation => {
+                return if time.semester % 2 == 1 { "寒假" } else { "暑假" }.to_string();
+            }
+        }
+
+        let first_bell = self.bell_schedule.first().map_or(8, |b| b.hour);
+        let description = match self.bell_before(time.hour).map(|b| &b.event) {
+            None if time.hour + 1 >= first_bell => "起床 & 早餐",
+            None => "睡觉时间",
+            Some(TimeEvent::ClassStart { .. }) if time.hour < 12 => "上午课程",
+            Some(TimeEvent::ClassStart { .. }) => "下午课程",
+            Some(TimeEvent::Break) => "课间休息",
+            Some(TimeEvent::LunchBreak) => "午餐 & 午休",
+            Some(TimeEvent::FreeTime) => "课外活动/自由时间",
+            Some(TimeEvent::Dinner) => "晚餐",
+            Some(TimeEvent::EveningStudy) => "晚自习",
+            Some(TimeEvent::Bedtime) => "就寝",
+            Some(_) => "未知时段",
+        };
+        let Some(exam) = self.exam_week(time.week) else {
+            return description.to_string();
+        };
+        match self.period_at(time.hour) {
+            Some(_) if time.day_type == DayType::ExamDay => exam.name.clone(),
+            _ => format!("{}周 · {description}", exam.name),
+        }
+    }
+
+    /// 校验校历，问题追加到 `problems`
+    pub fn validate(&self, problems: &mut Vec<String>) {
+        if self.start_date.weekday() != Weekday::Mon {
+            problems.push(format!("calendar start_date {} is not a Monday", self.start_date));
+        }
+        if self.start_hour >= 24 {
+            problems.push(format!("calendar start_hour {} is out of range", self.start_hour));
+        }
+        if self.weeks_per_semester == 0 {
+            problems.push("calendar has no teaching weeks".to_string());
+        }
 
-            if self.current.day_of_week > 5 {
-                events.push(TimeEvent::Weekend);
+        let mut exam_weeks = HashSet::new();
+        for exam in &self.exam_weeks {
+            if !(1..=self.weeks_per_semester).contains(&exam.week) || !(1..=5).contains(&exam.exam_day) {
+                problems.push(format!(
+                    "exam {} has invalid date week {} day {}",
+                    exam.name, exam.week, exam.exam_day
+                ));
+            }
+            for semester in [1, 2] {
+                let date = self.date_of(semester, exam.week, exam.exam_day);
+                if let Some(holiday) = self.holiday_on(date) {
+                    problems.push(format!("exam {} falls on holiday {} ({date})", exam.name, holiday.name));
+                }
+            }
+            if !exam_weeks.insert(exam.week) {
+                problems.push(format!("exam {} shares week {} with another exam", exam.name, exam.week));
             }
         }
 
-        // 课程表时间事件
-        if self.current.day_of_week <= 5 {
-            for h in old_hour..self.current.hour + self.step_hours {
-                match h {
-                    8 => events.push(TimeEvent::ClassStart { period: 1 }),
-                    9 => events.push(TimeEvent::ClassStart { period: 2 }),
-                    10 => events.push(TimeEvent::Break),
-                    11 => events.push(TimeEvent::ClassStart { period: 3 }),
-                    12 => events.push(TimeEvent::LunchBreak),
-                    14 => events.push(TimeEvent::ClassStart { period: 4 }),
-                    15 => events.push(TimeEvent::ClassStart { period: 5 }),
-                    16 => events.push(TimeEvent::FreeTime),
-                    18 => events.push(TimeEvent::Dinner),
-                    19 => events.push(TimeEvent::EveningStudy),
-                    22 => events.push(TimeEvent::Bedtime),
-                    _ => {}
+        let mut last_hour = None;
+        let mut periods = HashSet::new();
+        for bell in &self.bell_schedule {
+            if bell.hour >= 24 || last_hour.is_some_and(|h| bell.hour <= h) {
+                problems.push(format!("bell at hour {} is out of order", bell.hour));
+            }
+            last_hour = Some(bell.hour);
+            match bell.event {
+                TimeEvent::ClassStart { period } if !periods.insert(period) => {
+                    problems.push(format!("period {period} rings more than once"));
                 }
+                TimeEvent::NewDay | TimeEvent::NewWeek | TimeEvent::NewSemester | TimeEvent::DayOff => {
+                    problems.push(format!("bell at hour {} cannot ring {:?}", bell.hour, bell.event));
+                }
+                _ => {}
             }
         }
 
+        for holiday in &self.holidays {
+            if holiday.end.is_some_and(|end| end < holiday.start) {
+                problems.push(format!("holiday {} ends before it starts", holiday.name));
+            }
+        }
+    }
+}
+
+/// 仿真时钟
+pub struct SimulationClock {
+    calendar: SchoolCalendar,
+    current: SimulationTime,
+    /// 从开学第一天零点起经过的小时数
+    elapsed_hours: u64,
+    /// 每步推进的小时数
+    step_hours: u32,
+}
+
+impl SimulationClock {
+    pub fn new(step_hours: u32) -> Self {
+        Self::with_calendar(SchoolCalendar::default(), step_hours)
+    }
+
+    pub fn with_calendar(calendar: SchoolCalendar, step_hours: u32) -> Self {
+        let elapsed_hours = calendar.start_hour as u64;
+        Self {
+            current: calendar.time_at(elapsed_hours, 0),
+            calendar,
+            elapsed_hours,
+            step_hours,
+        }
+    }
+
+    pub fn current_time(&self) -> &SimulationTime {
+        &self.current
+    }
+
+    pub fn calendar(&self) -> &SchoolCalendar {
+        &self.calendar
+    }
+
+    /// 每步推进的小时数
+    pub fn step_hours(&self) -> u32 {
+        self.step_hours
+    }
+
+    /// 推进一步，返回触发的时间事件
+    ///
+    /// 逐小时走过 (上一时刻, 当前时刻]，每个整点的事件恰好触发一次，
+    /// 与步长和是否跨越午夜无关。
+    pub fn advance(&mut self) -> Vec<TimeEvent> {
+        let tick = self.current.tick + 1;
+        let mut events = Vec::new();
+        for _ in 0..self.step_hours {
+            self.elapsed_hours += 1;
+            let time = self.calendar.time_at(self.elapsed_hours, tick);
+            events.extend(self.calendar.events_at(&time));
+        }
+        self.current = self.calendar.time_at(self.elapsed_hours, tick);
         events
     }
 
     /// 获取当前时间段描述
-    pub fn current_period_description(&self) -> &str {
-        if self.current.day_of_week > 5 {
-            return "周末自由时间";
-        }
+    pub fn current_period_description(&self) -> String {
+        self.calendar.period_description(&self.current)
+    }
 
-        match self.current.hour {
-            0..=6 => "睡觉时间",
-            7 => "起床 & 早餐",
-            8..=9 => "上午课程",
-            10 => "课间休息",
-            11 => "上午课程",
-            12..=13 => "午餐 & 午休",
-            14..=15 => "下午课程",
-            16..=17 => "课外活动/自由时间",
-            18 => "晚餐",
-            19..=21 => "晚自习",
-            22..=23 => "就寝",
-            _ => "未知时段",
+    /// 当前正在上第几节课（非上课日或不在课上时为 None）
+    pub fn current_period(&self) -> Option<u32> {
+        if !self.current.is_school_day() {
+            return None;
         }
+        self.calendar.period_at(self.current.hour)
     }
 
     /// 重置时钟
     pub fn reset(&mut self) {
-        self.current = SimulationTime::new();
+        *self = Self::with_calendar(self.calendar.clone(), self.step_hours);
     }
 }
 
 /// 时间事件
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TimeEvent {
     ClassStart { period: u32 },
     Break,
@@ -119,7 +425,8 @@ pub enum TimeEvent {
     NewDay,
     NewWeek,
     NewSemester,
-    Weekend,
+    /// 不上课的一天（周末、节假日、寒暑假）开始
+    DayOff,
 }
 
 impl TimeEvent {
@@ -142,7 +449,7 @@ impl TimeEvent {
             TimeEvent::NewDay => "新的一天",
             TimeEvent::NewWeek => "新的一周",
             TimeEvent::NewSemester => "新学期",
-            TimeEvent::Weekend => "周末",
+            TimeEvent::DayOff => "休息日",
         }
     }
 }
@@ -151,25 +458,120 @@ impl TimeEvent {
 mod tests {
     use super::*;
 
+    fn count(events: &[TimeEvent], event: &TimeEvent) -> usize {
+        events.iter().filter(|e| *e == event).count()
+    }
+
     #[test]
     fn test_clock_advance() {
         let mut clock = SimulationClock::new(1);
+        assert_eq!(clock.current_time().hour, 7);
+        assert_eq!(
+            clock.current_time().datetime.unwrap().to_string(),
+            "2025-09-01 07:00:00"
+        );
+
+        let events = clock.advance();
         assert_eq!(clock.current_time().hour, 8);
+        assert_eq!(clock.current_time().total_hours(), 8);
+        assert_eq!(events, vec![TimeEvent::ClassStart { period: 1 }]);
+        assert_eq!(clock.current_period(), Some(1));
 
+        // 上一时刻的事件不会重复触发
         let events = clock.advance();
-        assert_eq!(clock.current_time().hour, 9);
-        // Should trigger ClassStart for period 2
-        assert!(events.iter().any(|e| matches!(e, TimeEvent::ClassStart { period: 2 })));
+        assert_eq!(events, vec![TimeEvent::ClassStart { period: 2 }]);
     }
 
     #[test]
-    fn test_day_rollover() {
-        let mut clock = SimulationClock::new(1);
-        // Advance to end of day
-        for _ in 0..16 {
-            clock.advance();
+    fn test_events_independent_of_step_size() {
+        let mut hourly = SimulationClock::new(1);
+        let mut coarse = SimulationClock::new(5);
+        let (mut a, mut b) = (Vec::new(), Vec::new());
+        for _ in 0..15 * 24 {
+            a.extend(hourly.advance());
         }
-        // hour should have wrapped around
-        assert!(clock.current_time().hour < 24);
+        for _ in 0..3 * 24 {
+            b.extend(coarse.advance());
+        }
+        assert_eq!(a, b);
+        assert_eq!(hourly.current_time().datetime, coarse.current_time().datetime);
+
+        // 15 天里有两个周末，跨越午夜的事件在大步长下也不会丢
+        assert_eq!(count(&a, &TimeEvent::NewDay), 15);
+        assert_eq!(count(&a, &TimeEvent::NewWeek), 2);
+        assert_eq!(count(&a, &TimeEvent::DayOff), 4);
+        assert_eq!(count(&a, &TimeEvent::ClassStart { period: 1 }), 11);
+    }
+
+    #[test]
+    fn test_holidays_and_breaks() {
+        let mut calendar = SchoolCalendar::default();
+        calendar.holidays.push(Holiday {
+            name: "国庆节".to_string(),
+            start: NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(),
+            end: NaiveDate::from_ymd_opt(2025, 10, 7),
+        });
+
+        let national_day = calendar.date_of(1, 5, 3);
+        assert_eq!(national_day.to_string(), "2025-10-01");
+        assert_eq!(calendar.day_type(national_day), DayType::Holiday);
+
+        // 第一学期 20 周后进入 5 周寒假，然后第二学期从第 1 周开始
+        let winter = calendar.date_of(1, 21, 1);
+        assert_eq!(calendar.day_type(winter), DayType::Vacation);
+        let spring = calendar.date_of(2, 1, 1);
+        assert_eq!(spring.to_string(), "2026-02-23");
+        assert_eq!(calendar.locate(spring), (2, 1));
+        assert_eq!(calendar.locate(calendar.date_of(3, 1, 1)), (3, 1));
+
+        let hours = (spring - calendar.start_date).num_hours() as u64 + 8;
+        let time = calendar.time_at(hours, 0);
+        assert_eq!((time.semester, time.week, time.day_of_week), (2, 1, 1));
+        assert_eq!(calendar.events_at(&time), vec![TimeEvent::ClassStart { period: 1 }]);
+        let midnight = calendar.time_at(hours - 8, 0);
+        assert!(calendar.events_at(&midnight).contains(&TimeEvent::NewSemester));
+
+        let break_day = calendar.time_at(hours - 24 * 7 + 8, 0);
+        assert_eq!(calendar.period_description(&break_day), "寒假");
+        assert!(calendar.events_at(&break_day).is_empty());
+        // 时间差按开学以来的小时数计算，跨学期也连续
+        assert_eq!(time.total_hours() - break_day.total_hours(), 24 * 7 - 8);
+    }
+
+    #[test]
+    fn test_exam_weeks() {
+        let mut calendar = SchoolCalendar::default();
+        calendar.exam_weeks.push(ExamWeek {
+            name: "期中考试".to_string(),
+            kind: ExamKind::Midterm,
+            week: 10,
+            exam_day: 3,
+        });
+
+        let exams = calendar.exam_periods();
+        assert_eq!((exams[0].week, exams[0].day_of_week), (10, 3));
+
+        // 考试当天仍按作息上课铃，其余上课日为复习
+        let hours = |week, day, hour| {
+            (calendar.date_of(1, week, day) - calendar.start_date).num_hours() as u64 + hour
+        };
+        let exam_day = calendar.time_at(hours(10, 3, 9), 0);
+        assert_eq!(exam_day.day_type, DayType::ExamDay);
+        assert!(exam_day.is_school_day());
+        assert_eq!(calendar.events_at(&exam_day), vec![TimeEvent::ClassStart { period: 2 }]);
+        assert_eq!(calendar.period_description(&exam_day), "期中考试");
+
+        let review = calendar.time_at(hours(10, 2, 9), 0);
+        assert_eq!(review.day_type, DayType::SchoolDay);
+        assert_eq!(calendar.period_description(&review), "期中考试周 · 上午课程");
+        let ordinary = calendar.time_at(hours(9, 3, 9), 0);
+        assert_eq!(calendar.period_description(&ordinary), "上午课程");
+    }
+
+    #[test]
+    fn test_rejects_calendar_without_weeks() {
+        let err = serde_json::from_str::<SchoolCalendar>(r#"{"weeks_per_semester": 0}"#).unwrap_err();
+        assert!(err.to_string().contains("weeks_per_semester must be positive"));
+        assert!(serde_json::from_str::<SchoolCalendar>(r#"{"weeks_per_semester": 18}"#).is_ok());
     }
 }
@@ -38,6 +38,9 @@ export const api = {
   triggerEvent: (event: PresetEvent) => request<{ success: boolean }>(
     '/api/interventions/event', { method: 'POST', body: JSON.stringify({ event }) }
   ),
+  adjustParams: (parameter: string, value: number) => request<{ success: boolean; message: string }>(
+    '/api/simulation/params', { method: 'PUT', body: JSON.stringify({ parameter, value }) }
+  ),
 
   // Chat
   chat: (agentId: string, role: string, message: string) => request<{ reply: string; impact: string }>(
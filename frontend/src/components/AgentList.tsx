@@ -20,6 +20,7 @@ const ACTIVITY_LABELS: Record<string, { label: string; color: string }> = {
   Resting: { label: '休息中', color: '#94a3b8' },
   Reflecting: { label: '反思中', color: '#8b5cf6' },
   Activity: { label: '活动中', color: '#ec4899' },
+  Teaching: { label: '授课中', color: '#0ea5e9' },
   Moving: { label: '移动中', color: '#fbbf24' },
   Troubled: { label: '困扰中', color: '#fb7185' },
 };
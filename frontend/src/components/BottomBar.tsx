@@ -9,8 +9,8 @@ const PRESET_EVENTS = [
   { label: '运动会', value: 'SportsMeet' },
 ];
 
-function ParamSlider({ label, value, onChange }: {
-  label: string; value: number; onChange: (v: number) => void;
+function ParamSlider({ label, value, onChange, onCommit }: {
+  label: string; value: number; onChange: (v: number) => void; onCommit: () => void;
 }) {
   return (
     <div className="flex items-center gap-2">
@@ -21,6 +21,8 @@ function ParamSlider({ label, value, onChange }: {
         max={100}
         value={value}
         onChange={(e) => onChange(Number(e.target.value))}
+        onPointerUp={onCommit}
+        onKeyUp={onCommit}
         className="flex-1 h-1 appearance-none bg-surface-overlay rounded-full cursor-pointer
           [&::-webkit-slider-thumb]:appearance-none [&::-webkit-slider-thumb]:w-2.5 [&::-webkit-slider-thumb]:h-2.5
           [&::-webkit-slider-thumb]:rounded-full [&::-webkit-slider-thumb]:bg-accent-cyan [&::-webkit-slider-thumb]:cursor-pointer"
@@ -37,9 +39,17 @@ export function BottomBar() {
     difficulty: 50,
     socialDensity: 50,
     competitivePressure: 50,
-    randomEvents: 30,
+    randomEvents: 10,
   });
 
+  const handleAdjustParam = async (parameter: string, value: number) => {
+    try {
+      await api.adjustParams(parameter, value / 100);
+    } catch (e) {
+      console.error('Failed to adjust parameter:', e);
+    }
+  };
+
   const handleTriggerEvent = async (eventType: string) => {
     try {
       await api.triggerEvent(eventType as never);
@@ -116,10 +126,10 @@ export function BottomBar() {
           {/* Parameters */}
           <div className="flex-1 space-y-2">
             <h4 className="text-[10px] font-semibold text-text-secondary tracking-wider uppercase">Parameters</h4>
-            <ParamSlider label="Difficulty" value={params.difficulty} onChange={(v) => setParams(p => ({ ...p, difficulty: v }))} />
-            <ParamSlider label="Social" value={params.socialDensity} onChange={(v) => setParams(p => ({ ...p, socialDensity: v }))} />
-            <ParamSlider label="Competition" value={params.competitivePressure} onChange={(v) => setParams(p => ({ ...p, competitivePressure: v }))} />
-            <ParamSlider label="Random" value={params.randomEvents} onChange={(v) => setParams(p => ({ ...p, randomEvents: v }))} />
+            <ParamSlider label="Difficulty" value={params.difficulty} onChange={(v) => setParams(p => ({ ...p, difficulty: v }))} onCommit={() => handleAdjustParam('CourseDifficulty', params.difficulty)} />
+            <ParamSlider label="Social" value={params.socialDensity} onChange={(v) => setParams(p => ({ ...p, socialDensity: v }))} onCommit={() => handleAdjustParam('SocialDensity', params.socialDensity)} />
+            <ParamSlider label="Competition" value={params.competitivePressure} onChange={(v) => setParams(p => ({ ...p, competitivePressure: v }))} onCommit={() => handleAdjustParam('CompetitivePressure', params.competitivePressure)} />
+            <ParamSlider label="Random" value={params.randomEvents} onChange={(v) => setParams(p => ({ ...p, randomEvents: v }))} onCommit={() => handleAdjustParam('RandomEventFrequency', params.randomEvents)} />
           </div>
 
           {/* Events */}
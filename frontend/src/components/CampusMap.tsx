@@ -1,7 +1,7 @@
 import { Stage, Layer, Rect, Circle, Text, Group, Line } from 'react-konva';
 import { useEffect, useRef, useState, useCallback } from 'react';
 import { useSimulationStore } from '../stores/simulation';
-import type { Agent } from '../types';
+import type { Agent, AgentMovement, WorldSnapshot } from '../types';
 
 // Campus locations with coordinates from backend (scaled to canvas)
 const LOCATIONS = [
@@ -17,6 +17,7 @@ const LOCATIONS = [
   { id: 'club_room', name: '社团活动室', type: 'ClubRoom', x: 500, y: 280, w: 100, h: 65, color: '#ec4899' },
   { id: 'auditorium', name: '礼堂', type: 'Auditorium', x: 640, y: 340, w: 120, h: 80, color: '#f59e0b' },
   { id: 'rest_area', name: '休息区', type: 'RestArea', x: 350, y: 275, w: 90, h: 60, color: '#94a3b8' },
+  { id: 'staff_office', name: '教师办公室', type: 'Office', x: 460, y: 185, w: 100, h: 55, color: '#0ea5e9' },
   { id: 'hallway', name: '走廊', type: 'RestArea', x: 300, y: 190, w: 140, h: 50, color: '#2a2b3a' },
 ];
 
@@ -56,6 +57,39 @@ function getAgentPosition(
   };
 }
 
+// Duration of the walk animation after each tick
+const MOVE_ANIMATION_MS = 800;
+
+function locationCenter(locationId: string): { x: number; y: number } {
+  const loc = LOCATIONS.find(l => l.id === locationId);
+  if (!loc) return { x: 400, y: 300 };
+  return { x: loc.x + loc.w / 2, y: loc.y + loc.h / 2 };
+}
+
+// Point at fraction t (0..1) of the total length of a polyline
+function pointAlong(points: { x: number; y: number }[], t: number): { x: number; y: number } {
+  if (points.length === 1) return points[0];
+  const lengths = points.slice(1).map((p, i) => Math.hypot(p.x - points[i].x, p.y - points[i].y));
+  const total = lengths.reduce((a, b) => a + b, 0);
+  let remaining = total * t;
+  for (let i = 0; i < lengths.length; i++) {
+    if (remaining <= lengths[i] || i === lengths.length - 1) {
+      const f = lengths[i] === 0 ? 1 : Math.min(remaining / lengths[i], 1);
+      return {
+        x: points[i].x + (points[i + 1].x - points[i].x) * f,
+        y: points[i].y + (points[i + 1].y - points[i].y) * f,
+      };
+    }
+    remaining -= lengths[i];
+  }
+  return points[points.length - 1];
+}
+
+// Waypoints of a movement: centers of the locations passed, ending at the agent's slot
+function movementPoints(movement: AgentMovement, end: { x: number; y: number }) {
+  return [...movement.path.slice(0, -1).map(locationCenter), end];
+}
+
 interface AgentNodeProps {
   agent: Agent;
   x: number;
@@ -155,7 +189,13 @@ function AgentNode({ agent, x, y, isSelected, onSelect }: AgentNodeProps) {
 export function CampusMap() {
   const containerRef = useRef<HTMLDivElement>(null);
   const [dimensions, setDimensions] = useState({ width: 800, height: 600 });
-  const { agents, selectedAgentId, selectAgent } = useSimulationStore();
+  const { agents, selectedAgentId, selectAgent, snapshot } = useSimulationStore();
+  const [moveAnimation, setMoveAnimation] = useState<{ snapshot: WorldSnapshot | null; t: number }>({
+    snapshot: null,
+    t: 1,
+  });
+  // A new snapshot starts its walk from the beginning until the first frame runs
+  const moveProgress = moveAnimation.snapshot === snapshot ? moveAnimation.t : 0;
 
   const handleResize = useCallback(() => {
     if (containerRef.current) {
@@ -172,6 +212,20 @@ export function CampusMap() {
     return () => window.removeEventListener('resize', handleResize);
   }, [handleResize]);
 
+  // Animate this tick's movements along their paths
+  useEffect(() => {
+    if (!snapshot?.movements?.length) return;
+    const start = performance.now();
+    let frame = requestAnimationFrame(function animate(now) {
+      const t = Math.min((now - start) / MOVE_ANIMATION_MS, 1);
+      setMoveAnimation({ snapshot, t });
+      if (t < 1) frame = requestAnimationFrame(animate);
+    });
+    return () => cancelAnimationFrame(frame);
+  }, [snapshot]);
+
+  const movementByAgent = new Map((snapshot?.movements ?? []).map(m => [m.agent_id, m]));
+
   // Group agents by location
   const agentsByLocation: Record<string, Agent[]> = {};
   for (const agent of agents) {
@@ -275,10 +329,32 @@ export function CampusMap() {
             />
           ))}
 
+          {/* Movement paths */}
+          {moveProgress < 1 && Object.entries(agentsByLocation).flatMap(([locId, locAgents]) =>
+            locAgents.map((agent, idx) => {
+              const movement = movementByAgent.get(agent.id);
+              if (!movement || movement.path.length < 2) return null;
+              const points = movementPoints(movement, getAgentPosition(locId, idx, locAgents.length));
+              return (
+                <Line
+                  key={`path-${agent.id}`}
+                  points={points.flatMap(p => [p.x, p.y])}
+                  stroke={`${getAgentColor(agent.mbti)}40`}
+                  strokeWidth={1}
+                  dash={[2, 4]}
+                />
+              );
+            })
+          )}
+
           {/* Agent nodes */}
           {Object.entries(agentsByLocation).flatMap(([locId, locAgents]) =>
             locAgents.map((agent, idx) => {
-              const pos = getAgentPosition(locId, idx, locAgents.length);
+              const slot = getAgentPosition(locId, idx, locAgents.length);
+              const movement = movementByAgent.get(agent.id);
+              const pos = movement && movement.path.length > 1 && moveProgress < 1
+                ? pointAlong(movementPoints(movement, slot), moveProgress)
+                : slot;
               return (
                 <AgentNode
                   key={agent.id}
@@ -9,11 +9,12 @@ const SPEEDS: { label: string; value: SimulationSpeed }[] = [
   { label: '10x', value: 'Maximum' },
 ];
 
-function formatTime(time: { semester: number; week: number; day_of_week: number; hour: number } | null): string {
+function formatTime(time: { semester: number; week: number; day_of_week: number; hour: number; datetime?: string | null } | null): string {
   if (!time) return '--';
   const days = ['Mon', 'Tue', 'Wed', 'Thu', 'Fri', 'Sat', 'Sun'];
-  const day = days[time.day_of_week % 7] || '?';
-  return `S${time.semester} W${time.week} ${day} ${time.hour}:00`;
+  const day = days[time.day_of_week - 1] || '?';
+  const date = time.datetime ? ` (${time.datetime.slice(0, 10)})` : '';
+  return `S${time.semester} W${time.week} ${day} ${time.hour}:00${date}`;
 }
 
 export function TopBar() {
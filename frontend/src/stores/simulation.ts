@@ -41,6 +41,7 @@ interface SimulationStore {
   snapshot: WorldSnapshot | null;
   events: SimulationEvent[];
   eventLog: SimulationEvent[];
+  warnings: string[];
 
   // UI
   selectedAgentId: string | null;
@@ -76,6 +77,7 @@ export const useSimulationStore = create<SimulationStore>((set, get) => ({
   snapshot: null,
   events: [],
   eventLog: [],
+  warnings: [],
   selectedAgentId: null,
   selectedAgentDetail: null,
   rightPanel: 'detail',
@@ -127,9 +129,16 @@ export const useSimulationStore = create<SimulationStore>((set, get) => ({
               agents,
               events: update.events,
               eventLog: [...state.eventLog, ...update.events].slice(-100),
+              warnings: [...state.warnings, ...(update.warnings ?? [])].slice(-50),
             });
             break;
           }
+          case 'Intervention':
+            set({
+              eventLog: [...state.eventLog, ...update.events].slice(-100),
+              warnings: [...state.warnings, ...update.warnings].slice(-50),
+            });
+            break;
           case 'SpeedChanged':
             set({ speed: update.speed });
             break;
@@ -10,6 +10,11 @@ export interface SimulationTime {
   day_of_week: number;
   hour: number;
   tick: number;
+  day_type?: 'SchoolDay' | 'ExamDay' | 'Weekend' | 'Holiday' | 'Vacation';
+  /** ISO 8601 date-time derived from the school calendar */
+  datetime?: string | null;
+  /** Hours since the first day of the calendar */
+  elapsed_hours?: number;
 }
 
 // Personality
@@ -44,12 +49,14 @@ export type AgentActivity =
   | 'Resting'
   | 'Reflecting'
   | 'Activity'
+  | 'Teaching'
   | 'Moving'
   | 'Troubled';
 
 export interface Agent {
   id: AgentId;
   name: string;
+  role?: string;
   mbti: string;
   location: LocationId;
   activity: AgentActivity;
@@ -105,6 +112,7 @@ export interface SimulationEvent {
   involved_agents: AgentId[];
   narrative: string;
   intensity: number;
+  causes?: EventId[];
 }
 
 // Simulation Speed
@@ -124,6 +132,14 @@ export interface Relationship {
   trust: number;
 }
 
+// Movement along the campus graph during one tick
+export interface AgentMovement {
+  agent_id: AgentId;
+  path: LocationId[];
+  travel_minutes: number;
+  arrived: boolean;
+}
+
 // World Snapshot
 export interface WorldSnapshot {
   time: SimulationTime;
@@ -138,11 +154,13 @@ export interface WorldSnapshot {
   }>;
   relationships: Relationship[];
   active_events: string[];
+  movements: AgentMovement[];
 }
 
 // WebSocket updates
 export type SimulationUpdate =
-  | { type: 'Tick'; time: SimulationTime; snapshot: WorldSnapshot; events: SimulationEvent[] }
+  | { type: 'Tick'; time: SimulationTime; snapshot: WorldSnapshot; events: SimulationEvent[]; warnings?: string[] }
+  | { type: 'Intervention'; time: SimulationTime; events: SimulationEvent[]; warnings: string[] }
   | { type: 'SpeedChanged'; speed: SimulationSpeed }
   | { type: 'Started' }
   | { type: 'Stopped' };